@@ -7,7 +7,7 @@ fn main() {
     env_logger::init();
 
     smol::run(async {
-        let (mut obs, mut event_receiver) = Obs::connect("localhost", 4444).await.unwrap();
+        let (obs, mut event_receiver) = Obs::connect("localhost", 4444).await.unwrap();
         obs.authenticate("1234").await.unwrap();
 
         // this Task will keep running in the background and print any events that arrive
@@ -0,0 +1,110 @@
+//! Types for animating scene items with `Obs::animate_item`.
+
+use std::time::Duration;
+use typed_builder::TypedBuilder;
+
+/// An animation of a scene item from one transform to another, see `Obs::animate_item`.
+#[derive(TypedBuilder, Debug, Clone, PartialEq)]
+pub struct Animation {
+    /// Name of the scene the scene item belongs to. Defaults to the current scene.
+    #[builder(default, setter(strip_option, into))]
+    pub scene_name: Option<String>,
+    /// Name of the scene item.
+    #[builder(setter(into))]
+    pub item: String,
+    /// The transform the item is set to first.
+    pub from: Transform,
+    /// The transform the item ends up in.
+    pub to: Transform,
+    /// How long the animation takes.
+    pub duration: Duration,
+    /// Number of evenly spaced steps the item is moved in. At least 1 step is taken.
+    pub steps: u32,
+    /// Defaults to `Easing::Linear`.
+    #[builder(default = Easing::Linear)]
+    pub easing: Easing,
+}
+
+/// The position, scale and rotation of a scene item.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform {
+    /// x position from the left
+    pub position_x: f64,
+    /// y position from the top
+    pub position_y: f64,
+    /// x-scale factor
+    pub scale_x: f64,
+    /// y-scale factor
+    pub scale_y: f64,
+    /// clockwise rotation in degrees
+    pub rotation: f64,
+}
+
+impl Transform {
+    /// Interpolates between this transform and the given one. `t` = 0.0 returns `self` and `t` = 1.0 returns `to`.
+    pub fn interpolate(&self, to: &Transform, t: f64) -> Transform {
+        let lerp = |from: f64, to: f64| from + (to - from) * t;
+        Transform {
+            position_x: lerp(self.position_x, to.position_x),
+            position_y: lerp(self.position_y, to.position_y),
+            scale_x: lerp(self.scale_x, to.scale_x),
+            scale_y: lerp(self.scale_y, to.scale_y),
+            rotation: lerp(self.rotation, to.rotation),
+        }
+    }
+}
+
+/// Easing function used to determine the progress of an animation over time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Easing {
+    /// Constant speed.
+    Linear,
+    /// Starts slow and speeds up.
+    EaseIn,
+    /// Starts fast and slows down.
+    EaseOut,
+    /// Starts slow, speeds up and slows down at the end.
+    EaseInOut,
+}
+
+impl Easing {
+    /// Maps the elapsed time `t` (0.0 to 1.0) to the progress of the animation (0.0 to 1.0).
+    pub fn apply(self, t: f64) -> f64 {
+        match self {
+            Self::Linear => t,
+            Self::EaseIn => t * t,
+            Self::EaseOut => t * (2.0 - t),
+            Self::EaseInOut => {
+                if t < 0.5 {
+                    2.0 * t * t
+                } else {
+                    -1.0 + (4.0 - 2.0 * t) * t
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn easing() {
+        for easing in &[
+            Easing::Linear,
+            Easing::EaseIn,
+            Easing::EaseOut,
+            Easing::EaseInOut,
+        ] {
+            assert_eq!(easing.apply(0.0), 0.0);
+            assert_eq!(easing.apply(1.0), 1.0);
+        }
+        assert_eq!(Easing::Linear.apply(0.25), 0.25);
+        assert!(Easing::EaseIn.apply(0.25) < 0.25);
+        assert!(Easing::EaseOut.apply(0.25) > 0.25);
+        assert_eq!(Easing::EaseInOut.apply(0.5), 0.5);
+        assert!(Easing::EaseInOut.apply(0.25) < 0.25);
+        assert!(Easing::EaseInOut.apply(0.75) > 0.75);
+    }
+}
@@ -0,0 +1,66 @@
+//! Audit log of the requests and responses exchanged with OBS, see `ObsConfig::audit_log`.
+
+use serde_json::{json, Value};
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, Write},
+    path::Path,
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+// fields whose values are replaced in the log, e.g. the authentication response and stream keys
+const SECRET_FIELDS: &[&str] = &["auth", "password", "key"];
+const REDACTED: &str = "<redacted>";
+
+// appends newline-delimited JSON to a file, written to by the handler thread
+#[derive(Debug)]
+pub(crate) struct AuditLog {
+    file: Mutex<File>,
+}
+
+impl AuditLog {
+    pub(crate) fn open(path: &Path) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self {
+            file: Mutex::new(file),
+        })
+    }
+
+    // writes a line with the message, direction is "request" or "response"
+    // failing to write is logged instead of closing the connection
+    pub(crate) fn write(&self, direction: &str, text: &str) {
+        let mut message = serde_json::from_str(text).unwrap_or_else(|_| Value::from(text));
+        redact(&mut message);
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|since_epoch| since_epoch.as_millis() as u64)
+            .unwrap_or(0);
+        let line = json!({
+            "timestamp": timestamp,
+            "direction": direction,
+            "message": message,
+        });
+        let mut file = self.file.lock().unwrap();
+        if let Err(e) = writeln!(file, "{}", line) {
+            log::warn!("Failed to write to the audit log: {}", e);
+        }
+    }
+}
+
+// replaces the values of the secret fields, including in nested objects
+fn redact(value: &mut Value) {
+    match value {
+        Value::Object(object) => {
+            for (key, value) in object {
+                if SECRET_FIELDS.contains(&key.as_str()) && !value.is_null() {
+                    *value = Value::from(REDACTED);
+                } else {
+                    redact(value);
+                }
+            }
+        }
+        Value::Array(values) => values.iter_mut().for_each(redact),
+        _ => {}
+    }
+}
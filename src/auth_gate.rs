@@ -0,0 +1,67 @@
+//! Holds back requests until authentication has completed, see `ObsConfig::defer_until_authenticated`.
+
+use futures::{
+    channel::oneshot,
+    future::{FutureExt, Shared},
+};
+use std::sync::Mutex;
+
+// requests that are allowed before authentication because they're needed for it
+const AUTHENTICATION_REQUESTS: &[&str] = &["GetAuthRequired", "Authenticate"];
+
+// opened when authentication completes, after which waiting returns immediately until the gate is closed again
+pub(crate) struct AuthGate {
+    state: Mutex<GateState>,
+}
+
+struct GateState {
+    // None while the gate is open
+    sender: Option<oneshot::Sender<()>>,
+    receiver: Shared<oneshot::Receiver<()>>,
+}
+
+impl GateState {
+    fn closed() -> Self {
+        let (sender, receiver) = oneshot::channel();
+        Self {
+            sender: Some(sender),
+            receiver: receiver.shared(),
+        }
+    }
+}
+
+impl Default for AuthGate {
+    fn default() -> Self {
+        Self {
+            state: Mutex::new(GateState::closed()),
+        }
+    }
+}
+
+impl AuthGate {
+    pub(crate) fn open(&self) {
+        if let Some(sender) = self.state.lock().unwrap().sender.take() {
+            log::debug!("Authentication completed, sending deferred requests");
+            let _ = sender.send(());
+        }
+    }
+
+    // closes the gate again, e.g. for a new connection that has to authenticate again
+    // requests already waiting on a closed gate keep waiting for the next open
+    pub(crate) fn close(&self) {
+        let mut state = self.state.lock().unwrap();
+        if state.sender.is_none() {
+            *state = GateState::closed();
+        }
+    }
+
+    // waits until the gate is opened, unless the request is needed for authentication
+    pub(crate) async fn wait(&self, request_type: &str) {
+        if AUTHENTICATION_REQUESTS.contains(&request_type) {
+            return;
+        }
+        let receiver = self.state.lock().unwrap().receiver.clone();
+        // the sender is only dropped without sending along with the gate itself
+        let _ = receiver.await;
+    }
+}
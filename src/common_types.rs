@@ -1,36 +1,76 @@
 //! Common types used several other modules.
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct SceneItem {
+    /// Scene item height. OBS may send this as an integer, and it defaults to 0 if missing.
+    #[serde(default)]
     pub cy: f32,
+    /// Scene item width. OBS may send this as an integer, and it defaults to 0 if missing.
+    #[serde(default)]
     pub cx: f32,
     /// The name of this Scene Item.
     pub name: String,
     /// Scene item ID
     pub id: i32,
     /// Whether or not this Scene Item is set to "visible".
+    /// Called `render` by OBS, see also `SceneItem::visible`.
     pub render: bool,
     /// Whether or not this Scene Item is locked and can't be moved around
     pub locked: bool,
-    pub source_cx: i32,
-    pub source_cy: i32,
+    /// Base width of the source. Not sent by OBS for some source types.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_cx: Option<i32>,
+    /// Base height of the source. Not sent by OBS for some source types.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_cy: Option<i32>,
     /// Source type.
     #[serde(rename = "type")]
     pub scene_item_type: SceneItemType,
+    /// Defaults to 1, i.e. full volume, if missing.
+    #[serde(default = "full_volume")]
     pub volume: f32,
+    /// Defaults to 0 if missing.
+    #[serde(default)]
     pub x: f32,
+    /// Defaults to 0 if missing.
+    #[serde(default)]
     pub y: f32,
     /// Name of the item's parent (if this item belongs to a group)
-    #[serde(rename = "parentGroupName")]
+    #[serde(rename = "parentGroupName", skip_serializing_if = "Option::is_none")]
     pub parent_group_name: Option<String>,
     /// List of children (if this item is a group)
-    #[serde(rename = "groupChildren")]
+    #[serde(rename = "groupChildren", skip_serializing_if = "Option::is_none")]
     pub group_children: Option<Vec<SceneItem>>,
 }
 
+// default for SceneItem::volume, so that a missing volume doesn't read as muted
+fn full_volume() -> f32 {
+    1.0
+}
+
+impl SceneItem {
+    /// Whether or not this Scene Item is set to "visible".
+    /// Same as the `render` field, named consistently with `SetSceneItemProperties::visible`.
+    pub fn visible(&self) -> bool {
+        self.render
+    }
+}
+
+// returns the given items followed by the items in their groups, recursively
+pub(crate) fn flatten_scene_items(items: &[SceneItem]) -> Vec<&SceneItem> {
+    let mut flattened = vec![];
+    for item in items {
+        flattened.push(item);
+        if let Some(children) = &item.group_children {
+            flattened.extend(flatten_scene_items(children));
+        }
+    }
+    flattened
+}
+
 /// Note: Contains more variants than documented in the reference, more variants may be missing.
-#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 #[non_exhaustive]
 pub enum SceneItemType {
@@ -45,7 +85,45 @@ pub enum SceneItemType {
     Unknown,
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq)]
+/// Alignment flags used by `Position` and `Bounds`. Flags can be combined, e.g. `TOP | LEFT`.
+pub mod alignment {
+    pub const CENTER: i32 = 0;
+    pub const LEFT: i32 = 1;
+    pub const RIGHT: i32 = 2;
+    pub const TOP: i32 = 4;
+    pub const BOTTOM: i32 = 8;
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SceneItemTransform {
+    pub position: Position,
+    /// The clockwise rotation of the scene item in degrees around the point of alignment.
+    pub rotation: f64,
+    pub scale: Scale,
+    pub crop: Crop,
+    /// If the scene item is visible.
+    pub visible: bool,
+    /// If the scene item is locked in position.
+    pub locked: bool,
+    pub bounds: Bounds,
+    /// Base width (without scaling) of the source
+    pub source_width: i32,
+    /// Base source (without scaling) of the source
+    pub source_height: i32,
+    /// Scene item width (base source width multiplied by the horizontal scaling factor)
+    pub width: f64,
+    /// Scene item height (base source height multiplied by the vertical scaling factor)
+    pub height: f64,
+    /// Name of the item's parent (if this item belongs to a group)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent_group_name: Option<String>,
+    /// List of children (if this item is a group)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group_children: Option<Vec<SceneItemTransform>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Position {
     /// x position from the left
     pub x: f64,
@@ -55,7 +133,7 @@ pub struct Position {
     pub alignment: i32,
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Scale {
     /// x-scale factor
     pub x: f64,
@@ -64,7 +142,7 @@ pub struct Scale {
 }
 
 /// Rectangular crop for scene items.
-#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct Crop {
     /// pixels cropped off the top
     pub top: i32,
@@ -77,7 +155,7 @@ pub struct Crop {
 }
 
 /// Bounding box for scene items.
-#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Bounds {
     /// bounds scaling type
     #[serde(rename = "type")]
@@ -91,7 +169,7 @@ pub struct Bounds {
 }
 
 /// Bounds scaling type.
-#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub enum BoundsType {
     #[serde(rename = "OBS_BOUNDS_NONE")]
     None,
@@ -109,8 +187,22 @@ pub enum BoundsType {
     MaxOnly,
 }
 
+/// Audio monitoring type of a source.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MonitorType {
+    /// The source is not monitored.
+    #[serde(rename = "none")]
+    None,
+    /// The source is only played on the monitoring device.
+    #[serde(rename = "monitorOnly")]
+    MonitorOnly,
+    /// The source is played on the monitoring device and sent to the outputs.
+    #[serde(rename = "monitorAndOutput")]
+    MonitorAndOutput,
+}
+
 /// Contains various statistics.
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "kebab-case")]
 pub struct ObsStats {
     /// Current framerate.
@@ -133,7 +225,19 @@ pub struct ObsStats {
     pub free_disk_space: f64,
 }
 
-#[derive(Deserialize, Debug, PartialEq, Eq)]
+impl ObsStats {
+    /// Free recording disk space (in gigabytes)
+    pub fn free_disk_space_gb(&self) -> f64 {
+        self.free_disk_space / 1024.0
+    }
+
+    /// Checks if the free recording disk space is below the given threshold (in gigabytes).
+    pub fn low_disk_space(&self, threshold_gb: f64) -> bool {
+        self.free_disk_space_gb() < threshold_gb
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
 pub enum SourceTypesType {
     Input,
@@ -142,7 +246,7 @@ pub enum SourceTypesType {
     Other,
 }
 
-#[derive(Deserialize, Serialize, Debug, PartialEq)]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 #[non_exhaustive]
 pub enum FilterType {
     #[serde(rename = "clut_filter")]
@@ -171,35 +275,320 @@ pub enum FilterType {
     Unknown,
 }
 
-#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+/// The kind of a source. Kinds without a variant are represented by `Other`.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(from = "String", into = "String")]
 #[non_exhaustive]
 pub enum SourceKind {
-    #[serde(rename = "alsa_input_capture")]
     AudioCaptureDeviceAlsa,
-    #[serde(rename = "pulse_input_capture")]
+    BrowserSource,
     AudioInputCapturePulseAudio,
-    #[serde(rename = "pulse_output_capture")]
     AudioOutputCapturePulseAudio,
-    #[serde(rename = "color_source_v2")]
     ColorSource,
-    #[serde(rename = "image_source")]
     Image,
-    #[serde(rename = "slideshow")]
     ImageSlideShow,
-    #[serde(rename = "jack_output_capture")]
     JACKInputClient,
-    #[serde(rename = "ffmpeg_source")]
     MediaSource,
-    #[serde(rename = "scene")]
     Scene,
-    #[serde(rename = "xshm_input")]
     ScreenCaptureXSHM,
-    #[serde(rename = "text_ft2_source_v2")]
     TextFreeType2,
-    #[serde(rename = "v4l2_input")]
     VideoCaptureDeviceV4L2,
-    #[serde(rename = "xcomposite_input")]
     WindowCaptureXcomposite,
-    #[serde(other)]
-    Unknown,
+    /// A kind without a variant, e.g. a source type from a plugin.
+    Other(String),
+}
+
+impl SourceKind {
+    /// The kind as used by OBS, e.g. `ffmpeg_source`.
+    pub fn as_str(&self) -> &str {
+        match self {
+            SourceKind::AudioCaptureDeviceAlsa => "alsa_input_capture",
+            SourceKind::BrowserSource => "browser_source",
+            SourceKind::AudioInputCapturePulseAudio => "pulse_input_capture",
+            SourceKind::AudioOutputCapturePulseAudio => "pulse_output_capture",
+            SourceKind::ColorSource => "color_source_v2",
+            SourceKind::Image => "image_source",
+            SourceKind::ImageSlideShow => "slideshow",
+            SourceKind::JACKInputClient => "jack_output_capture",
+            SourceKind::MediaSource => "ffmpeg_source",
+            SourceKind::Scene => "scene",
+            SourceKind::ScreenCaptureXSHM => "xshm_input",
+            SourceKind::TextFreeType2 => "text_ft2_source_v2",
+            SourceKind::VideoCaptureDeviceV4L2 => "v4l2_input",
+            SourceKind::WindowCaptureXcomposite => "xcomposite_input",
+            SourceKind::Other(kind) => kind,
+        }
+    }
+}
+
+impl From<String> for SourceKind {
+    fn from(kind: String) -> Self {
+        match kind.as_str() {
+            "alsa_input_capture" => SourceKind::AudioCaptureDeviceAlsa,
+            "browser_source" => SourceKind::BrowserSource,
+            "pulse_input_capture" => SourceKind::AudioInputCapturePulseAudio,
+            "pulse_output_capture" => SourceKind::AudioOutputCapturePulseAudio,
+            "color_source_v2" => SourceKind::ColorSource,
+            "image_source" => SourceKind::Image,
+            "slideshow" => SourceKind::ImageSlideShow,
+            "jack_output_capture" => SourceKind::JACKInputClient,
+            "ffmpeg_source" => SourceKind::MediaSource,
+            "scene" => SourceKind::Scene,
+            "xshm_input" => SourceKind::ScreenCaptureXSHM,
+            "text_ft2_source_v2" => SourceKind::TextFreeType2,
+            "v4l2_input" => SourceKind::VideoCaptureDeviceV4L2,
+            "xcomposite_input" => SourceKind::WindowCaptureXcomposite,
+            _ => SourceKind::Other(kind),
+        }
+    }
+}
+
+impl From<SourceKind> for String {
+    fn from(kind: SourceKind) -> Self {
+        match kind {
+            SourceKind::Other(kind) => kind,
+            kind => kind.as_str().to_string(),
+        }
+    }
+}
+
+/// The kind of an output. Kinds without a variant are represented by `Other`.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(from = "String", into = "String")]
+#[non_exhaustive]
+pub enum OutputKind {
+    FfmpegMuxer,
+    FfmpegOutput,
+    FtlOutput,
+    ReplayBuffer,
+    RtmpOutput,
+    VirtualCam,
+    /// A kind without a variant, e.g. an output type from a plugin.
+    Other(String),
+}
+
+impl OutputKind {
+    /// The kind as used by OBS, e.g. `rtmp_output`.
+    pub fn as_str(&self) -> &str {
+        match self {
+            OutputKind::FfmpegMuxer => "ffmpeg_muxer",
+            OutputKind::FfmpegOutput => "ffmpeg_output",
+            OutputKind::FtlOutput => "ftl_output",
+            OutputKind::ReplayBuffer => "replay_buffer",
+            OutputKind::RtmpOutput => "rtmp_output",
+            OutputKind::VirtualCam => "virtualcam_output",
+            OutputKind::Other(kind) => kind,
+        }
+    }
+}
+
+impl From<String> for OutputKind {
+    fn from(kind: String) -> Self {
+        match kind.as_str() {
+            "ffmpeg_muxer" => OutputKind::FfmpegMuxer,
+            "ffmpeg_output" => OutputKind::FfmpegOutput,
+            "ftl_output" => OutputKind::FtlOutput,
+            "replay_buffer" => OutputKind::ReplayBuffer,
+            "rtmp_output" => OutputKind::RtmpOutput,
+            "virtualcam_output" => OutputKind::VirtualCam,
+            _ => OutputKind::Other(kind),
+        }
+    }
+}
+
+impl From<OutputKind> for String {
+    fn from(kind: OutputKind) -> Self {
+        match kind {
+            OutputKind::Other(kind) => kind,
+            kind => kind.as_str().to_string(),
+        }
+    }
+}
+
+/// The kind of a transition. Kinds without a variant are represented by `Other`.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(from = "String", into = "String")]
+#[non_exhaustive]
+pub enum TransitionKind {
+    Cut,
+    Fade,
+    FadeToColor,
+    LumaWipe,
+    Slide,
+    Stinger,
+    Swipe,
+    /// A kind without a variant, e.g. a transition type from a plugin.
+    Other(String),
+}
+
+impl TransitionKind {
+    /// The kind as used by OBS, e.g. `fade_transition`.
+    pub fn as_str(&self) -> &str {
+        match self {
+            TransitionKind::Cut => "cut_transition",
+            TransitionKind::Fade => "fade_transition",
+            TransitionKind::FadeToColor => "fade_to_color_transition",
+            TransitionKind::LumaWipe => "wipe_transition",
+            TransitionKind::Slide => "slide_transition",
+            TransitionKind::Stinger => "obs_stinger_transition",
+            TransitionKind::Swipe => "swipe_transition",
+            TransitionKind::Other(kind) => kind,
+        }
+    }
+}
+
+impl From<String> for TransitionKind {
+    fn from(kind: String) -> Self {
+        match kind.as_str() {
+            "cut_transition" => TransitionKind::Cut,
+            "fade_transition" => TransitionKind::Fade,
+            "fade_to_color_transition" => TransitionKind::FadeToColor,
+            "wipe_transition" => TransitionKind::LumaWipe,
+            "slide_transition" => TransitionKind::Slide,
+            "obs_stinger_transition" => TransitionKind::Stinger,
+            "swipe_transition" => TransitionKind::Swipe,
+            _ => TransitionKind::Other(kind),
+        }
+    }
+}
+
+impl From<TransitionKind> for String {
+    fn from(kind: TransitionKind) -> Self {
+        match kind {
+            TransitionKind::Other(kind) => kind,
+            kind => kind.as_str().to_string(),
+        }
+    }
+}
+
+/// Settings of a browser source, used with `SetSourceSettings::typed`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct BrowserSourceSettings {
+    /// Indicates that a local file is in use.
+    pub is_local_file: bool,
+    /// File path.
+    pub local_file: String,
+    /// Url.
+    pub url: String,
+    /// CSS to inject.
+    pub css: String,
+    /// Width.
+    pub width: i32,
+    /// Height.
+    pub height: i32,
+    /// Framerate.
+    pub fps: i32,
+    /// Indicates whether the source should be shutdown when not visible.
+    pub shutdown: bool,
+    /// Indicates whether the browser should be refreshed when the scene becomes active.
+    pub restart_when_active: bool,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn stats_with_free_disk_space(free_disk_space: f64) -> ObsStats {
+        ObsStats {
+            fps: 60.0,
+            render_total_frames: 0,
+            render_missed_frames: 0,
+            output_total_frames: 0,
+            output_skipped_frames: 0,
+            average_frame_time: 0.0,
+            cpu_usage: 0.0,
+            memory_usage: 0.0,
+            free_disk_space,
+        }
+    }
+
+    #[test]
+    fn scene_item_visible() {
+        let item: SceneItem = serde_json::from_value(serde_json::json!({
+            "cx": 1.0,
+            "cy": 1.0,
+            "id": 1,
+            "locked": false,
+            "name": "item",
+            "render": false,
+            "source_cx": 1,
+            "source_cy": 1,
+            "type": "input",
+            "volume": 1.0,
+            "x": 0.0,
+            "y": 0.0
+        }))
+        .unwrap();
+        assert!(!item.visible());
+    }
+
+    #[test]
+    fn scene_item_minimal() {
+        let item: SceneItem = serde_json::from_value(serde_json::json!({
+            "cx": 0.0,
+            "cy": 0.0,
+            "id": 3,
+            "locked": false,
+            "name": "audio",
+            "render": true,
+            "type": "ffmpeg_source",
+            "volume": 1.0,
+            "x": 0.0,
+            "y": 0.0
+        }))
+        .unwrap();
+        assert_eq!(item.source_cx, None);
+        assert_eq!(item.source_cy, None);
+        assert_eq!(item.scene_item_type, SceneItemType::Unknown);
+    }
+
+    #[test]
+    fn scene_item_missing_numeric_fields() {
+        let item: SceneItem = serde_json::from_value(serde_json::json!({
+            "cx": 1920,
+            "cy": 1080,
+            "id": 4,
+            "locked": true,
+            "name": "capture",
+            "render": true,
+            "type": "input"
+        }))
+        .unwrap();
+        assert_eq!(item.cx, 1920.0);
+        assert_eq!(item.cy, 1080.0);
+        assert_eq!(item.source_cx, None);
+        assert_eq!(item.source_cy, None);
+        assert_eq!(item.volume, 1.0);
+        assert_eq!(item.x, 0.0);
+        assert_eq!(item.y, 0.0);
+    }
+
+    #[test]
+    fn free_disk_space_gb() {
+        let stats = stats_with_free_disk_space(2048.0);
+        assert_eq!(stats.free_disk_space_gb(), 2.0);
+    }
+
+    #[test]
+    fn low_disk_space() {
+        let stats = stats_with_free_disk_space(5120.0);
+        assert!(stats.low_disk_space(10.0));
+        assert!(!stats.low_disk_space(5.0));
+        assert!(!stats.low_disk_space(1.0));
+    }
+
+    #[test]
+    fn monitor_type() {
+        let types = [
+            (MonitorType::None, "none"),
+            (MonitorType::MonitorOnly, "monitorOnly"),
+            (MonitorType::MonitorAndOutput, "monitorAndOutput"),
+        ];
+        for (monitor_type, name) in types.iter() {
+            assert_eq!(serde_json::to_value(monitor_type).unwrap(), *name);
+            let deserialized: MonitorType =
+                serde_json::from_value(serde_json::json!(name)).unwrap();
+            assert_eq!(deserialized, *monitor_type);
+        }
+    }
 }
@@ -0,0 +1,42 @@
+//! Compatibility with the obs-websocket version of the server, see `Obs::check_compatibility`.
+
+/// The version of the obs-websocket protocol supported by this crate.
+pub const SUPPORTED_OBS_WEBSOCKET_VERSION: &str = "4.9";
+
+/// How the obs-websocket version of the server compares to `SUPPORTED_OBS_WEBSOCKET_VERSION`.
+/// Only the components of the supported version are compared, so e.g. 4.9.1 is compatible with 4.9.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compatibility {
+    /// The server has the supported version.
+    Ok,
+    /// The server is newer than the supported version and may have changed or removed requests.
+    ServerNewer,
+    /// The server is older than the supported version and may lack some requests.
+    ServerOlder,
+}
+
+impl Compatibility {
+    // compares the server version to the supported version, returns None if the server version can't be parsed
+    pub(crate) fn check(server_version: &str) -> Option<Self> {
+        let supported = parse_version(SUPPORTED_OBS_WEBSOCKET_VERSION)?;
+        let server = parse_version(server_version)?;
+        // compare only the components in the supported version, missing server components are treated as 0
+        let server = (0..supported.len())
+            .map(|i| server.get(i).copied().unwrap_or(0))
+            .collect::<Vec<_>>();
+        Some(match server.cmp(&supported) {
+            std::cmp::Ordering::Equal => Compatibility::Ok,
+            std::cmp::Ordering::Greater => Compatibility::ServerNewer,
+            std::cmp::Ordering::Less => Compatibility::ServerOlder,
+        })
+    }
+}
+
+// parses a version like 4.9.1 into its components
+fn parse_version(version: &str) -> Option<Vec<u32>> {
+    version
+        .trim()
+        .split('.')
+        .map(|component| component.parse().ok())
+        .collect()
+}
@@ -0,0 +1,135 @@
+//! Configuration for the connection to OBS.
+
+use async_tungstenite::tungstenite::protocol::WebSocketConfig;
+use serde_json::Value;
+use std::{fmt::Debug, path::PathBuf, time::Duration};
+use typed_builder::TypedBuilder;
+
+/// Optional settings for a connection to OBS. Used with `Obs::connect_with_config`.
+#[derive(TypedBuilder, Debug)]
+pub struct ObsConfig {
+    /// If set, a GetVersion request is sent to OBS at this interval.
+    /// If it fails or OBS doesn't respond within the interval, the connection is considered lost and is closed,
+    /// which is reported with an `EventType::HealthCheckFailed` event to the event receivers.
+    /// Connections to an address are then reconnected once per interval until it succeeds, like with `Obs::reconnect`,
+    /// except that the event receiver returned when connecting keeps receiving events from the new connection.
+    /// Connections with a custom transport are not reconnected, which is left to the user with `Obs::reconnect_with_transport`.
+    /// Useful for detecting and recovering from half-open connections.
+    #[builder(default, setter(strip_option))]
+    pub health_check_interval: Option<Duration>,
+    /// Interceptors that are called for every request sent to OBS and every response received, in order.
+    #[builder(default)]
+    pub interceptors: Vec<Box<dyn Interceptor>>,
+    /// If true, requests are sent as pretty-printed JSON instead of compact JSON. Useful for debugging.
+    #[builder(default)]
+    pub pretty_json: bool,
+    /// Prefix used for the message-ids generated for requests, e.g. to tell the requests of different clients apart in the OBS logs.
+    /// Avoid custom message-ids that start with the prefix to avoid clashes. Defaults to `_`.
+    #[builder(default = "_".to_string(), setter(into))]
+    pub message_id_prefix: String,
+    /// Maximum size of an incoming WebSocket message in bytes. Defaults to tungstenite's default of 64 MiB.
+    /// Larger messages, e.g. big screenshots, are rejected and close the connection.
+    #[builder(default, setter(strip_option))]
+    pub max_message_size: Option<usize>,
+    /// Maximum size of a single incoming WebSocket frame in bytes. Defaults to tungstenite's default of 16 MiB.
+    #[builder(default, setter(strip_option))]
+    pub max_frame_size: Option<usize>,
+    /// Asked for a new password when `Obs::authenticate` fails due to a wrong password.
+    /// Authentication is retried once with the new password.
+    #[builder(default, setter(strip_option))]
+    pub password_provider: Option<Box<dyn PasswordProvider>>,
+    /// If true, a panic in the handler thread, e.g. in an interceptor, closes the connection and resolves
+    /// the pending requests with `ObsError::ConnectionLost` instead of leaving them waiting.
+    #[builder(default)]
+    pub catch_handler_panics: bool,
+    /// Rewrites scene names in the requests sent to OBS and back in the responses and events received,
+    /// e.g. for namespacing the scenes of different users of a shared OBS instance.
+    /// Only fields that always contain a scene name are rewritten, so e.g. source names that refer to scenes are not.
+    #[builder(default, setter(strip_option))]
+    pub scene_name_mapper: Option<Box<dyn SceneNameMapper>>,
+    /// If set, every request sent to OBS and every response received is appended to this file as a line of JSON
+    /// with a timestamp in milliseconds since the Unix epoch. Secrets like passwords and stream keys are redacted.
+    /// Useful as a durable record of the commands sent to OBS.
+    #[builder(default, setter(strip_option, into))]
+    pub audit_log: Option<PathBuf>,
+    /// If set, the connection is closed once this many responses have been received to requests that were never sent
+    /// or were already answered, which suggests that the message-ids are out of sync, e.g. due to a misbehaving proxy.
+    /// Pending requests then return `ObsError::ConnectionLost` so that the application can reconnect cleanly.
+    #[builder(default, setter(strip_option))]
+    pub orphan_response_threshold: Option<usize>,
+    /// If true, requests other than the ones used by `Obs::authenticate` wait until `Obs::authenticate` has returned,
+    /// so that requests sent during startup aren't rejected by OBS for not being authenticated.
+    /// The requests wait again after `Obs::reconnect` until `Obs::authenticate` is called for the new connection.
+    /// Only enable this if `Obs::authenticate` is called, as the requests wait for it indefinitely otherwise.
+    #[builder(default)]
+    pub defer_until_authenticated: bool,
+    /// If set, at most this many requests sent with `Obs::request` wait for a response at a time and the rest are
+    /// queued until earlier ones complete, e.g. to avoid overwhelming OBS with helpers that send many requests at once.
+    /// A limit of 0 is treated as 1.
+    #[builder(default, setter(strip_option))]
+    pub max_concurrent_requests: Option<usize>,
+    /// Called with the request-type and the time between sending a request and receiving its response,
+    /// e.g. for collecting latency metrics. Called on the handler thread, so it should not block.
+    #[builder(default, setter(strip_option))]
+    pub on_request_complete: Option<Box<dyn RequestCompleteCallback>>,
+}
+
+impl ObsConfig {
+    // the tungstenite config for the WebSocket connection
+    pub(crate) fn websocket_config(&self) -> WebSocketConfig {
+        let default = WebSocketConfig::default();
+        WebSocketConfig {
+            max_message_size: self.max_message_size.or(default.max_message_size),
+            max_frame_size: self.max_frame_size.or(default.max_frame_size),
+            ..default
+        }
+    }
+}
+
+impl Default for ObsConfig {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+/// Hooks into the requests sent to OBS, e.g. for collecting metrics or modifying requests.
+/// Interceptors are called on the handler thread, so they should not block.
+pub trait Interceptor: Debug + Send + Sync {
+    /// Called with the JSON of a request before it's sent to OBS.
+    fn before(&self, _request_type: &str, _value: &mut Value) {}
+
+    /// Called with the result of a request before it's returned.
+    /// Ok contains the response JSON and Err contains the error message from OBS.
+    fn after(&self, _request_type: &str, _result: &Result<Value, String>) {}
+
+    /// Called with the payload of a binary message received from OBS.
+    /// obs-websocket only sends text messages, so this is only useful for custom decoding with alternate servers.
+    fn binary(&self, _data: &[u8]) {}
+}
+
+/// Callback for `ObsConfig::on_request_complete`, implemented for all `Fn(&str, Duration)` closures.
+pub trait RequestCompleteCallback: Fn(&str, Duration) + Send + Sync {}
+
+impl<F: Fn(&str, Duration) + Send + Sync> RequestCompleteCallback for F {}
+
+impl Debug for dyn RequestCompleteCallback {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("RequestCompleteCallback")
+    }
+}
+
+/// Provides a password for retrying authentication, e.g. by prompting the user. See `ObsConfig::password_provider`.
+pub trait PasswordProvider: Debug + Send + Sync {
+    /// Returns the password to retry authentication with.
+    fn password(&self) -> String;
+}
+
+/// Maps scene names between the names used by the application and the names used in OBS.
+/// See `ObsConfig::scene_name_mapper`.
+pub trait SceneNameMapper: Debug + Send + Sync {
+    /// Maps a scene name used by the application to the name used in OBS.
+    fn obs_name(&self, name: &str) -> String;
+
+    /// Maps a scene name used in OBS back to the name used by the application.
+    fn app_name(&self, name: &str) -> String;
+}
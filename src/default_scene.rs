@@ -0,0 +1,46 @@
+//! Requests with a default scene, see `Obs::with_default_scene`.
+
+use crate::{error::ObsError, obs::Obs, requests::Request};
+
+use serde_json::Value;
+
+// fields of scene item requests that default to the current scene
+const SCENE_FIELDS: &[&str] = &["scene-name", "sceneName", "scene", "fromScene", "toScene"];
+
+/// Sends requests with a default scene. The scene is filled in for the requests that would otherwise default to the
+/// current scene, e.g. `GetSceneItemProperties` without a scene name. Scenes that are set explicitly are kept.
+/// Created with `Obs::with_default_scene`.
+pub struct DefaultScene<'a> {
+    obs: &'a Obs,
+    scene_name: String,
+}
+
+impl<'a> DefaultScene<'a> {
+    pub(crate) fn new(obs: &'a Obs, scene_name: &str) -> Self {
+        Self {
+            obs,
+            scene_name: scene_name.to_string(),
+        }
+    }
+
+    /// The default scene.
+    pub fn scene_name(&self) -> &str {
+        &self.scene_name
+    }
+
+    /// Sends the given request to OBS, filling in the default scene if the request's scene is not set.
+    pub async fn request<T>(&self, req: &T) -> Result<T::Response, ObsError>
+    where
+        T: Request + std::fmt::Debug,
+    {
+        self.obs
+            .request_with(req, |value| {
+                for field in SCENE_FIELDS {
+                    if let Some(scene @ Value::Null) = value.get_mut(*field) {
+                        *scene = Value::from(self.scene_name.as_str());
+                    }
+                }
+            })
+            .await
+    }
+}
@@ -0,0 +1,18 @@
+//! Diagnostics snapshot of OBS, see `Obs::diagnostics`.
+
+use crate::{common_types::ObsStats, responses};
+
+use serde::Serialize;
+
+/// Information about OBS that is useful when reporting issues. Can be serialized e.g. into JSON.
+#[derive(Serialize, Debug, PartialEq)]
+pub struct Diagnostics {
+    /// Versions and available requests.
+    pub version: responses::GetVersion,
+    /// Performance statistics.
+    pub stats: ObsStats,
+    /// Video settings.
+    pub video_info: responses::GetVideoInfo,
+    /// Streaming and recording status.
+    pub streaming_status: responses::GetStreamingStatus,
+}
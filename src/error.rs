@@ -15,12 +15,18 @@ pub enum ObsError {
     ConnectionInterrupted,
     #[error("Oneshot channel sender closed")]
     OneshotCanceled(#[source] Canceled),
+    #[error("Connection to OBS was lost before a response was received")]
+    ConnectionLost,
     #[error("Not connected")]
     NotConnected,
     #[error("No authentication required")]
     NoAuthRequired,
+    #[error("Authentication failed, the password may be wrong")]
+    AuthenticationFailed,
     #[error("Tungstenite timed out")]
     TungsteniteTimeout,
+    #[error("Timed out waiting for OBS")]
+    RequestTimeout,
     #[error("Handler thread panicked")]
     HandlerThreadError(Box<dyn Any + Send + 'static>),
     #[error("Error(s) while disconnecting: socket: {socket_error:?}, thread: {thread_error:?}")]
@@ -30,6 +36,30 @@ pub enum ObsError {
     },
     #[error("Error from OBS: {0}")]
     ObsError(String),
+    #[error("Scene item not found: {0}")]
+    SceneItemNotFound(String),
+    #[error("Scene not found: {0}")]
+    SceneNotFound(String),
+    #[error("Output not found: {0}")]
+    OutputNotFound(String),
+    #[error("Profile not found: {0}")]
+    ProfileNotFound(String),
+    #[error("Scene collection not found: {0}")]
+    SceneCollectionNotFound(String),
+    #[error("Failed to deserialize the response to {request_type} (message-id {message_id}): {source}, response: {response}")]
+    InvalidResponse {
+        request_type: String,
+        message_id: String,
+        response: String,
+        #[source]
+        source: serde_json::Error,
+    },
+    #[error("The response to {request_type} (message-id {message_id}) is missing the field \"{missing_field}\"")]
+    MalformedResponse {
+        request_type: String,
+        message_id: String,
+        missing_field: String,
+    },
     #[error("Handshake interrupted")]
     HandshakeInterrupted,
     #[error("Handshake failed")]
@@ -46,10 +76,48 @@ pub enum ObsError {
     MissingSalt,
     #[error("Invalid address: {0}")]
     InvalidAddress(String),
+    #[error("Invalid URL {url}: {source}")]
+    InvalidUrl {
+        url: String,
+        #[source]
+        source: url::ParseError,
+    },
+    #[error("TLS connections (wss://) are not supported")]
+    TlsNotSupported,
+    #[error("Request {request_type} is not supported by obs-websocket {obs_websocket_version}")]
+    Unsupported {
+        request_type: String,
+        obs_websocket_version: String,
+    },
+    #[error("Scene index {index} is out of range for {scene_count} scenes")]
+    SceneIndexOutOfRange { index: usize, scene_count: usize },
+    #[error("Failed to determine the obs-websocket version of the server")]
+    UnknownServerVersion,
+    #[error("Invalid screenshot: {0}")]
+    InvalidScreenshot(String),
+    #[error("No connection with the label {0}")]
+    UnknownConnection(String),
     #[error("Failed to start thread")]
     Thread(#[source] std::io::Error),
 }
 
+impl ObsError {
+    // converts an error message from OBS into an error, using a more specific variant for known messages
+    pub(crate) fn from_obs_message(message: String) -> Self {
+        if message.contains("scene item doesn't exist") {
+            ObsError::SceneItemNotFound(message)
+        } else if message.contains("scene does not exist")
+            || message.contains("scene doesn't exist")
+        {
+            ObsError::SceneNotFound(message)
+        } else if message.contains("output doesn't exist") {
+            ObsError::OutputNotFound(message)
+        } else {
+            ObsError::ObsError(message)
+        }
+    }
+}
+
 impl<T: HandshakeRole> From<HandshakeError<T>> for ObsError {
     fn from(err: HandshakeError<T>) -> ObsError {
         match err {
@@ -62,8 +130,10 @@ impl<T: HandshakeRole> From<HandshakeError<T>> for ObsError {
 /// Errors that can occur in the handler thread
 #[derive(Debug, Error)]
 pub enum HandlerError {
-    #[error("Failed to send response")]
-    SendResponse,
     #[error("Tungstenite error")]
     Tungstenite(#[source] tungstenite::Error),
+    #[error("Handler panicked: {0}")]
+    Panicked(String),
+    #[error("Received {0} responses to unknown requests")]
+    TooManyOrphanResponses(usize),
 }
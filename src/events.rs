@@ -1,26 +1,38 @@
 //! Event types. Sent by the server as events occur in OBS.
 
 use crate::common_types::*;
-use serde::{de::Deserializer, Deserialize};
+use futures::{
+    future::{self, Either},
+    stream::{self, Stream, StreamExt},
+};
+use serde::{
+    de::Deserializer,
+    ser::{SerializeMap, Serializer},
+    Deserialize, Serialize,
+};
 use serde_json::Value;
+use smol::Timer;
+use std::{collections::VecDeque, time::Duration};
 
 /// Events are broadcast by the server to each connected client when a recognized action occurs within OBS.
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "kebab-case")]
 pub struct Event {
     /// time elapsed between now and stream start (only present if OBS Studio is streaming)
     /// Format: HH:MM:SS.mmm
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub stream_timecode: Option<String>,
     /// time elapsed between now and recording start (only present if OBS Studio is recording)
     /// Format: HH:MM:SS.mmm
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub rec_timecode: Option<String>,
     /// the type of event
-    #[serde(flatten)]
+    #[serde(flatten, serialize_with = "ser_event_type")]
     pub update_type: EventType,
 }
 
 /// Contains all the different kinds of events that can occur.
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(tag = "update-type")]
 pub enum EventType {
     // Scenes
@@ -59,6 +71,9 @@ pub enum EventType {
     TransitionBegin {
         /// Transition name.
         name: String,
+        /// Transition type. Only sent by newer versions of obs-websocket.
+        #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+        transition_type: Option<TransitionKind>,
         /// Transition duration (in milliseconds).
         duration: i32,
         /// Source scene of the transition
@@ -66,6 +81,19 @@ pub enum EventType {
         /// Destination scene of the transition
         to_scene: String,
     },
+    /// A transition (other than "cut") has ended.
+    #[serde(rename_all = "kebab-case")]
+    TransitionEnd {
+        /// Transition name.
+        name: String,
+        /// Transition type.
+        #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+        transition_type: Option<TransitionKind>,
+        /// Transition duration (in milliseconds).
+        duration: i32,
+        /// Destination scene of the transition
+        to_scene: String,
+    },
 
     // Profiles
     /// Triggered when switching to another profile or when renaming the current profile.
@@ -137,6 +165,12 @@ pub enum EventType {
     RecordingPaused,
     /// Current recording resumed
     RecordingResumed,
+    /// The file being written to has changed, e.g. when the replay buffer is saved.
+    #[serde(rename_all = "camelCase")]
+    RecordingFileChanged {
+        /// Absolute path to the new file.
+        new_file_name: String,
+    },
 
     // Replay Buffer
     /// A request to start the replay buffer has been issued.
@@ -151,6 +185,13 @@ pub enum EventType {
     // Other
     /// OBS is exiting.
     Exiting,
+    /// Not sent by OBS. Emitted when the health check fails, right before the connection is closed and reconnected.
+    /// See `ObsConfig::health_check_interval`.
+    #[serde(rename_all = "camelCase")]
+    HealthCheckFailed {
+        /// Why the health check failed.
+        reason: String,
+    },
 
     // General
     /// Emitted every 2 seconds after enabling it by calling SetHeartbeat.
@@ -159,24 +200,34 @@ pub enum EventType {
         /// Toggles between every JSON message as an "I am alive" indicator.
         pulse: bool,
         /// Current active profile.
+        #[serde(skip_serializing_if = "Option::is_none")]
         current_profile: Option<String>,
         /// Current active scene.
+        #[serde(skip_serializing_if = "Option::is_none")]
         current_scene: Option<String>,
         /// Current streaming state.
+        #[serde(skip_serializing_if = "Option::is_none")]
         streaming: Option<bool>,
         /// Total time (in seconds) since the stream started.
+        #[serde(skip_serializing_if = "Option::is_none")]
         total_stream_time: Option<i32>,
         /// Total bytes sent since the stream started.
+        #[serde(skip_serializing_if = "Option::is_none")]
         total_stream_bytes: Option<i32>,
         /// Total frames streamed since the stream started.
+        #[serde(skip_serializing_if = "Option::is_none")]
         total_stream_frames: Option<i32>,
         /// Current recording state.
+        #[serde(skip_serializing_if = "Option::is_none")]
         recording: Option<bool>,
         /// Total time (in seconds) since recording started.
+        #[serde(skip_serializing_if = "Option::is_none")]
         total_record_time: Option<i32>,
         /// Total bytes recorded since the recording started.
+        #[serde(skip_serializing_if = "Option::is_none")]
         total_record_bytes: Option<i32>,
         /// Total frames recorded since the recording started.
+        #[serde(skip_serializing_if = "Option::is_none")]
         total_record_frames: Option<i32>,
         /// OBS Stats
         stats: ObsStats,
@@ -247,7 +298,7 @@ pub enum EventType {
         mixers: [Mixer; 6],
         /// Raw mixer flags (little-endian, one bit per mixer)
         /// Bit layout: 1 1 [mixer 6] [mixer 5] [mixer 4] [mixer 3] [mixer 2] [mixer 1]
-        #[serde(deserialize_with = "de_hex_string")]
+        #[serde(deserialize_with = "de_hex_string", serialize_with = "ser_hex_string")]
         hex_mixers_value: u8,
     },
     /// A source has been renamed.
@@ -387,36 +438,191 @@ pub enum EventType {
         /// The new enabled state of Studio Mode.
         new_state: bool,
     },
+
+    /// An event that failed to deserialize, e.g. due to an unknown update-type or a field with an unexpected value.
+    /// Only created by the connection, never deserialized directly.
+    /// Serialized as part of an `Event` in the shape it was received in, with the update-type and the other fields.
+    #[serde(skip)]
+    Unknown {
+        /// The update-type of the event.
+        update_type: String,
+        /// The other fields of the event.
+        data: Value,
+    },
 }
 
+// an event that is deserialized in two stages so that a single bad field doesn't drop the whole event
 #[derive(Deserialize, Debug, PartialEq)]
-#[serde(rename_all = "camelCase")]
-pub struct SceneItemTransform {
-    pub position: Position,
-    /// The clockwise rotation of the scene item in degrees around the point of alignment.
-    pub rotation: f64,
-    pub scale: Scale,
-    pub crop: Crop,
-    /// If the scene item is visible.
-    pub visible: bool,
-    /// If the scene item is locked in position.
-    pub locked: bool,
-    pub bounds: Bounds,
-    /// Base width (without scaling) of the source
-    pub source_width: i32,
-    /// Base source (without scaling) of the source
-    pub source_height: i32,
-    /// Scene item width (base source width multiplied by the horizontal scaling factor)
-    pub width: f64,
-    /// Scene item height (base source height multiplied by the vertical scaling factor)
-    pub height: f64,
-    /// Name of the item's parent (if this item belongs to a group)
-    pub parent_group_name: Option<String>,
-    /// List of children (if this item is a group)
-    pub group_children: Option<Vec<SceneItemTransform>>,
+pub(crate) struct RawEvent {
+    #[serde(rename = "update-type")]
+    pub(crate) update_type: String,
+    #[serde(flatten)]
+    pub(crate) data: serde_json::Map<String, Value>,
 }
 
-#[derive(Deserialize, Debug, PartialEq)]
+impl RawEvent {
+    // deserializes the event, falling back to EventType::Unknown if it doesn't match the known events
+    pub(crate) fn parse(self) -> Event {
+        let RawEvent {
+            update_type,
+            mut data,
+        } = self;
+        data.insert("update-type".to_string(), Value::from(update_type.as_str()));
+        let value = Value::Object(data);
+        match Event::deserialize(&value) {
+            Ok(event) => event,
+            Err(e) => {
+                log::warn!("Failed to deserialize {} event: {}", update_type, e);
+                let mut data = match value {
+                    Value::Object(data) => data,
+                    _ => unreachable!("the event is always an object"),
+                };
+                data.remove("update-type");
+                let mut timecode = |key: &str| match data.remove(key) {
+                    Some(Value::String(timecode)) => Some(timecode),
+                    _ => None,
+                };
+                Event {
+                    stream_timecode: timecode("stream-timecode"),
+                    rec_timecode: timecode("rec-timecode"),
+                    update_type: EventType::Unknown {
+                        update_type,
+                        data: Value::Object(data),
+                    },
+                }
+            }
+        }
+    }
+}
+
+/// The payload of a specific kind of event. Used with `Obs::events_of` to receive only that kind of event.
+pub trait EventPayload: Sized {
+    /// Returns the payload if the event is of the corresponding kind.
+    fn from_event(event: EventType) -> Option<Self>;
+}
+
+/// Payload of `EventType::SwitchScenes`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SwitchScenesEvent {
+    /// The new scene.
+    pub scene_name: String,
+    /// List of scene items in the new scene.
+    pub sources: Vec<SceneItem>,
+}
+
+impl SwitchScenesEvent {
+    /// All the scene items in the new scene, including the items in groups.
+    /// Each group is followed by its children.
+    pub fn all_sources(&self) -> Vec<&SceneItem> {
+        flatten_scene_items(&self.sources)
+    }
+}
+
+impl EventPayload for SwitchScenesEvent {
+    fn from_event(event: EventType) -> Option<Self> {
+        match event {
+            EventType::SwitchScenes {
+                scene_name,
+                sources,
+            } => Some(Self {
+                scene_name,
+                sources,
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// Payload of `EventType::SceneItemTransformChanged`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SceneItemTransformChangedEvent {
+    /// Name of the scene.
+    pub scene_name: String,
+    /// Name of the item in the scene.
+    pub item_name: String,
+    /// Scene item ID
+    pub item_id: i32,
+    /// Scene item transform properties
+    pub transform: SceneItemTransform,
+}
+
+impl EventPayload for SceneItemTransformChangedEvent {
+    fn from_event(event: EventType) -> Option<Self> {
+        match event {
+            EventType::SceneItemTransformChanged {
+                scene_name,
+                item_name,
+                item_id,
+                transform,
+            } => Some(Self {
+                scene_name,
+                item_name,
+                item_id,
+                transform,
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// Coalesces bursts of transform changes, e.g. from dragging an item in OBS, into the latest change per scene item.
+/// After a change is received, changes are collected for the given window, after which the latest change of each item
+/// is returned in the order the items first changed. Use with `Obs::events_of::<SceneItemTransformChangedEvent>()`.
+pub fn coalesce_transforms<S>(
+    transforms: S,
+    window: Duration,
+) -> impl Stream<Item = SceneItemTransformChangedEvent> + Send + Unpin
+where
+    S: Stream<Item = SceneItemTransformChangedEvent> + Send + 'static,
+{
+    struct State<S> {
+        transforms: S,
+        coalesced: VecDeque<SceneItemTransformChangedEvent>,
+        ended: bool,
+    }
+
+    let state = State {
+        transforms: transforms.boxed(),
+        coalesced: VecDeque::new(),
+        ended: false,
+    };
+    stream::unfold(state, move |mut state| async move {
+        if let Some(transform) = state.coalesced.pop_front() {
+            return Some((transform, state));
+        }
+        if state.ended {
+            return None;
+        }
+        let first = state.transforms.next().await?;
+        let mut coalesced = VecDeque::from(vec![first]);
+        let mut timer = Timer::after(window);
+        loop {
+            match future::select(state.transforms.next(), &mut timer).await {
+                Either::Left((Some(transform), _)) => {
+                    let same_item = coalesced.iter_mut().find(|coalesced| {
+                        coalesced.scene_name == transform.scene_name
+                            && coalesced.item_id == transform.item_id
+                    });
+                    match same_item {
+                        Some(same_item) => *same_item = transform,
+                        None => coalesced.push_back(transform),
+                    }
+                }
+                Either::Left((None, _)) => {
+                    state.ended = true;
+                    break;
+                }
+                Either::Right(_) => break,
+            }
+        }
+        let transform = coalesced.pop_front()?;
+        state.coalesced = coalesced;
+        Some((transform, state))
+    })
+    .boxed()
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Mixer {
     /// Mixer number
     pub id: i32,
@@ -424,7 +630,7 @@ pub struct Mixer {
     pub enabled: bool,
 }
 
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Filter {
     /// Filter name
     pub name: String,
@@ -435,7 +641,7 @@ pub struct Filter {
 }
 
 /// Scene item.
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "kebab-case")]
 pub struct EventSceneItem {
     /// Item source name
@@ -471,6 +677,34 @@ where
     d.deserialize_str(HexVisitor)
 }
 
+// used to serialize 255 => "0xFF"
+fn ser_hex_string<S>(value: &u8, s: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    s.serialize_str(&format!("0x{:X}", value))
+}
+
+// used to serialize EventType::Unknown as the event it was received as, so that it can be parsed again
+fn ser_event_type<S>(event_type: &EventType, s: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match event_type {
+        EventType::Unknown { update_type, data } => {
+            let mut map = s.serialize_map(None)?;
+            map.serialize_entry("update-type", update_type)?;
+            if let Value::Object(data) = data {
+                for (key, value) in data {
+                    map.serialize_entry(key, value)?;
+                }
+            }
+            map.end()
+        }
+        event_type => event_type.serialize(s),
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -515,4 +749,203 @@ mod test {
     }"#;
         let _soc: Event = serde_json::from_str(soc).unwrap();
     }
+
+    #[test]
+    fn round_trip() {
+        let text = r#"{
+            "stream-timecode": "00:01:00.000",
+            "scene-name": "Scene",
+            "sources": [
+                {
+                    "cx": 1848.0,
+                    "cy": 1016.0,
+                    "id": 2,
+                    "locked": false,
+                    "name": "asd",
+                    "render": true,
+                    "source_cx": 1848,
+                    "source_cy": 1016,
+                    "type": "xcomposite_input",
+                    "volume": 1.0,
+                    "x": 0.0,
+                    "y": 0.0
+                }
+            ],
+            "update-type": "SwitchScenes"
+        }"#;
+        let value: Value = serde_json::from_str(text).unwrap();
+        let event: Event = serde_json::from_value(value.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&event).unwrap(), value);
+
+        let mixers = serde_json::json!({
+            "sourceName": "Mic",
+            "mixers": [
+                { "id": 1, "enabled": true },
+                { "id": 2, "enabled": true },
+                { "id": 3, "enabled": true },
+                { "id": 4, "enabled": true },
+                { "id": 5, "enabled": true },
+                { "id": 6, "enabled": true }
+            ],
+            "hexMixersValue": "0xFF",
+            "update-type": "SourceAudioMixersChanged"
+        });
+        let event: Event = serde_json::from_value(mixers.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&event).unwrap(), mixers);
+    }
+
+    #[test]
+    fn source_kind() {
+        let event = |kind: &str| {
+            serde_json::json!({
+                "sourceName": "source",
+                "sourceType": "input",
+                "sourceKind": kind,
+                "update-type": "SourceDestroyed",
+            })
+        };
+
+        let known: Event = serde_json::from_value(event("ffmpeg_source")).unwrap();
+        match known.update_type {
+            EventType::SourceDestroyed { source_kind, .. } => {
+                assert_eq!(source_kind, SourceKind::MediaSource)
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+
+        let unknown: Event = serde_json::from_value(event("obs_shader_source")).unwrap();
+        assert_eq!(
+            serde_json::to_value(&unknown).unwrap(),
+            event("obs_shader_source")
+        );
+        match unknown.update_type {
+            EventType::SourceDestroyed { source_kind, .. } => assert_eq!(
+                source_kind,
+                SourceKind::Other("obs_shader_source".to_string())
+            ),
+            other => panic!("unexpected event {:?}", other),
+        }
+    }
+
+    #[test]
+    fn transition_kind() {
+        let begin: Event = serde_json::from_value(serde_json::json!({
+            "name": "Fade",
+            "type": "fade_transition",
+            "duration": 300,
+            "from-scene": "Scene 1",
+            "to-scene": "Scene 2",
+            "update-type": "TransitionBegin",
+        }))
+        .unwrap();
+        match begin.update_type {
+            EventType::TransitionBegin {
+                transition_type, ..
+            } => assert_eq!(transition_type, Some(TransitionKind::Fade)),
+            other => panic!("unexpected event {:?}", other),
+        }
+
+        let end: Event = serde_json::from_value(serde_json::json!({
+            "name": "Move",
+            "type": "move_transition",
+            "duration": 300,
+            "to-scene": "Scene 2",
+            "update-type": "TransitionEnd",
+        }))
+        .unwrap();
+        match end.update_type {
+            EventType::TransitionEnd {
+                transition_type, ..
+            } => assert_eq!(
+                transition_type,
+                Some(TransitionKind::Other("move_transition".to_string()))
+            ),
+            other => panic!("unexpected event {:?}", other),
+        }
+
+        // older servers don't send the type
+        let begin: Event = serde_json::from_value(serde_json::json!({
+            "name": "Stinger",
+            "duration": 300,
+            "from-scene": "Scene 1",
+            "to-scene": "Scene 2",
+            "update-type": "TransitionBegin",
+        }))
+        .unwrap();
+        match begin.update_type {
+            EventType::TransitionBegin {
+                transition_type, ..
+            } => assert_eq!(transition_type, None),
+            other => panic!("unexpected event {:?}", other),
+        }
+    }
+
+    #[test]
+    fn switch_scenes_all_sources() {
+        let item = |id: i32, name: &str| {
+            serde_json::json!({
+                "cx": 100.0,
+                "cy": 100.0,
+                "id": id,
+                "locked": false,
+                "name": name,
+                "render": true,
+                "type": "input",
+            })
+        };
+        let mut group = item(2, "Group");
+        group["groupChildren"] = serde_json::json!([item(3, "Camera"), item(4, "Overlay")]);
+        let event: Event = serde_json::from_value(serde_json::json!({
+            "update-type": "SwitchScenes",
+            "scene-name": "Scene",
+            "sources": [item(1, "Text"), group],
+        }))
+        .unwrap();
+        let event = SwitchScenesEvent::from_event(event.update_type).unwrap();
+        let names = event
+            .all_sources()
+            .into_iter()
+            .map(|item| item.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(event.sources.len(), 2);
+        assert_eq!(names, vec!["Text", "Group", "Camera", "Overlay"]);
+    }
+
+    #[test]
+    fn coalesce_transforms() {
+        let transform_changed = |item_id: i32, rotation: f64| {
+            let event: Event = serde_json::from_value(serde_json::json!({
+                "update-type": "SceneItemTransformChanged",
+                "scene-name": "Scene",
+                "item-name": "Camera",
+                "item-id": item_id,
+                "transform": {
+                    "position": { "x": 0.0, "y": 0.0, "alignment": 5 },
+                    "rotation": rotation,
+                    "scale": { "x": 1.0, "y": 1.0 },
+                    "crop": { "top": 0, "right": 0, "bottom": 0, "left": 0 },
+                    "visible": true,
+                    "locked": false,
+                    "bounds": { "type": "OBS_BOUNDS_NONE", "alignment": 0, "x": 0.0, "y": 0.0 },
+                    "sourceWidth": 1920,
+                    "sourceHeight": 1080,
+                    "width": 1920.0,
+                    "height": 1080.0,
+                },
+            }))
+            .unwrap();
+            SceneItemTransformChangedEvent::from_event(event.update_type).unwrap()
+        };
+        let transforms = stream::iter(vec![
+            transform_changed(1, 10.0),
+            transform_changed(1, 20.0),
+            transform_changed(1, 30.0),
+        ]);
+        let coalesced = smol::block_on(
+            super::coalesce_transforms(transforms, Duration::from_millis(50)).collect::<Vec<_>>(),
+        );
+        assert_eq!(coalesced.len(), 1);
+        assert_eq!(coalesced[0].item_id, 1);
+        assert_eq!(coalesced[0].transform.rotation, 30.0);
+    }
 }
@@ -0,0 +1,150 @@
+//! Stream health classification, see `StreamHealth`.
+
+use crate::{
+    events::EventType,
+    responses::{CongestionLevel, Output},
+};
+
+/// Dropped frame ratio from which the stream health is `HealthStatus::Warning`.
+const WARNING_DROPPED_FRAME_RATIO: f64 = 0.01;
+/// Dropped frame ratio from which the stream health is `HealthStatus::Critical`.
+const CRITICAL_DROPPED_FRAME_RATIO: f64 = 0.05;
+
+/// The health of a stream, e.g. for rendering a colored indicator in an overlay.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StreamHealth {
+    /// Congestion of the stream output from 0.0 to 1.0, reported as the strain in `StreamStatus` events.
+    pub congestion: f64,
+    /// Ratio of dropped frames to the total number of frames. 0 if no frames have been sent.
+    pub dropped_frame_ratio: f64,
+}
+
+/// Classification of the health of a stream, ordered from best to worst.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum HealthStatus {
+    /// Congestion below 0.1 and less than 1% of frames dropped.
+    Good,
+    /// Congestion from 0.1 to below 0.5 or from 1% to below 5% of frames dropped.
+    Warning,
+    /// Congestion of 0.5 or above or 5% or more of frames dropped.
+    Critical,
+}
+
+impl StreamHealth {
+    /// Computes the health from a `StreamStatus` event. Returns `None` for other events.
+    pub fn from_stream_status(event: &EventType) -> Option<Self> {
+        match event {
+            EventType::StreamStatus {
+                strain,
+                num_total_frames,
+                num_dropped_frames,
+                ..
+            } => Some(Self {
+                congestion: *strain,
+                dropped_frame_ratio: ratio(*num_dropped_frames, *num_total_frames),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Computes the health from an output, e.g. the stream output from `ListOutputs`.
+    pub fn from_output(output: &Output) -> Self {
+        Self {
+            congestion: output.congestion,
+            dropped_frame_ratio: output.dropped_frame_ratio(),
+        }
+    }
+
+    /// Classifies the health using the thresholds documented on `HealthStatus`.
+    /// The worse of the congestion and the dropped frame ratio determines the status.
+    pub fn status(&self) -> HealthStatus {
+        let congestion = match CongestionLevel::from_congestion(self.congestion) {
+            CongestionLevel::Low => HealthStatus::Good,
+            CongestionLevel::Medium => HealthStatus::Warning,
+            CongestionLevel::High => HealthStatus::Critical,
+        };
+        let dropped_frames = if self.dropped_frame_ratio >= CRITICAL_DROPPED_FRAME_RATIO {
+            HealthStatus::Critical
+        } else if self.dropped_frame_ratio >= WARNING_DROPPED_FRAME_RATIO {
+            HealthStatus::Warning
+        } else {
+            HealthStatus::Good
+        };
+        congestion.max(dropped_frames)
+    }
+}
+
+// ratio of the part to the total, 0 if the total is 0
+fn ratio(part: i32, total: i32) -> f64 {
+    if total > 0 {
+        f64::from(part) / f64::from(total)
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn health(congestion: f64, dropped_frame_ratio: f64) -> HealthStatus {
+        StreamHealth {
+            congestion,
+            dropped_frame_ratio,
+        }
+        .status()
+    }
+
+    #[test]
+    fn congestion_thresholds() {
+        assert_eq!(health(0.0, 0.0), HealthStatus::Good);
+        assert_eq!(health(0.09, 0.0), HealthStatus::Good);
+        assert_eq!(health(0.1, 0.0), HealthStatus::Warning);
+        assert_eq!(health(0.49, 0.0), HealthStatus::Warning);
+        assert_eq!(health(0.5, 0.0), HealthStatus::Critical);
+    }
+
+    #[test]
+    fn dropped_frame_thresholds() {
+        assert_eq!(health(0.0, 0.009), HealthStatus::Good);
+        assert_eq!(health(0.0, 0.01), HealthStatus::Warning);
+        assert_eq!(health(0.0, 0.049), HealthStatus::Warning);
+        assert_eq!(health(0.0, 0.05), HealthStatus::Critical);
+        // the worse of the two wins
+        assert_eq!(health(0.1, 0.05), HealthStatus::Critical);
+    }
+
+    #[test]
+    fn from_stream_status() {
+        let event: crate::Event = serde_json::from_value(serde_json::json!({
+            "update-type": "StreamStatus",
+            "streaming": true,
+            "recording": false,
+            "replay-buffer-active": false,
+            "bytes-per-sec": 0,
+            "kbits-per-sec": 0,
+            "strain": 0.2,
+            "total-stream-time": 60,
+            "num-total-frames": 1000,
+            "num-dropped-frames": 10,
+            "fps": 60.0,
+            "render-total-frames": 0,
+            "render-missed-frames": 0,
+            "output-total-frames": 0,
+            "output-skipped-frames": 0,
+            "average-frame-time": 0,
+            "cpu-usage": 0.0,
+            "memory-usage": 0.0,
+            "free-disk-space": 0.0,
+        }))
+        .unwrap();
+        let health = StreamHealth::from_stream_status(&event.update_type).unwrap();
+        assert_eq!(health.congestion, 0.2);
+        assert_eq!(health.dropped_frame_ratio, 0.01);
+        assert_eq!(health.status(), HealthStatus::Warning);
+        assert_eq!(
+            StreamHealth::from_stream_status(&EventType::StreamStarted),
+            None
+        );
+    }
+}
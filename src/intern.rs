@@ -0,0 +1,26 @@
+//! Interning of scene and source names, see `Obs::scene_names`.
+
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex},
+};
+
+// deduplicates names so that repeatedly fetched names share the same allocation
+// names are never removed, which is fine for the relatively small and stable set of names in OBS
+#[derive(Debug, Default)]
+pub(crate) struct Interner {
+    names: Mutex<HashSet<Arc<str>>>,
+}
+
+impl Interner {
+    // returns the shared copy of the name, adding it to the cache if it's not there yet
+    pub(crate) fn intern(&self, name: &str) -> Arc<str> {
+        let mut names = self.names.lock().unwrap();
+        if let Some(interned) = names.get(name) {
+            return interned.clone();
+        }
+        let interned: Arc<str> = Arc::from(name);
+        names.insert(interned.clone());
+        interned
+    }
+}
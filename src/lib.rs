@@ -1,14 +1,41 @@
 //! Rust API for the obs-websocket plugin
 
+pub mod animation;
 pub mod common_types;
 pub mod events;
 pub mod requests;
 pub mod responses;
+pub mod testing;
 
+mod audit;
+mod auth_gate;
+mod compatibility;
+mod config;
+mod default_scene;
+mod diagnostics;
 mod error;
+mod health;
+mod intern;
+mod liveness;
 mod obs;
+mod pool;
+mod scene_names;
+mod status;
+mod transport;
+mod volume;
 
+pub use compatibility::{Compatibility, SUPPORTED_OBS_WEBSOCKET_VERSION};
+pub use config::{
+    Interceptor, ObsConfig, PasswordProvider, RequestCompleteCallback, SceneNameMapper,
+};
+pub use default_scene::DefaultScene;
+pub use diagnostics::Diagnostics;
 pub use error::ObsError;
 pub use events::{Event, EventType};
 pub use futures;
+pub use health::{HealthStatus, StreamHealth};
+pub use liveness::{Liveness, LivenessSource};
 pub use obs::Obs;
+pub use pool::ObsPool;
+pub use transport::Transport;
+pub use volume::VolumeController;
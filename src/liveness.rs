@@ -0,0 +1,19 @@
+//! Liveness of the connection to OBS, see `Obs::liveness_stream`.
+
+/// Whether OBS was responsive at a point in time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Liveness {
+    /// Whether OBS was alive.
+    pub alive: bool,
+    /// How the liveness was determined.
+    pub source: LivenessSource,
+}
+
+/// How liveness was determined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LivenessSource {
+    /// A Heartbeat event was received.
+    Heartbeat,
+    /// No Heartbeat event was received in time, so a GetVersion request was sent.
+    Poll,
+}
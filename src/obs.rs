@@ -1,14 +1,30 @@
 //! Contains Obs, the primary struct for interacting with the OBS WebSocket server.
 
 use crate::{
+    animation::Animation,
+    audit::AuditLog,
+    auth_gate::AuthGate,
+    common_types::{
+        alignment, flatten_scene_items, BoundsType, OutputKind, SceneItem, SceneItemType,
+    },
+    compatibility::Compatibility,
+    config::ObsConfig,
+    default_scene::DefaultScene,
+    diagnostics::Diagnostics,
     error::{HandlerError, ObsError},
-    events::{self, Event},
+    events::{self, Event, EventPayload},
+    intern::Interner,
+    liveness::{Liveness, LivenessSource},
     requests::*,
-    responses,
+    responses::{self, deserialize_response},
+    scene_names::map_scene_names,
+    status::StatusTracker,
+    transport::Transport,
+    volume::{VolumeController, VolumeState},
 };
 
 use async_tungstenite::{
-    tungstenite::{protocol::Role, Message as WebSocketMessage},
+    tungstenite::{self, protocol::Role, Message as WebSocketMessage},
     WebSocketStream,
 };
 use futures::{
@@ -16,28 +32,46 @@ use futures::{
         mpsc::{self, UnboundedReceiver, UnboundedSender},
         oneshot::{self, Sender as OneshotSender},
     },
-    future::{self, Either},
+    future::{self, Either, Future, FutureExt},
     sink::SinkExt,
-    stream::StreamExt,
+    stream::{self, Stream, StreamExt},
 };
 use piper::Arc;
 use serde::Deserialize;
 use serde_json::Value;
 use sha2::{Digest, Sha256};
-use smol::{Async, Timer};
+use smol::{lock::Semaphore, Async, Timer};
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
+    io,
     net::{TcpStream, ToSocketAddrs},
+    panic,
+    sync::{
+        self,
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
     thread::{self, JoinHandle},
-    time::Duration,
+    time::{Duration, Instant},
 };
 
-type WebSocketHandle = WebSocketStream<Arc<Async<TcpStream>>>;
+// how many times waiting for the socket to be ready for sending is attempted in case of an interrupted flush
+const SEND_ATTEMPTS: u32 = 3;
+const SEND_RETRY_DELAY: Duration = Duration::from_millis(10);
+
+type WebSocketHandle = WebSocketStream<Box<dyn Transport>>;
 type HandlerHandle = JoinHandle<Result<(), HandlerError>>;
 
 /// The primary struct for interacting with the OBS WebSocket server.
 pub struct Obs {
-    connection_data: ConnectionData,
+    // shared with the health check, which replaces it when reconnecting, see ObsConfig::health_check_interval
+    connection_data: sync::Arc<Mutex<Option<ConnectionData>>>,
+    shared: sync::Arc<SharedState>,
+    // address and port for reconnecting, None if connected with a custom transport
+    address: Option<(String, u16)>,
+    // set by close, after which reconnecting is not allowed
+    // connection_data alone can't tell this apart from a failed reconnect
+    closed: bool,
 }
 
 impl Obs {
@@ -47,39 +81,253 @@ impl Obs {
         address: &str,
         port: u16,
     ) -> Result<(Self, UnboundedReceiver<events::Event>), ObsError> {
+        Obs::connect_with_config(address, port, ObsConfig::default()).await
+    }
+
+    /// Attempts to connect to OBS without receiving events. Events are dropped without deserializing them
+    /// unless they're needed, e.g. by `subscribe` or `enable_status_tracking`.
+    /// Returns an error if already connected.
+    pub async fn connect_no_events(address: &str, port: u16) -> Result<Self, ObsError> {
+        // the events are only dropped once the receiver is dropped
+        let (obs, _events) = Obs::connect(address, port).await?;
+        Ok(obs)
+    }
+
+    /// Attempts to connect to OBS using a URL such as `ws://localhost:4444`. The port defaults to 4444.
+    /// Note that TLS is not supported, so `wss://` URLs return an error.
+    pub async fn connect_url(
+        url: &str,
+    ) -> Result<(Self, UnboundedReceiver<events::Event>), ObsError> {
+        let url = ObsUrl::parse(url)?;
+        if url.tls {
+            return Err(ObsError::TlsNotSupported);
+        }
+        Obs::connect(&url.host, url.port).await
+    }
+
+    /// Attempts to connect to OBS using the given configuration.
+    pub async fn connect_with_config(
+        address: &str,
+        port: u16,
+        config: ObsConfig,
+    ) -> Result<(Self, UnboundedReceiver<events::Event>), ObsError> {
+        let (tcp_stream, ws_addr) = Obs::connect_tcp(address, port).await?;
+        Obs::connect_with_address(
+            Box::new(Arc::new(tcp_stream)),
+            &ws_addr,
+            config,
+            Some((address.to_string(), port)),
+        )
+        .await
+    }
+
+    // connects a TCP stream to OBS, returns the stream and the URL for the WebSocket handshake
+    async fn connect_tcp(address: &str, port: u16) -> Result<(Async<TcpStream>, String), ObsError> {
         log::debug!("Connecting to: {}:{}", address, port);
+        let addr = format!("{}:{}", address, port);
+        let ws_addr = format!("ws://{}", addr);
 
-        let (thread_sender, thread_receiver) = mpsc::unbounded::<Message>();
+        // parse addr
+        let addr = addr
+            .to_socket_addrs()
+            .ok()
+            .and_then(|mut sa| sa.next())
+            .ok_or_else(|| ObsError::InvalidAddress(addr.clone()))?;
+        log::debug!("Connecting TCP stream to: {}", addr);
+
+        // connect to OBS
+        let tcp_stream = Async::<TcpStream>::connect(addr).await?;
+        Ok((tcp_stream, ws_addr))
+    }
+
+    /// Attempts to connect to OBS over the given transport, which should already be connected.
+    /// The URL is used for the WebSocket handshake, e.g. `ws://localhost:4444`.
+    pub async fn connect_with_transport<T: Transport>(
+        transport: T,
+        url: &str,
+        config: ObsConfig,
+    ) -> Result<(Self, UnboundedReceiver<events::Event>), ObsError> {
+        Obs::connect_with_address(Box::new(transport), url, config, None).await
+    }
+
+    // connects over the transport, the address is used for reconnecting and is None for custom transports
+    async fn connect_with_address(
+        transport: Box<dyn Transport>,
+        url: &str,
+        config: ObsConfig,
+        address: Option<(String, u16)>,
+    ) -> Result<(Self, UnboundedReceiver<events::Event>), ObsError> {
+        let audit_log = match &config.audit_log {
+            Some(path) => Some(AuditLog::open(path)?),
+            None => None,
+        };
+        let request_limiter = config
+            .max_concurrent_requests
+            .map(|max| Semaphore::new(max.max(1)));
+        let shared = sync::Arc::new(SharedState {
+            config,
+            audit_log,
+            request_limiter,
+            ..SharedState::default()
+        });
+        let connection = sync::Arc::new(Mutex::new(None));
+        let reconnect = address.clone().map(|address| AutoReconnect {
+            address,
+            connection: sync::Arc::downgrade(&connection),
+        });
+        let (event_sender, event_receiver) = mpsc::unbounded::<Event>();
+        let connection_data =
+            Obs::start_connection(transport, url, &shared, event_sender, reconnect).await?;
+        *connection.lock().unwrap() = Some(connection_data);
+        let obs = Obs {
+            connection_data: connection,
+            shared,
+            address,
+            closed: false,
+        };
+        Ok((obs, event_receiver))
+    }
+
+    /// Reconnects to OBS, e.g. after the connection was lost. Only works for connections to an address,
+    /// connections with a custom transport should use `reconnect_with_transport`.
+    /// See `reconnect_with_transport` for what is preserved.
+    /// Returns an error if the connection was closed with `close`.
+    pub async fn reconnect(&mut self) -> Result<UnboundedReceiver<events::Event>, ObsError> {
+        if self.closed {
+            return Err(ObsError::NotConnected);
+        }
+        let (address, port) = self.address.clone().ok_or(ObsError::NotConnected)?;
+        let (tcp_stream, ws_addr) = Obs::connect_tcp(&address, port).await?;
+        self.reconnect_with_transport(Arc::new(tcp_stream), &ws_addr)
+            .await
+    }
+
+    /// Closes the current connection if it's still open and reconnects to OBS over the given transport.
+    /// The receivers created with `subscribe` and status tracking are preserved, and the tracked status is
+    /// requested again since events may have been missed. Authentication has to be repeated, and with
+    /// `ObsConfig::defer_until_authenticated` requests wait for it again.
+    /// Like when connecting, a new event receiver is returned. The previous one ends with the previous connection.
+    /// Returns an error if the connection was closed with `close`.
+    /// If reconnecting fails, requests return `ObsError::NotConnected` until reconnecting succeeds.
+    /// If requesting the tracked status fails, it's logged and the status is updated from events only.
+    pub async fn reconnect_with_transport<T: Transport>(
+        &mut self,
+        transport: T,
+        url: &str,
+    ) -> Result<UnboundedReceiver<events::Event>, ObsError> {
+        if self.closed {
+            return Err(ObsError::NotConnected);
+        }
+        log::info!("Reconnecting");
+        // None if a previous reconnect failed
+        let old = self.connection_data.lock().unwrap().take();
+        if let Some(ConnectionData {
+            thread_sender,
+            mut socket_handle,
+            thread_handle,
+        }) = old
+        {
+            // the old connection is usually already lost, so errors while closing it are expected
+            thread_sender.close_channel();
+            let _ = thread_handle.join();
+            let _ = socket_handle.close(None).await;
+        }
+        self.shared.reset_connection_state();
+
+        let reconnect = self.address.clone().map(|address| AutoReconnect {
+            address,
+            connection: sync::Arc::downgrade(&self.connection_data),
+        });
         let (event_sender, event_receiver) = mpsc::unbounded::<Event>();
+        let connection_data = Obs::start_connection(
+            Box::new(transport),
+            url,
+            &self.shared,
+            event_sender,
+            reconnect,
+        )
+        .await?;
+        *self.connection_data.lock().unwrap() = Some(connection_data);
+        if self.shared.status.is_enabled() {
+            if let Err(e) = self.enable_status_tracking().await {
+                log::warn!("Failed to request the status after reconnecting: {}", e);
+            }
+        }
+        Ok(event_receiver)
+    }
+
+    // initializes the WebSocket connection and starts the threads for it
+    // the events received over the connection are sent with the event sender
+    async fn start_connection(
+        transport: Box<dyn Transport>,
+        url: &str,
+        shared: &sync::Arc<SharedState>,
+        event_sender: UnboundedSender<Event>,
+        reconnect: Option<AutoReconnect>,
+    ) -> Result<ConnectionData, ObsError> {
+        let (thread_sender, thread_receiver) = mpsc::unbounded::<Message>();
         let (websocket_stream, send_socket, close_handle) =
-            Obs::init_sockets(address, port).await?;
-        let thread_handle =
-            Obs::start_handler(send_socket, thread_receiver, websocket_stream, event_sender)
-                .map_err(ObsError::Thread)?;
+            Obs::init_sockets(transport, url, &shared.config).await?;
+        if let Some(interval) = shared.config.health_check_interval {
+            // not joined, it stops by itself within an interval after the connection is closed
+            Obs::start_health_check(
+                thread_sender.clone(),
+                event_sender.clone(),
+                shared.clone(),
+                interval,
+                reconnect,
+            )
+            .map_err(ObsError::Thread)?;
+        }
+        let thread_handle = Obs::start_handler(
+            send_socket,
+            thread_receiver,
+            websocket_stream,
+            event_sender,
+            shared.clone(),
+        )
+        .map_err(ObsError::Thread)?;
 
         let connection_data = ConnectionData {
             socket_handle: close_handle,
             thread_handle,
             thread_sender,
         };
-        Ok((Obs { connection_data }, event_receiver))
+        Ok(connection_data)
     }
 
     /// Disconnects from OBS.
     /// Returns an error if not connected, or if there was an issue closing the WebSocket socket
     /// or closing the thread.
-    pub async fn disconnect(self) -> Result<(), ObsError> {
+    pub async fn disconnect(mut self) -> Result<(), ObsError> {
+        self.close().await
+    }
+
+    /// Disconnects from OBS without consuming `self`. Afterwards, requests return `ObsError::NotConnected`.
+    /// Returns an error if not connected, or if there was an issue closing the WebSocket socket
+    /// or closing the thread.
+    pub async fn close(&mut self) -> Result<(), ObsError> {
         let ConnectionData {
             thread_sender,
             mut socket_handle,
             thread_handle,
-        } = self.connection_data;
+        } = self
+            .connection_data
+            .lock()
+            .unwrap()
+            .take()
+            .ok_or(ObsError::NotConnected)?;
+        self.closed = true;
+        // dropping the subscribers' senders ends their streams
+        self.shared.event_subscribers.lock().unwrap().clear();
 
         log::info!("Closing connection");
         // closing thread sender should close the thread
+        // the thread is joined before closing the socket so that the close frame can't be written in the middle of
+        // a request the thread is still sending
         thread_sender.close_channel();
-        let socket_res = socket_handle.close(None).await;
         let thread_res = thread_handle.join();
+        let socket_res = socket_handle.close(None).await;
 
         if socket_res.is_err() || thread_res.is_err() {
             return Err(ObsError::DisconnectError {
@@ -92,23 +340,211 @@ impl Obs {
         Ok(())
     }
 
+    // returns the sender for the handler thread of the current connection
+    fn thread_sender(&self) -> Result<UnboundedSender<Message>, ObsError> {
+        self.connection_data
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|data| data.thread_sender.clone())
+            .ok_or(ObsError::NotConnected)
+    }
+
+    /// Returns false if the connection to OBS has been closed or lost.
+    pub fn is_connected(&self) -> bool {
+        matches!(&*self.connection_data.lock().unwrap(), Some(data) if !data.thread_sender.is_closed())
+    }
+
+    /// Returns the number of responses received to requests that were never sent or were already answered.
+    /// See `ObsConfig::orphan_response_threshold`.
+    pub fn orphan_response_count(&self) -> usize {
+        self.shared.unexpected_responses.load(Ordering::Relaxed)
+    }
+
+    /// Returns the paths of the files saved by OBS during this session, in order, as reported by `RecordingFileChanged` events,
+    /// e.g. the clips saved with `SaveReplayBuffer`. OBS also reports new recording files this way, which are included as well.
+    pub fn saved_replays(&self) -> Vec<String> {
+        self.shared.saved_files.lock().unwrap().clone()
+    }
+
+    /// Returns a new receiver for events from OBS.
+    /// Each receiver gets its own copy of every event, including the receiver returned when connecting.
+    pub fn subscribe(&self) -> UnboundedReceiver<Event> {
+        let (sender, receiver) = mpsc::unbounded();
+        self.shared.event_subscribers.lock().unwrap().push(sender);
+        receiver
+    }
+
+    /// Returns a stream of the payloads of a specific kind of event, e.g. `obs.events_of::<SwitchScenesEvent>()`.
+    /// The stream is `Send + Unpin` so that it can be moved to other tasks and combined with e.g. `select`.
+    pub fn events_of<T: EventPayload + Send>(&self) -> impl Stream<Item = T> + Send + Unpin {
+        self.subscribe()
+            .filter_map(|event| future::ready(T::from_event(event.update_type)))
+    }
+
+    /// Starts keeping track of whether OBS is streaming, recording or has the replay buffer active,
+    /// which can then be checked with `is_streaming`, `is_recording`, `is_recording_paused` and `is_replay_active`.
+    /// The initial status is fetched with GetStreamingStatus, after which it's updated as events are received.
+    /// The replay buffer is assumed to be inactive until a ReplayStarted event is received.
+    pub async fn enable_status_tracking(&self) -> Result<(), ObsError> {
+        self.shared.status.enable();
+        let status = self.request(&GetStreamingStatus::builder().build()).await?;
+        self.shared.status.seed(&status);
+        Ok(())
+    }
+
+    /// Returns true if OBS is streaming. Requires `enable_status_tracking`, returns false otherwise.
+    pub fn is_streaming(&self) -> bool {
+        self.shared.status.is_streaming()
+    }
+
+    /// Returns true if OBS is recording. Requires `enable_status_tracking`, returns false otherwise.
+    pub fn is_recording(&self) -> bool {
+        self.shared.status.is_recording()
+    }
+
+    /// Returns true if the recording is paused. Requires `enable_status_tracking`, returns false otherwise.
+    pub fn is_recording_paused(&self) -> bool {
+        self.shared.status.is_recording_paused()
+    }
+
+    /// Returns true if the replay buffer is active. Requires `enable_status_tracking`, returns false otherwise.
+    pub fn is_replay_active(&self) -> bool {
+        self.shared.status.is_replay_active()
+    }
+
     /// Sends the given request to OBS.
     pub async fn request<T>(&self, req: &T) -> Result<T::Response, ObsError>
     where
         T: Request + std::fmt::Debug,
     {
-        let ConnectionData { thread_sender, .. } = &self.connection_data;
+        self.request_with(req, |_| {}).await
+    }
+
+    // like request, but modifies the JSON of the request with the given function before sending it
+    pub(crate) async fn request_with<T, F>(
+        &self,
+        req: &T,
+        modify: F,
+    ) -> Result<T::Response, ObsError>
+    where
+        T: Request + std::fmt::Debug,
+        F: FnOnce(&mut Value),
+    {
+        let thread_sender = self.thread_sender()?;
+        if self.shared.config.defer_until_authenticated {
+            self.shared.auth_gate.wait(T::REQUEST_TYPE).await;
+        }
+        // held until the response is received, see ObsConfig::max_concurrent_requests
+        let _permit = match &self.shared.request_limiter {
+            Some(limiter) => Some(limiter.acquire().await),
+            None => None,
+        };
+        Obs::send_request_with(
+            &thread_sender,
+            &self.shared.config.message_id_prefix,
+            req,
+            modify,
+        )
+        .await
+    }
+
+    /// Returns a wrapper whose requests default to the given scene instead of the current scene.
+    /// Useful for tools that operate on a fixed scene. See `DefaultScene`.
+    pub fn with_default_scene(&self, scene_name: &str) -> DefaultScene<'_> {
+        DefaultScene::new(self, scene_name)
+    }
+
+    /// Checks that OBS supports the given request according to the available requests reported by GetVersion.
+    /// Returns `ObsError::Unsupported` if not. GetVersion is only requested once per connection and then cached.
+    /// If the GetVersion response can't be parsed, e.g. because the server is not obs-websocket 4.x,
+    /// the available requests are unknown and every request is assumed to be supported.
+    pub async fn require_request<T: Request>(&self) -> Result<(), ObsError> {
+        let version = match self.cached_version().await? {
+            ServerVersion::Known(version) => version,
+            ServerVersion::Unknown => return Ok(()),
+        };
+        if version
+            .available_requests
+            .iter()
+            .any(|request_type| request_type == T::REQUEST_TYPE)
+        {
+            Ok(())
+        } else {
+            Err(ObsError::Unsupported {
+                request_type: T::REQUEST_TYPE.to_string(),
+                obs_websocket_version: version.obs_websocket_version,
+            })
+        }
+    }
+
+    /// Compares the obs-websocket version of the server to `SUPPORTED_OBS_WEBSOCKET_VERSION`, e.g. for warning users.
+    /// Uses the cached GetVersion response like `require_request`.
+    /// Returns `ObsError::UnknownServerVersion` if the version can't be determined.
+    pub async fn check_compatibility(&self) -> Result<Compatibility, ObsError> {
+        match self.cached_version().await? {
+            ServerVersion::Known(version) => Compatibility::check(&version.obs_websocket_version)
+                .ok_or(ObsError::UnknownServerVersion),
+            ServerVersion::Unknown => Err(ObsError::UnknownServerVersion),
+        }
+    }
+
+    // returns the cached GetVersion response, requesting it if it's not cached yet
+    async fn cached_version(&self) -> Result<ServerVersion, ObsError> {
+        let cached = self.shared.version.lock().unwrap().clone();
+        if let Some(version) = cached {
+            return Ok(version);
+        }
+        let version = match self.request(&GetVersion::builder().build()).await {
+            Ok(version) => ServerVersion::Known(version),
+            Err(e @ ObsError::InvalidResponse { .. })
+            | Err(e @ ObsError::MalformedResponse { .. }) => {
+                log::warn!("Failed to parse the version of the server: {}", e);
+                ServerVersion::Unknown
+            }
+            Err(e) => return Err(e),
+        };
+        *self.shared.version.lock().unwrap() = Some(version.clone());
+        Ok(version)
+    }
+
+    // sends the request to the handler thread and waits for the response
+    async fn send_request<T>(
+        thread_sender: &UnboundedSender<Message>,
+        message_id_prefix: &str,
+        req: &T,
+    ) -> Result<T::Response, ObsError>
+    where
+        T: Request + std::fmt::Debug,
+    {
+        Obs::send_request_with(thread_sender, message_id_prefix, req, |_| {}).await
+    }
+
+    // like send_request, but modifies the JSON of the request with the given function before sending it
+    async fn send_request_with<T, F>(
+        thread_sender: &UnboundedSender<Message>,
+        message_id_prefix: &str,
+        req: &T,
+        modify: F,
+    ) -> Result<T::Response, ObsError>
+    where
+        T: Request + std::fmt::Debug,
+        F: FnOnce(&mut Value),
+    {
         log::debug!("Requesting: {:#?}", req);
-        let (message_id, value) = req.to_json();
+        let (message_id, mut value) = req.to_json(message_id_prefix);
+        modify(&mut value);
         log::trace!("Converted request to JSON: {:#}", value);
 
         // channel for receiving the response
-        let (oneshot_sender, oneshot_receiver) = oneshot::channel::<Result<Value, String>>();
+        let (oneshot_sender, oneshot_receiver) = oneshot::channel::<Result<Value, ObsError>>();
 
         // send to handler thread
         let message = Message {
-            message_id,
+            message_id: Some(message_id.clone()),
+            request_type: T::REQUEST_TYPE.to_string(),
             value,
+            raw_text: None,
             sender: oneshot_sender,
         };
         log::trace!("Sending");
@@ -122,11 +558,11 @@ impl Obs {
             Ok(res) => match res {
                 Ok(res) => {
                     log::debug!("Received response: {}", res);
-                    Ok(serde_json::from_value(res)?)
+                    deserialize_response::<T>(message_id, &res)
                 }
-                Err(res) => {
-                    log::error!("Received error: {:#?}", res);
-                    Err(ObsError::ObsError(res))
+                Err(err) => {
+                    log::error!("Received error: {}", err);
+                    Err(err)
                 }
             },
             Err(canceled) => {
@@ -136,8 +572,77 @@ impl Obs {
         }
     }
 
+    /// Sends the given text to OBS as is, which can be useful for debugging or trying out requests that
+    /// are not supported by this crate. If the text is a JSON object with a `message-id`, the response
+    /// with the same message-id is returned if it's received within the timeout. Otherwise `None` is returned.
+    ///
+    /// The text is not validated in any way, so sending invalid requests or reusing the message-ids of
+    /// other requests may cause OBS to close the connection or cause other requests to receive the wrong response.
+    /// Text that is not valid JSON is also sent as is, and `None` is returned for it since it has no message-id.
+    /// Interceptors are not called for the text.
+    pub async fn send_raw_text(
+        &self,
+        text: String,
+        timeout: Duration,
+    ) -> Result<Option<Value>, ObsError> {
+        let thread_sender = self.thread_sender()?;
+        // invalid JSON is sent anyway, see above
+        let value = serde_json::from_str::<Value>(&text).unwrap_or(Value::Null);
+        let message_id = value["message-id"].as_str().map(str::to_string);
+        let request_type = value["request-type"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string();
+
+        let (oneshot_sender, oneshot_receiver) = oneshot::channel::<Result<Value, ObsError>>();
+        let message = Message {
+            message_id: message_id.clone(),
+            request_type,
+            value,
+            raw_text: Some(text),
+            sender: oneshot_sender,
+        };
+        thread_sender
+            .unbounded_send(message)
+            .map_err(|_| ObsError::ConnectionInterrupted)?;
+        if message_id.is_none() {
+            return Ok(None);
+        }
+
+        match future::select(oneshot_receiver, Timer::after(timeout)).await {
+            Either::Left((Ok(res), _)) => res.map(Some),
+            Either::Left((Err(canceled), _)) => Err(ObsError::OneshotCanceled(canceled)),
+            Either::Right(_) => Ok(None),
+        }
+    }
+
     /// Tries to authenticate with OBS. Returns an error if no authentication is required.
-    pub async fn authenticate(&mut self, password: &str) -> Result<responses::Empty, ObsError> {
+    /// Returns `ObsError::AuthenticationFailed` if the password is wrong. If a `PasswordProvider` is configured,
+    /// it's asked for a new password and authentication is retried once before giving up.
+    /// Requests deferred with `ObsConfig::defer_until_authenticated` are sent once this returns, even if it fails.
+    pub async fn authenticate(&self, password: &str) -> Result<responses::Empty, ObsError> {
+        let result = self.authenticate_with_retry(password).await;
+        self.shared.auth_gate.open();
+        result
+    }
+
+    // authenticates with the given password, retrying with the password provider if it's wrong
+    async fn authenticate_with_retry(&self, password: &str) -> Result<responses::Empty, ObsError> {
+        match self.try_authenticate(password).await {
+            Err(ObsError::AuthenticationFailed) => match &self.shared.config.password_provider {
+                Some(provider) => {
+                    log::info!("Authentication failed, retrying with a new password");
+                    let password = provider.password();
+                    self.try_authenticate(&password).await
+                }
+                None => Err(ObsError::AuthenticationFailed),
+            },
+            res => res,
+        }
+    }
+
+    // authenticates with the given password
+    async fn try_authenticate(&self, password: &str) -> Result<responses::Empty, ObsError> {
         let auth = self.request(&GetAuthRequired::builder().build()).await?;
         if auth.auth_required {
             log::debug!("Authentication required");
@@ -153,760 +658,6006 @@ impl Obs {
             let auth_response = base64::encode(&auth_response_hash);
             log::info!("Authenticating");
             let req = Authenticate::builder().auth(auth_response).build();
-            Ok(self.request(&req).await?)
+            match self.request(&req).await {
+                // OBS responds with an error message if the password is wrong
+                Err(ObsError::ObsError(message)) => {
+                    log::warn!("Authentication failed: {}", message);
+                    Err(ObsError::AuthenticationFailed)
+                }
+                res => res,
+            }
         } else {
             Err(ObsError::NoAuthRequired)
         }
     }
 
-    // initializes the connection to OBS WebSocket
-    async fn init_sockets(
-        address: &str,
-        port: u16,
-    ) -> Result<(WebSocketHandle, WebSocketHandle, WebSocketHandle), ObsError> {
-        let addr = format!("{}:{}", address, port);
-        let ws_addr = format!("ws://{}", addr);
+    /// Fetches the version, stats, video info and streaming status of OBS, e.g. for attaching to bug reports.
+    /// The requests are sent at the same time.
+    pub async fn diagnostics(&self) -> Result<Diagnostics, ObsError> {
+        let get_version = GetVersion::builder().build();
+        let get_stats = GetStats::builder().build();
+        let get_video_info = GetVideoInfo::builder().build();
+        let get_streaming_status = GetStreamingStatus::builder().build();
+        let (version, stats, video_info, streaming_status) = future::try_join4(
+            self.request(&get_version),
+            self.request(&get_stats),
+            self.request(&get_video_info),
+            self.request(&get_streaming_status),
+        )
+        .await?;
+        Ok(Diagnostics {
+            version,
+            stats: stats.stats,
+            video_info,
+            streaming_status,
+        })
+    }
 
-        // parse addr
-        let addr = addr
-            .to_socket_addrs()
-            .ok()
-            .and_then(|mut sa| sa.next())
-            .ok_or_else(|| ObsError::InvalidAddress(addr.clone()))?;
-        log::debug!("Connecting TCP stream to: {}", addr);
+    /// Fetches the current stream settings, modifies them with the given function and saves them.
+    /// All of the settings are sent back to OBS, so only the modified settings change.
+    pub async fn update_stream_settings<F>(&self, f: F) -> Result<(), ObsError>
+    where
+        F: FnOnce(&mut responses::StreamSettings),
+    {
+        let current = self.request(&GetStreamSettings::builder().build()).await?;
+        let mut settings = current.settings;
+        f(&mut settings);
+        let set = SetStreamSettings {
+            // the current type is kept if it's not set
+            stream_type: current.stream_type.as_str().map(str::to_string),
+            server: Some(settings.server),
+            key: Some(settings.key),
+            use_auth: Some(settings.use_auth),
+            username: settings.username,
+            password: settings.password,
+            save: true,
+        };
+        self.request(&set).await?;
+        Ok(())
+    }
 
-        // connect to OBS
-        let tcp_stream = Async::<TcpStream>::connect(addr).await?;
-        let tcp_stream = Arc::new(tcp_stream);
-        let send_stream = tcp_stream.clone();
-        let close_stream = tcp_stream.clone();
+    /// Enables Studio Mode if needed and runs the given function, e.g. for requests like `SetPreviewScene` that fail
+    /// when Studio Mode is disabled. If `restore` is true and Studio Mode was disabled before, it's disabled again
+    /// afterwards, even if the function fails.
+    pub async fn with_studio_mode<'a, F, Fut, T>(
+        &'a self,
+        restore: bool,
+        f: F,
+    ) -> Result<T, ObsError>
+    where
+        F: FnOnce(&'a Obs) -> Fut,
+        Fut: Future<Output = Result<T, ObsError>>,
+    {
+        let status = self
+            .request(&GetStudioModeStatus::builder().build())
+            .await?;
+        if !status.studio_mode {
+            self.request(&EnableStudioMode::builder().build()).await?;
+        }
+        let result = f(self).await;
+        if restore && !status.studio_mode {
+            let disabled = self.request(&DisableStudioMode::builder().build()).await;
+            // the function's error is more relevant than the restore error
+            if let (Ok(_), Err(e)) = (&result, disabled) {
+                return Err(e);
+            }
+        }
+        result
+    }
 
-        // establish WS connection to OBS with timeout
-        let tungstenite_future = async_tungstenite::client_async(ws_addr, tcp_stream);
-        futures::pin_mut!(tungstenite_future);
-        let timer = Timer::after(Duration::from_millis(100));
-        let (recv_socket, _res) = match future::select(tungstenite_future, timer).await {
-            Either::Left((tungstenite_client, _)) => tungstenite_client?,
-            Either::Right(_) => return Err(ObsError::TungsteniteTimeout),
-        };
+    /// Switches the program output to the given scene, optionally with the given transition name and duration in milliseconds.
+    /// In Studio Mode, the scene is set as the preview scene and then transitioned to the program output.
+    /// Otherwise the scene is switched to directly with the given transition made the current transition, as OBS
+    /// doesn't support overriding the transition of a single scene switch. The previous transition and its duration
+    /// are restored afterwards, even if switching fails.
+    pub async fn go_to_scene(
+        &self,
+        scene: &str,
+        transition: Option<(String, i32)>,
+    ) -> Result<(), ObsError> {
+        let status = self
+            .request(&GetStudioModeStatus::builder().build())
+            .await?;
+        if status.studio_mode {
+            self.request(&SetPreviewScene::builder().scene_name(scene).build())
+                .await?;
+            let transition_to_program = match transition {
+                Some((name, duration)) => TransitionToProgram::builder()
+                    .with_transition_name(name)
+                    .with_transition_duration(duration.to_string())
+                    .build(),
+                None => TransitionToProgram::builder().build(),
+            };
+            self.request(&transition_to_program).await?;
+        } else if let Some((name, duration)) = transition {
+            let previous = self
+                .request(&GetCurrentTransition::builder().build())
+                .await?;
+            let switched = self
+                .switch_scene_with_transition(scene, name, Some(duration))
+                .await;
+            let restored = self.set_transition(previous.name, previous.duration).await;
+            switched?;
+            restored?;
+        } else {
+            self.request(&SetCurrentScene::builder().scene_name(scene).build())
+                .await?;
+        }
+        Ok(())
+    }
 
-        let send_socket = WebSocketStream::from_raw_socket(send_stream, Role::Client, None).await;
-        let close_socket = WebSocketStream::from_raw_socket(close_stream, Role::Client, None).await;
-        Ok((recv_socket, send_socket, close_socket))
+    // makes the given transition current and switches to the scene with it
+    async fn switch_scene_with_transition(
+        &self,
+        scene: &str,
+        transition_name: String,
+        duration: Option<i32>,
+    ) -> Result<(), ObsError> {
+        self.set_transition(transition_name, duration).await?;
+        self.request(&SetCurrentScene::builder().scene_name(scene).build())
+            .await?;
+        Ok(())
     }
 
-    // handles an incoming WebSocket message from OBS
-    async fn handle_incoming(
-        pending_senders: &mut HashMap<String, OneshotSender<Result<Value, String>>>,
-        event_sender: &mut UnboundedSender<events::Event>,
-        message: String,
-    ) -> Result<(), HandlerError> {
-        log::trace!("Received text: {}", message);
-        match serde_json::from_str::<ResponseOrEvent>(&message) {
-            Ok(ResponseOrEvent::Response(response)) => {
-                // see if we have a sender with a matching message-id
-                if let Some(response_sender) = pending_senders.remove(&response.message_id) {
-                    log::debug!("Received response: {:#?}", response);
-                    let response = match response.response_data {
-                        responses::ResponseData::Ok(value) => Ok(value),
-                        responses::ResponseData::Error { error } => Err(error),
-                    };
-                    response_sender
-                        .send(response)
-                        .map_err(|_response| HandlerError::SendResponse)?;
-                } else {
-                    log::warn!("Unexpected response: {:?}", response);
-                }
-            }
-            Ok(ResponseOrEvent::Event(event)) => {
-                log::debug!("Received event: {:#?}", event);
-                let _ = event_sender.send(*event).await; // ignore errors, user may have dropped event receiver
-            }
-            Err(e) => log::error!(
-                "Received invalid text \"{}\" which failed to deserialize: {:#?}",
-                message,
-                e
-            ),
+    // sets the current transition and its duration, if any
+    async fn set_transition(&self, name: String, duration: Option<i32>) -> Result<(), ObsError> {
+        self.request(
+            &SetCurrentTransition::builder()
+                .transition_name(name)
+                .build(),
+        )
+        .await?;
+        if let Some(duration) = duration {
+            self.request(&SetTransitionDuration::builder().duration(duration).build())
+                .await?;
         }
         Ok(())
     }
 
-    // handles an outgoing Message to OBS
-    async fn handle_outgoing(
-        send_socket: &mut WebSocketHandle,
-        pending_senders: &mut HashMap<String, OneshotSender<Result<Value, String>>>,
-        message: Message,
-    ) -> Result<(), HandlerError> {
-        log::trace!("Received outgoing message: {:?}", message);
-        send_socket
-            .send(WebSocketMessage::text(message.value.to_string()))
-            .await
-            .map_err(HandlerError::Tungstenite)?;
-        log::debug!("Sent text: {:#}", message.value);
-        pending_senders.insert(message.message_id, message.sender);
+    /// Sets a single key in the settings of a filter, leaving its other settings as they are.
+    pub async fn set_filter_setting(
+        &self,
+        source_name: &str,
+        filter_name: &str,
+        key: &str,
+        value: impl Into<Value>,
+    ) -> Result<(), ObsError> {
+        let mut filter_settings = serde_json::Map::new();
+        filter_settings.insert(key.to_string(), value.into());
+        let set = SetSourceFilterSettings::builder()
+            .source_name(source_name)
+            .filter_name(filter_name)
+            .filter_settings(Value::Object(filter_settings))
+            .build();
+        self.request(&set).await?;
         Ok(())
     }
 
-    // starts the handler thread
-    fn start_handler(
-        mut send_socket: WebSocketHandle,
-        mut outgoing_receiver: UnboundedReceiver<Message>,
-        mut websocket_stream: WebSocketHandle,
-        mut event_sender: UnboundedSender<events::Event>,
-    ) -> Result<HandlerHandle, std::io::Error> {
-        log::debug!("Starting handler");
-        thread::Builder::new()
-            .name("message_handler".to_string())
-            .spawn(move || {
-                smol::block_on(async move {
-                    // { request's message-id -> oneshot sender for sending the response }
-                    let mut pending_senders = HashMap::new();
-                    // combine streams for outgoing (JSON from user) and incoming (WS from OBS) messages to thread
-                    loop {
-                        match future::select(outgoing_receiver.next(), websocket_stream.next())
-                            .await
-                        {
-                            Either::Left((outgoing, _)) => match outgoing {
-                                Some(outgoing) => {
-                                    Obs::handle_outgoing(
-                                        &mut send_socket,
-                                        &mut pending_senders,
-                                        outgoing,
-                                    )
-                                    .await?
-                                }
-                                None => {
-                                    log::info!("Outgoing sender closed, closing thread");
-                                    return Ok(());
-                                }
-                            },
-                            Either::Right((incoming, _)) => match incoming {
-                                Some(Ok(incoming)) => match incoming {
-                                    WebSocketMessage::Text(incoming) => {
-                                        // incoming text from OBS
-                                        Obs::handle_incoming(
-                                            &mut pending_senders,
-                                            &mut event_sender,
-                                            incoming,
-                                        )
-                                        .await?
-                                    }
-                                    WebSocketMessage::Close(close_frame) => {
-                                        let reason = close_frame
-                                            .map(|c| c.reason.into_owned())
-                                            .unwrap_or_else(|| "no reason given".to_string());
-                                        log::info!(
-                                            "OBS closed WebSocket connection, closing thread: {}",
-                                            reason
-                                        );
-                                        return Ok(());
-                                    }
-                                    unexpected => {
-                                        log::warn!("Unexpected websocket message: {}", unexpected);
-                                        continue;
-                                    }
-                                },
-                                Some(Err(e)) => {
-                                    log::error!("Tungstenite error, closing thread: {}", e);
-                                    return Err(HandlerError::Tungstenite(e));
-                                }
-                                None => {
-                                    log::info!("OBS socket closed, closing thread");
-                                    return Ok(());
-                                }
-                            },
-                        };
-                    }
-                })
+    /// Enables or disables the filter with the given name on each of the sources, e.g. a blur filter on every camera.
+    /// The requests are sent concurrently and a failure for one source doesn't stop the others.
+    /// Returns the sources that failed with their errors, so an empty list means every source succeeded.
+    pub async fn set_filter_visibility_all(
+        &self,
+        filter_name: &str,
+        enabled: bool,
+        sources: &[&str],
+    ) -> Vec<(String, ObsError)> {
+        let requests = sources
+            .iter()
+            .map(|source| {
+                SetSourceFilterVisibility::builder()
+                    .source_name(*source)
+                    .filter_name(filter_name)
+                    .filter_enabled(enabled)
+                    .build()
             })
+            .collect::<Vec<_>>();
+        let results = future::join_all(requests.iter().map(|req| self.request(req))).await;
+        sources
+            .iter()
+            .zip(results)
+            .filter_map(|(source, result)| result.err().map(|e| (source.to_string(), e)))
+            .collect()
     }
-}
 
-// message from the WebSocket server
-#[derive(Deserialize, Debug, PartialEq)]
-#[serde(untagged)]
-enum ResponseOrEvent {
-    Response(responses::ResponseWrapper),
-    Event(Box<events::Event>),
-}
+    /// Returns the top-level settings of a source as key-value pairs sorted by key, e.g. for rendering a generic
+    /// settings editor without a predefined schema. Note that OBS leaves out settings that have their default value.
+    pub async fn describe_source_settings(
+        &self,
+        source_name: &str,
+    ) -> Result<Vec<(String, Value)>, ObsError> {
+        let get = GetSourceSettings::builder()
+            .source_name(source_name)
+            .build();
+        let settings = self.request(&get).await?.source_settings;
+        Ok(match settings {
+            Value::Object(settings) => settings.into_iter().collect(),
+            _ => vec![],
+        })
+    }
 
-// message used to communicate with the handler channel that owns the WebSocket connection
-#[derive(Debug)]
-struct Message {
-    // message id
-    message_id: String,
-    // JSON to be sent
-    value: Value,
-    // oneshot sender to send the result back with
-    // ok contains the entire message which has been checked to not be an error
-    // err contains the error message
-    sender: OneshotSender<Result<Value, String>>,
-}
-
-// container for data related to the WebSocket connection
-struct ConnectionData {
-    socket_handle: WebSocketHandle,
-    thread_handle: HandlerHandle,
-    thread_sender: UnboundedSender<Message>,
-}
+    /// Returns the names of the available profiles.
+    pub async fn profile_names(&self) -> Result<Vec<String>, ObsError> {
+        let profiles = self.request(&ListProfiles::builder().build()).await?;
+        Ok(profiles
+            .profiles
+            .into_iter()
+            .map(|profile| profile.profile_name)
+            .collect())
+    }
 
-#[cfg(test)]
-mod test {
-    use super::*;
-    use crate::common_types::{self, *};
-    use async_tungstenite::tungstenite::server::accept;
-    use serde_json::{json, Value};
-    use std::{
-        net::TcpListener,
-        thread::{spawn, JoinHandle},
-    };
+    /// Returns the names of the available scene collections.
+    pub async fn scene_collection_names(&self) -> Result<Vec<String>, ObsError> {
+        let collections = self
+            .request(&ListSceneCollections::builder().build())
+            .await?;
+        Ok(collections
+            .scene_collections
+            .into_iter()
+            .map(|collection| collection.sc_name)
+            .collect())
+    }
 
-    fn init_logger() {
-        let _ = env_logger::builder().is_test(true).try_init();
+    /// Returns the names of the scenes in the current scene collection, in order.
+    /// The names are cached, so names that were returned before share their allocation with the earlier copies.
+    /// Useful for avoiding allocations when polling the scene list frequently.
+    pub async fn scene_names(&self) -> Result<Vec<sync::Arc<str>>, ObsError> {
+        let scene_list = self.request(&GetSceneList::builder().build()).await?;
+        Ok(scene_list
+            .scenes
+            .iter()
+            .map(|scene| self.shared.names.intern(&scene.name))
+            .collect())
     }
 
-    fn init_without_server(port: u16) -> Obs {
-        log::debug!("initiating without server at {}", port);
-        smol::block_on(Obs::connect("localhost", port))
-            .expect("failed to connect")
-            .0
+    /// Returns the names of all the sources, including scenes.
+    /// The names are cached like with `scene_names`.
+    pub async fn source_names(&self) -> Result<Vec<sync::Arc<str>>, ObsError> {
+        let sources = self.request(&GetSourcesList::builder().build()).await?;
+        Ok(sources
+            .sources
+            .iter()
+            .map(|source| self.shared.names.intern(&source.name))
+            .collect())
     }
 
-    fn init(responses: Vec<Value>) -> (Obs, JoinHandle<Vec<Value>>) {
-        let server = TcpListener::bind("localhost:0").expect("failed to bind");
-        let port = server.local_addr().expect("local addr").port();
-        log::info!("mock server started at {}", port);
-        let handle = spawn(move || {
-            let mut actual_requests = vec![];
-            let (stream, _) = server.accept().expect("accept");
-            log::info!("incoming connection");
-            let mut websocket = accept(stream).expect("failed to accept");
-            for mut response in responses {
-                let message = websocket.read_message().expect("failed to read message");
-                log::info!("read message {:#?}", message);
-                let parsed = serde_json::from_str::<Value>(&message.to_string())
-                    .expect("failed to deserialize");
-                let message_id = parsed
-                    .as_object()
-                    .as_ref()
-                    .unwrap()
-                    .get("message-id")
-                    .unwrap()
-                    .clone();
-                actual_requests.push(parsed);
-                response
-                    .as_object_mut()
-                    .unwrap()
-                    .insert("message-id".to_string(), message_id);
-                log::info!("responding with {:#?}", response);
-                websocket
-                    .write_message(WebSocketMessage::Text(response.to_string()))
-                    .expect("failed to write");
+    /// Exports the current scene collection into a JSON document, e.g. for backups.
+    /// The document contains the scenes with the properties of their items, including items in groups,
+    /// and the settings of every source used in the scenes.
+    pub async fn export_scene_collection(&self) -> Result<Value, ObsError> {
+        let collection = self
+            .request(&GetCurrentSceneCollection::builder().build())
+            .await?;
+        let scene_list = self.request(&GetSceneList::builder().build()).await?;
+
+        let mut sources = serde_json::Map::new();
+        let mut scenes = vec![];
+        for scene in &scene_list.scenes {
+            let mut items = vec![];
+            for item in flatten_scene_items(&scene.sources) {
+                let get = GetSceneItemProperties::builder()
+                    .scene_name(scene.name.as_str())
+                    .item(item.name.as_str())
+                    .build();
+                let properties = self.request(&get).await?;
+                items.push(serde_json::json!({
+                    "name": item.name,
+                    "parent-group-name": item.parent_group_name,
+                    "properties": properties,
+                }));
+                if !sources.contains_key(&item.name) {
+                    let get = GetSourceSettings::builder()
+                        .source_name(item.name.as_str())
+                        .build();
+                    let settings = self.request(&get).await?;
+                    sources.insert(item.name.clone(), serde_json::to_value(settings)?);
+                }
             }
-            log::info!("closing mock server");
-            websocket.close(None).expect("failed to close");
-            actual_requests
-        });
-        let obs = init_without_server(port);
-        (obs, handle)
+            scenes.push(serde_json::json!({
+                "name": scene.name,
+                "items": items,
+            }));
+        }
+
+        Ok(serde_json::json!({
+            "scene-collection": collection.sc_name,
+            "current-scene": scene_list.current_scene,
+            "scenes": scenes,
+            "sources": sources,
+        }))
     }
 
-    fn request_test<T>(
-        expected_requests: Vec<Value>,
-        expected_responses: Vec<Value>,
-        request: T,
-        expected: T::Response,
-    ) where
-        T: Request + PartialEq + std::fmt::Debug,
-        T::Response: PartialEq + std::fmt::Debug,
-    {
-        let (obs, handle) = init(expected_responses);
-        let res = smol::block_on(obs.request(&request)).expect("request returned err");
-        let actual_requests = handle.join().expect("failed to join");
-        smol::block_on(obs.disconnect()).unwrap();
-        for (request, mut actual_request) in expected_requests.into_iter().zip(actual_requests) {
-            // ignore message-id in the comparison
-            actual_request
-                .as_object_mut()
-                .unwrap()
-                .remove("message-id")
-                .unwrap();
-            assert_eq!(
-                request, actual_request,
-                "request (left) did not match expected (right)"
-            );
+    /// Switches to the scene at the given index in the scene list.
+    pub async fn set_scene_by_index(&self, index: usize) -> Result<(), ObsError> {
+        let scene_list = self.request(&GetSceneList::builder().build()).await?;
+        let scene = scene_list
+            .scenes
+            .get(index)
+            .ok_or(ObsError::SceneIndexOutOfRange {
+                index,
+                scene_count: scene_list.scenes.len(),
+            })?;
+        let set = SetCurrentScene::builder()
+            .scene_name(scene.name.as_str())
+            .build();
+        self.request(&set).await?;
+        Ok(())
+    }
+
+    /// Switches to the scene after the current one in the scene list, wrapping around to the first scene.
+    pub async fn next_scene(&self) -> Result<(), ObsError> {
+        self.switch_scene_relative(1).await
+    }
+
+    /// Switches to the scene before the current one in the scene list, wrapping around to the last scene.
+    pub async fn previous_scene(&self) -> Result<(), ObsError> {
+        self.switch_scene_relative(-1).await
+    }
+
+    // switches to the scene at the given offset from the current scene, wrapping around
+    async fn switch_scene_relative(&self, offset: isize) -> Result<(), ObsError> {
+        let scene_list = self.request(&GetSceneList::builder().build()).await?;
+        let scene_count = scene_list.scenes.len();
+        if scene_count == 0 {
+            return Err(ObsError::SceneIndexOutOfRange {
+                index: 0,
+                scene_count,
+            });
         }
-        assert_eq!(
-            res, expected,
-            "result (left) did not match expected (right)"
-        );
+        let current = scene_list
+            .scenes
+            .iter()
+            .position(|scene| scene.name == scene_list.current_scene)
+            .unwrap_or(0);
+        let index = (current as isize + offset).rem_euclid(scene_count as isize) as usize;
+        let set = SetCurrentScene::builder()
+            .scene_name(scene_list.scenes[index].name.as_str())
+            .build();
+        self.request(&set).await?;
+        Ok(())
     }
 
-    #[test]
-    fn get_version() {
-        init_logger();
+    /// Sets the properties of a Text GDI Plus source and then fetches its current properties.
+    pub async fn set_and_get_text_gdi(
+        &self,
+        req: &SetTextGDIPlusProperties,
+    ) -> Result<responses::GetTextGDIPlusProperties, ObsError> {
+        self.request(req).await?;
+        let get = GetTextGDIPlusProperties::builder()
+            .source(req.source.as_str())
+            .build();
+        self.request(&get).await
+    }
 
-        let request = json!({
-        "request-type": "GetVersion",
-        });
-        let response = json!({
-            "status": "ok",
-            "version": 1.1,
-            "obs-websocket-version": "4.7.0",
-            "obs-studio-version": "24.0.3",
-            "available-requests": "Request1,Request2"
-        });
-        let req = GetVersion::builder().build();
-        let expected = responses::GetVersion {
-            version: 1.1,
-            obs_websocket_version: "4.7.0".to_string(),
-            obs_studio_version: "24.0.3".to_string(),
-            available_requests: vec!["Request1".to_string(), "Request2".to_string()],
+    /// Checks if the scene has an item with the given name. Defaults to the current scene.
+    pub async fn item_exists(
+        &self,
+        scene_name: Option<&str>,
+        item: &str,
+    ) -> Result<bool, ObsError> {
+        let get = GetSceneItemProperties {
+            scene_name: scene_name.map(str::to_string),
+            item: item.to_string(),
         };
-        request_test(vec![request], vec![response], req, expected);
+        match self.request(&get).await {
+            Ok(_) => Ok(true),
+            Err(ObsError::SceneItemNotFound(_)) => Ok(false),
+            Err(e) => Err(e),
+        }
     }
 
-    #[test]
-    fn get_auth_required_true() {
-        init_logger();
+    /// Checks if a scene with the given name exists, e.g. for validating a name before switching to it.
+    pub async fn scene_exists(&self, scene_name: &str) -> Result<bool, ObsError> {
+        let scene_list = self.request(&GetSceneList::builder().build()).await?;
+        Ok(scene_list
+            .scenes
+            .iter()
+            .any(|scene| scene.name == scene_name))
+    }
 
-        let request = json!({
-            "request-type": "GetAuthRequired",
-        });
-        let response = json!({
-            "status": "ok",
-            "authRequired": true,
-            "challenge": "ch",
-            "salt": "sa",
-        });
-        let req = GetAuthRequired::builder().build();
-        let expected = responses::GetAuthRequired {
-            auth_required: true,
-            challenge: Some("ch".to_string()),
-            salt: Some("sa".to_string()),
-        };
-        request_test(vec![request], vec![response], req, expected);
+    /// Checks if a profile with the given name exists.
+    pub async fn profile_exists(&self, profile_name: &str) -> Result<bool, ObsError> {
+        let profiles = self.request(&ListProfiles::builder().build()).await?;
+        Ok(profiles
+            .profiles
+            .iter()
+            .any(|profile| profile.profile_name == profile_name))
     }
 
-    #[test]
-    fn get_auth_required_false() {
-        init_logger();
+    /// Checks if a scene collection with the given name exists.
+    pub async fn scene_collection_exists(&self, sc_name: &str) -> Result<bool, ObsError> {
+        let scene_collections = self
+            .request(&ListSceneCollections::builder().build())
+            .await?;
+        Ok(scene_collections
+            .scene_collections
+            .iter()
+            .any(|scene_collection| scene_collection.sc_name == sc_name))
+    }
 
-        let request = json!({
-            "request-type": "GetAuthRequired",
-        });
-        let response = json!({
-            "status": "ok",
-            "authRequired": false,
-        });
-        let req = GetAuthRequired::builder().build();
-        let expected = responses::GetAuthRequired {
-            auth_required: false,
-            challenge: None,
-            salt: None,
-        };
-        request_test(vec![request], vec![response], req, expected);
+    /// Switches to the given profile. If `validate` is true, returns `ObsError::ProfileNotFound`
+    /// without switching if the profile doesn't exist.
+    pub async fn set_current_profile(
+        &self,
+        profile_name: &str,
+        validate: bool,
+    ) -> Result<(), ObsError> {
+        if validate && !self.profile_exists(profile_name).await? {
+            return Err(ObsError::ProfileNotFound(profile_name.to_string()));
+        }
+        let set = SetCurrentProfile::builder()
+            .profile_name(profile_name)
+            .build();
+        self.request(&set).await?;
+        Ok(())
     }
 
-    #[test]
-    fn authenticate() {
-        init_logger();
+    /// Switches to the given scene collection. If `validate` is true, returns `ObsError::SceneCollectionNotFound`
+    /// without switching if the scene collection doesn't exist.
+    pub async fn set_current_scene_collection(
+        &self,
+        sc_name: &str,
+        validate: bool,
+    ) -> Result<(), ObsError> {
+        if validate && !self.scene_collection_exists(sc_name).await? {
+            return Err(ObsError::SceneCollectionNotFound(sc_name.to_string()));
+        }
+        let set = SetCurrentSceneCollection::builder()
+            .sc_name(sc_name)
+            .build();
+        self.request(&set).await?;
+        Ok(())
+    }
 
-        let requests = vec![
-            json!({
-                "request-type": "GetAuthRequired",
-            }),
-            json!({
-                "request-type": "Authenticate",
-                "auth": "Z69J+b7C5Zj7jIXlqVp/xjp36sFSmpJpxZ41GN/UTu4=",
-            }),
-        ];
-        let responses = vec![
-            json!({
-                "status": "ok",
-                "authRequired": true,
-                "challenge": "123",
-                "salt": "456",
-            }),
-            json!({
-                "status": "ok",
-            }),
-        ];
-        let expected = responses::Empty {};
-        let (mut obs, handle) = init(responses);
-        let res = smol::block_on(obs.authenticate("todo")).expect("authenticate");
-        let actual_requests = handle.join().expect("join");
-        smol::block_on(obs.disconnect()).unwrap();
-        for (request, mut actual_request) in requests.into_iter().zip(actual_requests) {
-            // remove message-id
-            actual_request
-                .as_object_mut()
-                .unwrap()
-                .remove("message-id")
-                .unwrap();
-            assert_eq!(
-                request, actual_request,
-                "request (left) did not match expected (right)"
-            );
+    /// Looks up the id of the first scene item with the given name in the scene, including items in groups.
+    /// Defaults to the current scene.
+    pub async fn resolve_item_id(
+        &self,
+        scene_name: Option<&str>,
+        item: &str,
+    ) -> Result<i32, ObsError> {
+        fn find<'a>(items: &'a [SceneItem], name: &str) -> Option<&'a SceneItem> {
+            items.iter().find_map(|item| {
+                if item.name == name {
+                    Some(item)
+                } else {
+                    item.group_children
+                        .as_ref()
+                        .and_then(|children| find(children, name))
+                }
+            })
         }
-        assert_eq!(
-            res, expected,
-            "result (left) did not match expected (right)"
-        );
+
+        let scene_list = self.request(&GetSceneList::builder().build()).await?;
+        let scene_name = scene_name.unwrap_or(&scene_list.current_scene);
+        let scene = scene_list
+            .scenes
+            .iter()
+            .find(|scene| scene.name == scene_name)
+            .ok_or_else(|| ObsError::SceneNotFound(scene_name.to_string()))?;
+        find(&scene.sources, item)
+            .map(|item| item.id)
+            .ok_or_else(|| ObsError::SceneItemNotFound(item.to_string()))
     }
 
-    #[test]
-    fn get_stats() {
-        init_logger();
+    /// Deletes the scene item with the given name. The name is resolved to the item's current id right before
+    /// deleting, so the right item is deleted even if ids have changed, e.g. after reloading the scene collection.
+    /// Defaults to the current scene.
+    pub async fn delete_scene_item_by_name(
+        &self,
+        scene_name: Option<&str>,
+        item: &str,
+    ) -> Result<(), ObsError> {
+        let id = self.resolve_item_id(scene_name, item).await?;
+        let delete = DeleteSceneItem {
+            scene: scene_name.map(str::to_string),
+            item_id: Some(ItemId::Id(id)),
+        };
+        self.request(&delete).await?;
+        Ok(())
+    }
 
-        let request = json!({
-            "request-type": "GetStats",
-        });
-        let response = json!({
-            "status": "ok",
-            "stats": {
-                "fps": 0.0,
-                "render-total-frames": 1,
-                "render-missed-frames": 2,
-                "output-total-frames": 3,
-                "output-skipped-frames": 4,
-                "average-frame-time": 5.0,
-                "cpu-usage": 6.0,
-                "memory-usage": 7.0,
-                "free-disk-space": 8.0,
+    /// Duplicates the scene item with the given name, resolving the name to the item's current id like
+    /// `delete_scene_item_by_name`. The scenes default to the current scene.
+    pub async fn duplicate_scene_item_by_name(
+        &self,
+        from_scene: Option<&str>,
+        to_scene: Option<&str>,
+        item: &str,
+    ) -> Result<responses::DuplicateSceneItem, ObsError> {
+        let id = self.resolve_item_id(from_scene, item).await?;
+        let duplicate = DuplicateSceneItem {
+            from_scene: from_scene.map(str::to_string),
+            to_scene: to_scene.map(str::to_string),
+            item_id: Some(ItemId::Id(id)),
+        };
+        self.request(&duplicate).await
+    }
+
+    /// Positions a scene item so that it is centered on the canvas.
+    /// The item's alignment is preserved and the size used is its bounding box if it has one, or its scaled size otherwise.
+    /// Rotation is not taken into account.
+    pub async fn center_item(&self, scene_name: Option<&str>, item: &str) -> Result<(), ObsError> {
+        let video_info = self.request(&GetVideoInfo::builder().build()).await?;
+        let get = GetSceneItemProperties {
+            scene_name: scene_name.map(str::to_string),
+            item: item.to_string(),
+        };
+        let properties = self.request(&get).await?;
+
+        let (width, height) = if properties.bounds.bounds_type == BoundsType::None {
+            (properties.width, properties.height)
+        } else {
+            (properties.bounds.x, properties.bounds.y)
+        };
+        // the position refers to the point on the item determined by its alignment
+        let align = properties.position.alignment;
+        let offset = |align_start: i32, align_end: i32, size: f64| {
+            if align & align_start != 0 {
+                -size / 2.0
+            } else if align & align_end != 0 {
+                size / 2.0
+            } else {
+                0.0
             }
-        });
-        let req = GetStats::builder().build();
-        let expected = responses::GetStats {
-            stats: ObsStats {
-                fps: 0.0,
-                render_total_frames: 1,
-                render_missed_frames: 2,
-                output_total_frames: 3,
-                output_skipped_frames: 4,
-                average_frame_time: 5.0,
-                cpu_usage: 6.0,
-                memory_usage: 7.0,
-                free_disk_space: 8.0,
-            },
         };
-        request_test(vec![request], vec![response], req, expected);
+        let x = f64::from(video_info.base_width) / 2.0
+            + offset(alignment::LEFT, alignment::RIGHT, width);
+        let y = f64::from(video_info.base_height) / 2.0
+            + offset(alignment::TOP, alignment::BOTTOM, height);
+
+        let mut set = SetSceneItemProperties::builder()
+            .item(item)
+            .position_x(x)
+            .position_y(y)
+            .build();
+        set.scene_name = get.scene_name;
+        self.request(&set).await?;
+        Ok(())
     }
 
-    #[test]
-    fn get_video_info() {
-        init_logger();
+    /// Mirrors a scene item horizontally and/or vertically, e.g. for mirroring a webcam.
+    /// OBS flips items by negating their scale, so the current scale is negated while preserving its magnitude.
+    /// Flipping an item that's already flipped restores it.
+    pub async fn flip_item(
+        &self,
+        scene_name: Option<&str>,
+        item: &str,
+        horizontal: bool,
+        vertical: bool,
+    ) -> Result<(), ObsError> {
+        if !horizontal && !vertical {
+            return Ok(());
+        }
+        let get = GetSceneItemProperties {
+            scene_name: scene_name.map(str::to_string),
+            item: item.to_string(),
+        };
+        let properties = self.request(&get).await?;
 
-        let request = json!({
-            "request-type": "GetVideoInfo",
-        });
-        let response = json!({
+        let mut set = SetSceneItemProperties::builder().item(item).build();
+        set.scene_name = get.scene_name;
+        if horizontal {
+            set.scale_x = Some(-properties.scale.x);
+        }
+        if vertical {
+            set.scale_y = Some(-properties.scale.y);
+        }
+        self.request(&set).await?;
+        Ok(())
+    }
+
+    /// Animates a scene item from one transform to another over the given duration.
+    /// The item is first set to `from`, after which it's moved towards `to` in the given number of evenly spaced steps.
+    /// Dropping the returned future stops the animation.
+    pub async fn animate_item(&self, animation: &Animation) -> Result<(), ObsError> {
+        let steps = animation.steps.max(1);
+        let interval = animation.duration / steps;
+        for step in 0..=steps {
+            if step > 0 {
+                Timer::after(interval).await;
+            }
+            let t = animation.easing.apply(f64::from(step) / f64::from(steps));
+            let frame = animation.from.interpolate(&animation.to, t);
+            let mut set = SetSceneItemProperties::builder()
+                .item(animation.item.as_str())
+                .position_x(frame.position_x)
+                .position_y(frame.position_y)
+                .scale_x(frame.scale_x)
+                .scale_y(frame.scale_y)
+                .rotation(frame.rotation)
+                .build();
+            set.scene_name = animation.scene_name.clone();
+            self.request(&set).await?;
+        }
+        Ok(())
+    }
+
+    /// Mirrors the transforms of the items in one scene to the items with the same names in another scene,
+    /// e.g. to keep the layout of a scene for a secondary display in sync with the main scene.
+    /// Items that don't exist in the target scene are skipped. Runs until the connection is closed or
+    /// a request fails, dropping the returned future stops mirroring.
+    pub async fn mirror_transforms(
+        &self,
+        from_scene: &str,
+        to_scene: &str,
+    ) -> Result<(), ObsError> {
+        let mut events = self.subscribe();
+        while let Some(event) = events.next().await {
+            let (item_name, transform) = match event.update_type {
+                events::EventType::SceneItemTransformChanged {
+                    scene_name,
+                    item_name,
+                    transform,
+                    ..
+                } if scene_name == from_scene => (item_name, transform),
+                _ => continue,
+            };
+            let set = SetSceneItemProperties {
+                scene_name: Some(to_scene.to_string()),
+                item: item_name,
+                position_x: Some(transform.position.x),
+                position_y: Some(transform.position.y),
+                position_alignment: Some(transform.position.alignment),
+                rotation: Some(transform.rotation),
+                scale_x: Some(transform.scale.x),
+                scale_y: Some(transform.scale.y),
+                crop_top: Some(transform.crop.top),
+                crop_bottom: Some(transform.crop.bottom),
+                crop_left: Some(transform.crop.left),
+                crop_right: Some(transform.crop.right),
+                visible: Some(transform.visible),
+                locked: Some(transform.locked),
+                bounds_type: Some(transform.bounds.bounds_type),
+                bounds_alignment: Some(transform.bounds.alignment),
+                bounds_x: Some(transform.bounds.x),
+                bounds_y: Some(transform.bounds.y),
+            };
+            match self.request(&set).await {
+                Ok(_) => {}
+                Err(ObsError::SceneItemNotFound(_)) => {
+                    log::debug!("{} not found in {}, skipping", set.item, to_scene)
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+
+    /// Fits a scene item to the canvas, keeping its aspect ratio. Same as "Fit to screen" in OBS.
+    /// The item's bounding box is set to cover the canvas and the item is scaled to fit inside it.
+    pub async fn fit_item_to_screen(
+        &self,
+        scene_name: Option<&str>,
+        item: &str,
+    ) -> Result<(), ObsError> {
+        let video_info = self.request(&GetVideoInfo::builder().build()).await?;
+        let mut set = SetSceneItemProperties::builder()
+            .item(item)
+            .position_x(0.0)
+            .position_y(0.0)
+            .position_alignment(alignment::TOP | alignment::LEFT)
+            .bounds_type(BoundsType::ScaleInner)
+            .bounds_alignment(alignment::CENTER)
+            .bounds_x(f64::from(video_info.base_width))
+            .bounds_y(f64::from(video_info.base_height))
+            .build();
+        set.scene_name = scene_name.map(str::to_string);
+        self.request(&set).await?;
+        Ok(())
+    }
+
+    /// Mutes the audio of the sources that are not in the given scene. The sources in it are left as they are,
+    /// so that e.g. a microphone the user has muted stays muted.
+    /// Sources in groups and nested scenes count as being in the scene. Sources that are not in any scene, such as the global audio sources, are not affected.
+    pub async fn mute_inactive_scene_audio(&self, active_scene: &str) -> Result<(), ObsError> {
+        let scene_list = self.request(&GetSceneList::builder().build()).await?;
+        let has_audio = |item: &&SceneItem| {
+            item.group_children.is_none()
+                && !matches!(
+                    item.scene_item_type,
+                    SceneItemType::Scene
+                        | SceneItemType::Filter
+                        | SceneItemType::Transition
+                        | SceneItemType::ImageSource
+                )
+        };
+
+        let mut active = vec![];
+        collect_scene_items(
+            &scene_list.scenes,
+            active_scene,
+            false,
+            &mut vec![],
+            &mut active,
+        );
+        let active = active
+            .into_iter()
+            .filter(has_audio)
+            .map(|item| item.name.as_str())
+            .collect::<HashSet<_>>();
+        let mut all = vec![];
+        for scene in &scene_list.scenes {
+            collect_scene_items(
+                &scene_list.scenes,
+                &scene.name,
+                false,
+                &mut vec![],
+                &mut all,
+            );
+        }
+        let all = all
+            .into_iter()
+            .filter(has_audio)
+            .map(|item| item.name.as_str())
+            .collect::<HashSet<_>>();
+
+        let requests = all
+            .difference(&active)
+            .map(|source| SetMute::builder().source(*source).mute(true).build())
+            .collect::<Vec<_>>();
+        future::try_join_all(requests.iter().map(|req| self.request(req))).await?;
+        Ok(())
+    }
+
+    /// Returns the names of the sources that are visible on the program output, e.g. for a tally system.
+    /// The visible items of the current scene are included along with the visible items in them if they're groups or
+    /// nested scenes. Each source is only included once, in the order they're first found.
+    pub async fn active_sources(&self) -> Result<Vec<String>, ObsError> {
+        let scene_list = self.request(&GetSceneList::builder().build()).await?;
+        let mut items = vec![];
+        collect_scene_items(
+            &scene_list.scenes,
+            &scene_list.current_scene,
+            true,
+            &mut vec![],
+            &mut items,
+        );
+        let mut sources = Vec::<String>::new();
+        for item in items {
+            if !sources.contains(&item.name) {
+                sources.push(item.name.clone());
+            }
+        }
+        Ok(sources)
+    }
+
+    /// Checks if the output with the given name is active. Outputs that don't exist are not active.
+    pub async fn is_output_active(&self, output_name: &str) -> Result<bool, ObsError> {
+        let get = GetOutputInfo::builder().output_name(output_name).build();
+        match self.request(&get).await {
+            Ok(info) => Ok(info.output_info.active),
+            Err(ObsError::OutputNotFound(_)) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Returns the names of the active outputs, e.g. for monitoring a stream and a recording at the same time.
+    pub async fn active_outputs(&self) -> Result<Vec<String>, ObsError> {
+        let outputs = self.request(&ListOutputs::builder().build()).await?;
+        Ok(outputs
+            .outputs
+            .into_iter()
+            .filter(|output| output.active)
+            .map(|output| output.name)
+            .collect())
+    }
+
+    /// Starts every output of the given kind, e.g. whatever RTMP output is configured, without knowing their names.
+    /// Returns the names of the outputs that were started.
+    pub async fn start_outputs_of_kind(&self, kind: OutputKind) -> Result<Vec<String>, ObsError> {
+        let names = self.output_names_of_kind(kind).await?;
+        for name in &names {
+            self.request(&StartOutput::builder().output_name(name).build())
+                .await?;
+        }
+        Ok(names)
+    }
+
+    /// Stops every output of the given kind. Returns the names of the outputs that were stopped.
+    pub async fn stop_outputs_of_kind(&self, kind: OutputKind) -> Result<Vec<String>, ObsError> {
+        let names = self.output_names_of_kind(kind).await?;
+        for name in &names {
+            self.request(&StopOutput::builder().output_name(name).build())
+                .await?;
+        }
+        Ok(names)
+    }
+
+    async fn output_names_of_kind(&self, kind: OutputKind) -> Result<Vec<String>, ObsError> {
+        let outputs = self.request(&ListOutputs::builder().build()).await?;
+        Ok(outputs
+            .outputs
+            .into_iter()
+            .filter(|output| output.output_type == kind)
+            .map(|output| output.name)
+            .collect())
+    }
+
+    /// Saves the replay buffer and waits for OBS to report the path of the saved file.
+    /// Returns `None` if no path was reported within the given timeout.
+    pub async fn save_replay_buffer_confirmed(
+        &self,
+        timeout: Duration,
+    ) -> Result<Option<String>, ObsError> {
+        // subscribe before sending the request so that the event can't be missed
+        let events = self.subscribe();
+        self.request(&SaveReplayBuffer::builder().build()).await?;
+
+        let saved_path = events.filter_map(|event| {
+            future::ready(match event.update_type {
+                events::EventType::RecordingFileChanged { new_file_name } => Some(new_file_name),
+                _ => None,
+            })
+        });
+        futures::pin_mut!(saved_path);
+        match future::select(saved_path.next(), Timer::after(timeout)).await {
+            Either::Left((path, _)) => Ok(path),
+            Either::Right(_) => {
+                log::warn!("Timed out waiting for the saved replay buffer path");
+                Ok(None)
+            }
+        }
+    }
+
+    /// Toggles recording and waits for OBS to report that recording started or stopped.
+    /// Returns the new recording state, or `ObsError::RequestTimeout` if neither was reported within the timeout.
+    /// Losing the connection while waiting also results in `ObsError::RequestTimeout`, since no events arrive anymore.
+    pub async fn toggle_recording_confirmed(&self, timeout: Duration) -> Result<bool, ObsError> {
+        // subscribe before sending the request so that the event can't be missed
+        let events = self.subscribe();
+        self.request(&StartStopRecording::builder().build()).await?;
+
+        let recording = events.filter_map(|event| {
+            future::ready(match event.update_type {
+                events::EventType::RecordingStarted => Some(true),
+                events::EventType::RecordingStopped => Some(false),
+                _ => None,
+            })
+        });
+        futures::pin_mut!(recording);
+        match future::select(recording.next(), Timer::after(timeout)).await {
+            // the subscription only ends if the subscribers are cleared by close
+            Either::Left((recording, _)) => recording.ok_or(ObsError::ConnectionLost),
+            Either::Right(_) => Err(ObsError::RequestTimeout),
+        }
+    }
+
+    /// Waits until the scene with the given name is the current scene, e.g. for scripted shows.
+    /// Returns immediately if the scene is already active and `ObsError::RequestTimeout` if it doesn't become active
+    /// within the timeout, which includes losing the connection while waiting.
+    pub async fn wait_for_scene(
+        &self,
+        scene_name: &str,
+        timeout: Duration,
+    ) -> Result<(), ObsError> {
+        let wait = async {
+            // subscribe before checking the current scene so that a switch in between can't be missed
+            let switches = self.events_of::<events::SwitchScenesEvent>();
+            let current = self.request(&GetCurrentScene::builder().build()).await?;
+            if current.name == scene_name {
+                return Ok(());
+            }
+            let switched = switches
+                .filter(|switch| future::ready(switch.scene_name == scene_name))
+                .next()
+                .await;
+            // the subscription only ends if the subscribers are cleared by close
+            switched.map(|_| ()).ok_or(ObsError::ConnectionLost)
+        };
+        futures::pin_mut!(wait);
+        match future::select(wait, Timer::after(timeout)).await {
+            Either::Left((result, _)) => result,
+            Either::Right(_) => Err(ObsError::RequestTimeout),
+        }
+    }
+
+    /// Sets the audio mixer routing of a source.
+    /// Returns `ObsError::Unsupported` if OBS doesn't support `SetTracks`, which requires obs-websocket 4.9.1.
+    pub async fn set_source_mixers(&self, mixers: &SetSourceMixers) -> Result<(), ObsError> {
+        self.require_request::<SetTracks>().await?;
+        for set in mixers.requests() {
+            self.request(&set).await?;
+        }
+        Ok(())
+    }
+
+    /// Fades the volume of a source from its current volume to the target volume.
+    /// The volume is changed in the given number of evenly spaced steps over the duration.
+    /// Dropping the returned future stops the fade.
+    pub async fn fade_volume(
+        &self,
+        source: &str,
+        target: f64,
+        duration: Duration,
+        steps: u32,
+    ) -> Result<(), ObsError> {
+        let get = GetVolume::builder().source(source).build();
+        let start = self.request(&get).await?.volume;
+
+        let steps = steps.max(1);
+        let interval = duration / steps;
+        for step in 1..=steps {
+            Timer::after(interval).await;
+            let volume = start + (target - start) * f64::from(step) / f64::from(steps);
+            let set = SetVolume::builder().source(source).volume(volume).build();
+            self.request(&set).await?;
+        }
+        Ok(())
+    }
+
+    /// Returns a controller for setting the volume of a source that sends at most one SetVolume request per interval.
+    /// See `VolumeController`.
+    pub fn volume_controller(
+        &self,
+        source: &str,
+        interval: Duration,
+    ) -> Result<VolumeController, ObsError> {
+        let thread_sender = self.thread_sender()?;
+        let state = sync::Arc::new(Mutex::new(VolumeState::default()));
+        Obs::start_volume_controller(
+            thread_sender,
+            self.shared.config.message_id_prefix.clone(),
+            source.to_string(),
+            interval,
+            state.clone(),
+        )
+        .map_err(ObsError::Thread)?;
+        Ok(VolumeController::new(state))
+    }
+
+    /// Returns a stream that reports whether OBS is alive once per interval.
+    /// Heartbeat events are used if they are flowing, see `SetHeartbeat`. If no heartbeat arrives within the interval,
+    /// a GetVersion request is sent instead and OBS is considered alive if it responds within the interval.
+    /// The interval should be longer than the 2 second heartbeat interval to avoid unnecessary polling.
+    /// The stream doesn't end when the connection is lost, OBS is reported as not alive instead. Drop it to stop polling.
+    pub fn liveness_stream(
+        &self,
+        interval: Duration,
+    ) -> impl Stream<Item = Liveness> + Send + Unpin + '_ {
+        let heartbeats = self
+            .subscribe()
+            .filter(|event| {
+                future::ready(matches!(
+                    event.update_type,
+                    events::EventType::Heartbeat { .. }
+                ))
+            })
+            .boxed();
+        stream::unfold(heartbeats, move |mut heartbeats| async move {
+            let liveness = match future::select(heartbeats.next(), Timer::after(interval)).await {
+                Either::Left((Some(_), _)) => Liveness {
+                    alive: true,
+                    source: LivenessSource::Heartbeat,
+                },
+                Either::Left((None, _)) => return None,
+                Either::Right(_) => {
+                    log::trace!("No heartbeat received, polling");
+                    let get_version = GetVersion::builder().build();
+                    let ping = self.request(&get_version);
+                    futures::pin_mut!(ping);
+                    let alive = matches!(
+                        future::select(ping, Timer::after(interval)).await,
+                        Either::Left((Ok(_), _))
+                    );
+                    Liveness {
+                        alive,
+                        source: LivenessSource::Poll,
+                    }
+                }
+            };
+            Some((liveness, heartbeats))
+        })
+        .boxed()
+    }
+
+    /// Returns a stream of PNG screenshots of the given source, scaled to the given width.
+    /// The first screenshot is taken immediately and the rest at the given interval.
+    /// Dropping the stream stops taking screenshots.
+    pub fn source_thumbnails<'a>(
+        &'a self,
+        source: &'a str,
+        interval: Duration,
+        width: i32,
+    ) -> impl Stream<Item = Result<Vec<u8>, ObsError>> + Send + Unpin + 'a {
+        stream::unfold(true, move |first| async move {
+            if !first {
+                Timer::after(interval).await;
+            }
+            Some((self.source_thumbnail(source, width).await, false))
+        })
+        .boxed()
+    }
+
+    // takes a PNG screenshot of the source and decodes it
+    async fn source_thumbnail(&self, source: &str, width: i32) -> Result<Vec<u8>, ObsError> {
+        let req = TakeSourceScreenshot::builder()
+            .source_name(source)
+            .embed_picture_format(EmbedPictureFormat::Png)
+            .width(width)
+            .build();
+        let screenshot = self.request(&req).await?;
+        let data_uri = screenshot
+            .img
+            .ok_or_else(|| ObsError::InvalidScreenshot("missing image data".to_string()))?;
+        // data:image/png;base64,...
+        let (_, data) = data_uri.split_at(
+            data_uri.find(',').ok_or_else(|| {
+                ObsError::InvalidScreenshot(format!("invalid data URI: {}", data_uri))
+            })? + 1,
+        );
+        base64::decode(data).map_err(|e| ObsError::InvalidScreenshot(e.to_string()))
+    }
+
+    // initializes the connection to OBS WebSocket
+    async fn init_sockets(
+        transport: Box<dyn Transport>,
+        url: &str,
+        config: &ObsConfig,
+    ) -> Result<(WebSocketHandle, WebSocketHandle, WebSocketHandle), ObsError> {
+        let send_stream = transport.clone_transport();
+        let close_stream = transport.clone_transport();
+
+        // establish WS connection to OBS with timeout
+        let websocket_config = config.websocket_config();
+        let tungstenite_future =
+            async_tungstenite::client_async_with_config(url, transport, Some(websocket_config));
+        futures::pin_mut!(tungstenite_future);
+        let timer = Timer::after(Duration::from_millis(100));
+        let (recv_socket, _res) = match future::select(tungstenite_future, timer).await {
+            Either::Left((tungstenite_client, _)) => tungstenite_client?,
+            Either::Right(_) => return Err(ObsError::TungsteniteTimeout),
+        };
+
+        // the same connection is shared by three WebSocketStreams, each of which is only used in one direction:
+        // recv_socket only reads, which is also why it performs the handshake so that anything OBS sent right after
+        // the handshake response stays in its read buffer, send_socket only writes requests from the handler thread
+        // and close_socket only writes the close frame once the handler thread has stopped sending
+        let send_socket =
+            WebSocketStream::from_raw_socket(send_stream, Role::Client, Some(websocket_config))
+                .await;
+        let close_socket =
+            WebSocketStream::from_raw_socket(close_stream, Role::Client, Some(websocket_config))
+                .await;
+        Ok((recv_socket, send_socket, close_socket))
+    }
+
+    // handles an incoming WebSocket message from OBS
+    async fn handle_incoming(
+        pending_requests: &mut HashMap<String, PendingRequest>,
+        event_sender: &mut UnboundedSender<events::Event>,
+        shared: &SharedState,
+        message: String,
+    ) -> Result<(), HandlerError> {
+        log::trace!("Received text: {}", message);
+        match serde_json::from_str::<ResponseOrEvent>(&message) {
+            Ok(ResponseOrEvent::Response(response)) => {
+                if let Some(audit_log) = &shared.audit_log {
+                    audit_log.write("response", &message);
+                }
+                // see if we have a sender with a matching message-id
+                if let Some(pending) = pending_requests.remove(&response.message_id) {
+                    log::debug!("Received response: {:#?}", response);
+                    let mut response = match response.response_data {
+                        responses::ResponseData::Ok(value) => Ok(value),
+                        responses::ResponseData::Error { error } => Err(error),
+                    };
+                    for interceptor in &shared.config.interceptors {
+                        interceptor.after(&pending.request_type, &response);
+                    }
+                    if let Some(on_request_complete) = &shared.config.on_request_complete {
+                        on_request_complete(&pending.request_type, pending.sent_at.elapsed());
+                    }
+                    if let (Some(mapper), Ok(Value::Object(object))) =
+                        (&shared.config.scene_name_mapper, &mut response)
+                    {
+                        map_scene_names(Some(&pending.request_type), object, |name| {
+                            mapper.app_name(name)
+                        });
+                        // the results of a batch are in the same order as its requests
+                        if let Some(Value::Array(results)) = object.get_mut("results") {
+                            for (result, request_type) in
+                                results.iter_mut().zip(&pending.batch_request_types)
+                            {
+                                if let Value::Object(result) = result {
+                                    map_scene_names(Some(request_type), result, |name| {
+                                        mapper.app_name(name)
+                                    });
+                                }
+                            }
+                        }
+                    }
+                    // the receiver is gone if the caller gave up, e.g. due to a timeout or dropping the future
+                    if pending
+                        .sender
+                        .send(response.map_err(ObsError::from_obs_message))
+                        .is_err()
+                    {
+                        log::debug!(
+                            "Dropped the response to {} as it's no longer waited for",
+                            pending.request_type
+                        );
+                    }
+                } else {
+                    // the first response to a request wins, later ones with the same message-id are ignored
+                    let count = shared.unexpected_responses.fetch_add(1, Ordering::Relaxed) + 1;
+                    log::warn!("Unexpected response: {:?}", response);
+                    log::debug!("{} unexpected responses received", count);
+                    if let Some(threshold) = shared.config.orphan_response_threshold {
+                        if count >= threshold {
+                            log::error!("Received {} unexpected responses, closing thread", count);
+                            return Err(HandlerError::TooManyOrphanResponses(count));
+                        }
+                    }
+                }
+            }
+            Ok(ResponseOrEvent::Event(mut event)) => {
+                // skip deserializing the event if nothing would receive it, e.g. with Obs::connect_no_events
+                // RecordingFileChanged is always tracked for Obs::saved_replays
+                let has_listeners = !event_sender.is_closed()
+                    || shared.status.is_enabled()
+                    || event.update_type == "RecordingFileChanged"
+                    || shared
+                        .event_subscribers
+                        .lock()
+                        .unwrap()
+                        .iter()
+                        .any(|subscriber| !subscriber.is_closed());
+                if !has_listeners {
+                    let count = shared.dropped_events.fetch_add(1, Ordering::Relaxed) + 1;
+                    log::trace!(
+                        "Dropped {} event without listeners, {} dropped",
+                        event.update_type,
+                        count
+                    );
+                    return Ok(());
+                }
+                if let Some(mapper) = &shared.config.scene_name_mapper {
+                    map_scene_names(None, &mut event.data, |name| mapper.app_name(name));
+                }
+                let event = event.parse();
+                log::debug!("Received event: {:#?}", event);
+                shared.status.handle_event(&event.update_type);
+                if let events::EventType::RecordingFileChanged { new_file_name } =
+                    &event.update_type
+                {
+                    shared
+                        .saved_files
+                        .lock()
+                        .unwrap()
+                        .push(new_file_name.clone());
+                }
+                // drop the subscribers whose receivers have been dropped
+                shared
+                    .event_subscribers
+                    .lock()
+                    .unwrap()
+                    .retain(|subscriber| subscriber.unbounded_send(event.clone()).is_ok());
+                let _ = event_sender.send(event).await; // ignore errors, user may have dropped event receiver
+            }
+            Err(e) => log::error!(
+                "Received invalid text \"{}\" which failed to deserialize: {:#?}",
+                message,
+                e
+            ),
+        }
+        Ok(())
+    }
+
+    // handles an outgoing Message to OBS
+    async fn handle_outgoing(
+        send_socket: &mut WebSocketHandle,
+        pending_requests: &mut HashMap<String, PendingRequest>,
+        shared: &SharedState,
+        mut message: Message,
+    ) -> Result<(), HandlerError> {
+        log::trace!("Received outgoing message: {:?}", message);
+        // drop the requests that are no longer waiting for a response, e.g. due to a timeout
+        pending_requests.retain(|_, pending| !pending.sender.is_canceled());
+        let mut batch_request_types = vec![];
+        let text = match message.raw_text.take() {
+            Some(raw_text) => raw_text,
+            None => {
+                // OBS executes the requests in a batch as is, so they're prepared like individual requests
+                if message.request_type == ExecuteBatch::REQUEST_TYPE {
+                    if let Some(Value::Array(requests)) = message.value.get_mut("requests") {
+                        for request in requests {
+                            let request_type = request["request-type"]
+                                .as_str()
+                                .unwrap_or_default()
+                                .to_string();
+                            prepare_request(shared, &request_type, request);
+                            batch_request_types.push(request_type);
+                        }
+                    }
+                }
+                prepare_request(shared, &message.request_type, &mut message.value);
+                if shared.config.pretty_json {
+                    format!("{:#}", message.value)
+                } else {
+                    message.value.to_string()
+                }
+            }
+        };
+        let sent_at = Instant::now();
+        // waiting for the socket to become ready flushes the transport, which may be interrupted
+        // only this is retried, since nothing of this message has been written yet
+        // once writing has started, an error may have left a partial frame that sending again would corrupt
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            match future::poll_fn(|cx| send_socket.poll_ready_unpin(cx)).await {
+                Ok(()) => break,
+                Err(e) if attempts < SEND_ATTEMPTS && is_transient(&e) => {
+                    log::warn!("Socket not ready for sending, retrying: {}", e);
+                    Timer::after(SEND_RETRY_DELAY).await;
+                }
+                Err(e) => {
+                    log::error!("Failed to send text, closing thread: {}", e);
+                    let _ = message.sender.send(Err(ObsError::ConnectionLost));
+                    return Err(HandlerError::Tungstenite(e));
+                }
+            }
+        }
+        let sent = match send_socket.start_send_unpin(WebSocketMessage::text(text.as_str())) {
+            Ok(()) => send_socket.flush().await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = sent {
+            log::error!("Failed to send text, closing thread: {}", e);
+            let _ = message.sender.send(Err(ObsError::ConnectionLost));
+            return Err(HandlerError::Tungstenite(e));
+        }
+        log::debug!("Sent text: {}", text);
+        if let Some(audit_log) = &shared.audit_log {
+            audit_log.write("request", &text);
+        }
+        // responses are only matched by message-id, so without one there's nothing to wait for
+        if let Some(message_id) = message.message_id {
+            let pending = PendingRequest {
+                request_type: message.request_type,
+                batch_request_types,
+                sender: message.sender,
+                sent_at,
+            };
+            pending_requests.insert(message_id, pending);
+        }
+        Ok(())
+    }
+
+    // starts a thread that periodically sends GetVersion to OBS and closes the connection if it fails
+    // the failure is reported with a HealthCheckFailed event before closing, after which it reconnects if it can
+    fn start_health_check(
+        thread_sender: UnboundedSender<Message>,
+        event_sender: UnboundedSender<Event>,
+        shared: sync::Arc<SharedState>,
+        interval: Duration,
+        reconnect: Option<AutoReconnect>,
+    ) -> Result<JoinHandle<()>, std::io::Error> {
+        log::debug!("Starting health check");
+        thread::Builder::new()
+            .name("health_check".to_string())
+            .spawn(move || {
+                smol::block_on(async move {
+                    let reason = loop {
+                        Timer::after(interval).await;
+                        if thread_sender.is_closed() {
+                            log::info!("Connection closed, stopping health check");
+                            return;
+                        }
+                        let get_version = GetVersion::builder().build();
+                        let ping = Obs::send_request(
+                            &thread_sender,
+                            &shared.config.message_id_prefix,
+                            &get_version,
+                        );
+                        futures::pin_mut!(ping);
+                        match future::select(ping, Timer::after(interval)).await {
+                            Either::Left((Ok(_), _)) => log::trace!("Health check ok"),
+                            // the server responded, it just sent something unexpected
+                            Either::Left((Err(e @ ObsError::InvalidResponse { .. }), _))
+                            | Either::Left((Err(e @ ObsError::MalformedResponse { .. }), _)) => {
+                                log::warn!("Unexpected health check response: {}", e)
+                            }
+                            Either::Left((Err(e), _)) => break e.to_string(),
+                            Either::Right(_) => break "timed out".to_string(),
+                        }
+                    };
+                    log::warn!("Health check failed, closing connection: {}", reason);
+                    let event = Event {
+                        stream_timecode: None,
+                        rec_timecode: None,
+                        update_type: events::EventType::HealthCheckFailed { reason },
+                    };
+                    shared
+                        .event_subscribers
+                        .lock()
+                        .unwrap()
+                        .retain(|subscriber| subscriber.unbounded_send(event.clone()).is_ok());
+                    let _ = event_sender.unbounded_send(event);
+                    thread_sender.close_channel();
+                    if let Some(reconnect) = reconnect {
+                        Obs::auto_reconnect(
+                            thread_sender,
+                            event_sender,
+                            shared,
+                            interval,
+                            reconnect,
+                        )
+                        .await;
+                    }
+                })
+            })
+    }
+
+    // reconnects to the address once per interval until it succeeds,
+    // or until the connection is closed, replaced with a manual reconnect or the Obs is dropped
+    async fn auto_reconnect(
+        thread_sender: UnboundedSender<Message>,
+        event_sender: UnboundedSender<Event>,
+        shared: sync::Arc<SharedState>,
+        interval: Duration,
+        reconnect: AutoReconnect,
+    ) {
+        // true while the Obs still holds the connection that failed the health check
+        let is_current = |connection: &Option<ConnectionData>| matches!(connection, Some(data) if data.thread_sender.same_receiver(&thread_sender));
+        loop {
+            Timer::after(interval).await;
+            match reconnect.connection.upgrade() {
+                Some(connection) if is_current(&connection.lock().unwrap()) => {}
+                _ => {
+                    log::info!("Connection closed or replaced, stopping reconnecting");
+                    return;
+                }
+            }
+            log::info!("Reconnecting after the health check failed");
+            let (address, port) = &reconnect.address;
+            let connecting = async {
+                let (tcp_stream, ws_addr) = Obs::connect_tcp(address, *port).await?;
+                Obs::start_connection(
+                    Box::new(Arc::new(tcp_stream)),
+                    &ws_addr,
+                    &shared,
+                    event_sender.clone(),
+                    Some(reconnect.clone()),
+                )
+                .await
+            };
+            let connection_data = match connecting.await {
+                Ok(connection_data) => connection_data,
+                Err(e) => {
+                    log::warn!("Failed to reconnect: {}", e);
+                    continue;
+                }
+            };
+            let new_sender = connection_data.thread_sender.clone();
+            // the connection is only replaced if it's still the one that failed, otherwise the new one is closed
+            let replaced = match reconnect.connection.upgrade() {
+                Some(connection) => {
+                    let mut connection = connection.lock().unwrap();
+                    if is_current(&connection) {
+                        shared.reset_connection_state();
+                        Ok(connection.replace(connection_data))
+                    } else {
+                        Err(connection_data)
+                    }
+                }
+                None => Err(connection_data),
+            };
+            match replaced {
+                Ok(old) => {
+                    if let Some(ConnectionData {
+                        mut socket_handle,
+                        thread_handle,
+                        ..
+                    }) = old
+                    {
+                        // the handler thread is already stopping since the channel was closed
+                        let _ = thread_handle.join();
+                        let _ = socket_handle.close(None).await;
+                    }
+                }
+                Err(mut connection_data) => {
+                    log::info!("Connection closed or replaced while reconnecting");
+                    new_sender.close_channel();
+                    let _ = connection_data.thread_handle.join();
+                    let _ = connection_data.socket_handle.close(None).await;
+                    return;
+                }
+            }
+            if shared.status.is_enabled() {
+                // events may have been missed while disconnected
+                let get_status = GetStreamingStatus::builder().build();
+                match Obs::send_request(&new_sender, &shared.config.message_id_prefix, &get_status)
+                    .await
+                {
+                    Ok(status) => shared.status.seed(&status),
+                    Err(e) => log::warn!("Failed to request the status after reconnecting: {}", e),
+                }
+            }
+            log::info!("Reconnected after the health check failed");
+            return;
+        }
+    }
+
+    // starts a thread that sends the latest volume set with a VolumeController once per interval
+    fn start_volume_controller(
+        thread_sender: UnboundedSender<Message>,
+        message_id_prefix: String,
+        source: String,
+        interval: Duration,
+        state: sync::Arc<Mutex<VolumeState>>,
+    ) -> Result<JoinHandle<()>, std::io::Error> {
+        log::debug!("Starting volume controller for {}", source);
+        thread::Builder::new()
+            .name("volume_controller".to_string())
+            .spawn(move || {
+                smol::block_on(async move {
+                    loop {
+                        Timer::after(interval).await;
+                        let (pending, closed) = {
+                            let mut state = state.lock().unwrap();
+                            (state.pending.take(), state.closed)
+                        };
+                        if let Some(volume) = pending {
+                            let set = SetVolume::builder()
+                                .source(source.as_str())
+                                .volume(volume)
+                                .build();
+                            if let Err(e) =
+                                Obs::send_request(&thread_sender, &message_id_prefix, &set).await
+                            {
+                                log::warn!("Failed to set the volume of {}: {}", source, e);
+                            }
+                        }
+                        if closed || thread_sender.is_closed() {
+                            log::debug!("Stopping volume controller for {}", source);
+                            return;
+                        }
+                    }
+                })
+            })
+    }
+
+    // starts the handler thread
+    fn start_handler(
+        send_socket: WebSocketHandle,
+        outgoing_receiver: UnboundedReceiver<Message>,
+        websocket_stream: WebSocketHandle,
+        event_sender: UnboundedSender<events::Event>,
+        shared: sync::Arc<SharedState>,
+    ) -> Result<HandlerHandle, std::io::Error> {
+        log::debug!("Starting handler");
+        thread::Builder::new()
+            .name("message_handler".to_string())
+            .spawn(move || {
+                smol::block_on(async move {
+                    // { request's message-id -> pending request waiting for the response }
+                    let mut pending_requests = HashMap::new();
+                    let handle_messages = Obs::handle_messages(
+                        send_socket,
+                        outgoing_receiver,
+                        websocket_stream,
+                        event_sender,
+                        &shared,
+                        &mut pending_requests,
+                    );
+                    let res = if shared.config.catch_handler_panics {
+                        // the pending requests are only resolved below, so a panic can't leave them in a bad state
+                        match panic::AssertUnwindSafe(handle_messages)
+                            .catch_unwind()
+                            .await
+                        {
+                            Ok(res) => res,
+                            Err(payload) => {
+                                let message = panic_message(&*payload);
+                                log::error!("Handler panicked, closing thread: {}", message);
+                                Err(HandlerError::Panicked(message))
+                            }
+                        }
+                    } else {
+                        handle_messages.await
+                    };
+                    // the pending requests will not get a response anymore
+                    for (_, pending) in pending_requests.drain() {
+                        let _ = pending.sender.send(Err(ObsError::ConnectionLost));
+                    }
+                    res
+                })
+            })
+    }
+
+    // handles messages until the connection is closed
+    async fn handle_messages(
+        mut send_socket: WebSocketHandle,
+        mut outgoing_receiver: UnboundedReceiver<Message>,
+        mut websocket_stream: WebSocketHandle,
+        mut event_sender: UnboundedSender<events::Event>,
+        shared: &SharedState,
+        pending_requests: &mut HashMap<String, PendingRequest>,
+    ) -> Result<(), HandlerError> {
+        // combine streams for outgoing (JSON from user) and incoming (WS from OBS) messages to thread
+        loop {
+            match future::select(outgoing_receiver.next(), websocket_stream.next()).await {
+                Either::Left((outgoing, _)) => match outgoing {
+                    Some(outgoing) => {
+                        Obs::handle_outgoing(&mut send_socket, pending_requests, shared, outgoing)
+                            .await?
+                    }
+                    None => {
+                        log::info!("Outgoing sender closed, closing thread");
+                        return Ok(());
+                    }
+                },
+                Either::Right((incoming, _)) => match incoming {
+                    Some(Ok(incoming)) => match incoming {
+                        WebSocketMessage::Text(incoming) => {
+                            // incoming text from OBS
+                            Obs::handle_incoming(
+                                pending_requests,
+                                &mut event_sender,
+                                shared,
+                                incoming,
+                            )
+                            .await?
+                        }
+                        WebSocketMessage::Close(close_frame) => {
+                            let reason = close_frame
+                                .map(|c| c.reason.into_owned())
+                                .unwrap_or_else(|| "no reason given".to_string());
+                            log::info!(
+                                "OBS closed WebSocket connection, closing thread: {}",
+                                reason
+                            );
+                            return Ok(());
+                        }
+                        WebSocketMessage::Binary(data) => {
+                            log::debug!("Received binary message of {} bytes", data.len());
+                            for interceptor in &shared.config.interceptors {
+                                interceptor.binary(&data);
+                            }
+                        }
+                        unexpected => {
+                            log::warn!("Unexpected websocket message: {}", unexpected);
+                            continue;
+                        }
+                    },
+                    Some(Err(e)) => {
+                        log::error!("Tungstenite error, closing thread: {}", e);
+                        return Err(HandlerError::Tungstenite(e));
+                    }
+                    None => {
+                        log::info!("OBS socket closed, closing thread");
+                        return Ok(());
+                    }
+                },
+            };
+        }
+    }
+}
+
+// rewrites the scene names in the JSON of a request and passes it to the interceptors before it's sent
+fn prepare_request(shared: &SharedState, request_type: &str, value: &mut Value) {
+    if let (Some(mapper), Value::Object(object)) = (&shared.config.scene_name_mapper, &mut *value) {
+        map_scene_names(None, object, |name| mapper.obs_name(name));
+    }
+    for interceptor in &shared.config.interceptors {
+        interceptor.before(request_type, value);
+    }
+}
+
+// checks if the error from waiting for the socket to be ready may go away if waited for again
+// WouldBlock is not included since async-tungstenite turns it into waiting
+fn is_transient(error: &tungstenite::Error) -> bool {
+    matches!(error, tungstenite::Error::Io(e) if e.kind() == io::ErrorKind::Interrupted)
+}
+
+// extracts the message from a panic payload
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+// collects the items in the given scene, including the items in its groups and nested scenes
+// if visible_only is true, hidden items are skipped along with the items in them
+fn collect_scene_items<'a>(
+    scenes: &'a [responses::Scene],
+    scene_name: &str,
+    visible_only: bool,
+    visited_scenes: &mut Vec<&'a str>,
+    items: &mut Vec<&'a SceneItem>,
+) {
+    fn collect<'a>(
+        scenes: &'a [responses::Scene],
+        scene_items: &'a [SceneItem],
+        visible_only: bool,
+        visited_scenes: &mut Vec<&'a str>,
+        items: &mut Vec<&'a SceneItem>,
+    ) {
+        for item in scene_items {
+            if visible_only && !item.visible() {
+                continue;
+            }
+            items.push(item);
+            if let Some(children) = &item.group_children {
+                collect(scenes, children, visible_only, visited_scenes, items);
+            }
+            if item.scene_item_type == SceneItemType::Scene {
+                collect_scene_items(scenes, &item.name, visible_only, visited_scenes, items);
+            }
+        }
+    }
+
+    if let Some(scene) = scenes.iter().find(|s| s.name == scene_name) {
+        // guards against scenes that contain each other
+        if visited_scenes.contains(&scene.name.as_str()) {
+            return;
+        }
+        visited_scenes.push(&scene.name);
+        collect(scenes, &scene.sources, visible_only, visited_scenes, items);
+    }
+}
+
+// address parsed from a ws:// or wss:// URL
+#[derive(Debug, PartialEq, Eq)]
+struct ObsUrl {
+    tls: bool,
+    host: String,
+    port: u16,
+}
+
+impl ObsUrl {
+    const DEFAULT_PORT: u16 = 4444;
+
+    fn parse(url: &str) -> Result<Self, ObsError> {
+        let invalid = || ObsError::InvalidAddress(url.to_string());
+        let (tls, rest) = if let Some(rest) = url.strip_prefix("ws://") {
+            (false, rest)
+        } else if let Some(rest) = url.strip_prefix("wss://") {
+            (true, rest)
+        } else {
+            return Err(invalid());
+        };
+        // ignore any path
+        let authority = rest.split('/').next().unwrap_or_default();
+        // IPv6 addresses are enclosed in brackets, e.g. [::1]:4444
+        let (host, port) = if let Some(bracketed) = authority.strip_prefix('[') {
+            let end = bracketed.find(']').ok_or_else(invalid)?;
+            (&bracketed[..end], bracketed[end + 1..].strip_prefix(':'))
+        } else {
+            let mut split = authority.splitn(2, ':');
+            (split.next().unwrap_or_default(), split.next())
+        };
+        if host.is_empty() {
+            return Err(invalid());
+        }
+        let port = match port {
+            Some(port) => port.parse().map_err(|_| invalid())?,
+            None => Self::DEFAULT_PORT,
+        };
+        Ok(ObsUrl {
+            tls,
+            host: host.to_string(),
+            port,
+        })
+    }
+}
+
+// message from the WebSocket server
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(untagged)]
+enum ResponseOrEvent {
+    Response(responses::ResponseWrapper),
+    Event(events::RawEvent),
+}
+
+// message used to communicate with the handler channel that owns the WebSocket connection
+#[derive(Debug)]
+struct Message {
+    // message id, None for raw text without one
+    message_id: Option<String>,
+    // request-type
+    request_type: String,
+    // JSON to be sent
+    value: Value,
+    // text to be sent as is instead of the JSON, see Obs::send_raw_text
+    raw_text: Option<String>,
+    // oneshot sender to send the result back with
+    // ok contains the entire message which has been checked to not be an error
+    // err contains the error message from OBS or the reason why there is no response
+    sender: OneshotSender<Result<Value, ObsError>>,
+}
+
+// a request sent to OBS that is waiting for a response
+struct PendingRequest {
+    // request-type
+    request_type: String,
+    // request-types of the requests in an ExecuteBatch, in order, empty for other requests
+    batch_request_types: Vec<String>,
+    // oneshot sender to send the result back with
+    sender: OneshotSender<Result<Value, ObsError>>,
+    // when the request was sent, for measuring latency
+    sent_at: Instant,
+}
+
+// state shared between Obs and the handler thread
+#[derive(Default)]
+struct SharedState {
+    config: ObsConfig,
+    // senders for the receivers created with Obs::subscribe
+    event_subscribers: Mutex<Vec<UnboundedSender<Event>>>,
+    // see Obs::enable_status_tracking
+    status: StatusTracker,
+    // cached GetVersion response, see Obs::require_request
+    version: Mutex<Option<ServerVersion>>,
+    // see Obs::scene_names
+    names: Interner,
+    // responses without a pending request, e.g. duplicates or responses to canceled requests
+    unexpected_responses: AtomicUsize,
+    // see ObsConfig::audit_log
+    audit_log: Option<AuditLog>,
+    // events that were not deserialized because nothing would have received them
+    dropped_events: AtomicUsize,
+    // see ObsConfig::defer_until_authenticated
+    auth_gate: AuthGate,
+    // see ObsConfig::max_concurrent_requests
+    request_limiter: Option<Semaphore>,
+    // see Obs::saved_replays
+    saved_files: Mutex<Vec<String>>,
+}
+
+impl SharedState {
+    // resets the state that belongs to the previous connection before reconnecting
+    fn reset_connection_state(&self) {
+        // the server may have changed
+        *self.version.lock().unwrap() = None;
+        if self.config.defer_until_authenticated {
+            // the new connection has to authenticate again
+            self.auth_gate.close();
+        }
+    }
+}
+
+// the version of the server as reported by GetVersion
+#[derive(Clone)]
+enum ServerVersion {
+    Known(responses::GetVersion),
+    // the response couldn't be parsed, e.g. because the server is not obs-websocket 4.x
+    Unknown,
+}
+
+// container for data related to the WebSocket connection
+struct ConnectionData {
+    socket_handle: WebSocketHandle,
+    thread_handle: HandlerHandle,
+    thread_sender: UnboundedSender<Message>,
+}
+
+// used by the health check to reconnect to the address after it fails
+#[derive(Clone)]
+struct AutoReconnect {
+    address: (String, u16),
+    // the connection of the Obs, which is gone once the Obs is dropped
+    connection: sync::Weak<Mutex<Option<ConnectionData>>>,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        animation::Transform,
+        common_types::{self, *},
+        events::EventType,
+    };
+    use async_tungstenite::tungstenite::server::accept;
+    use serde_json::{json, Value};
+    use std::{
+        net::TcpListener,
+        sync::atomic::AtomicBool,
+        thread::{spawn, JoinHandle},
+    };
+
+    fn init_logger() {
+        let _ = env_logger::builder().is_test(true).try_init();
+    }
+
+    fn init_without_server(port: u16, config: ObsConfig) -> Obs {
+        log::debug!("initiating without server at {}", port);
+        smol::block_on(Obs::connect_with_config("localhost", port, config))
+            .expect("failed to connect")
+            .0
+    }
+
+    // one direction of an in-memory connection
+    #[derive(Default)]
+    struct Pipe {
+        buffer: std::collections::VecDeque<u8>,
+        reader: Option<std::task::Waker>,
+        closed: bool,
+    }
+
+    // one end of an in-memory connection, clones share the same end
+    #[derive(Clone)]
+    struct MemoryTransport {
+        incoming: sync::Arc<Mutex<Pipe>>,
+        outgoing: sync::Arc<Mutex<Pipe>>,
+    }
+
+    impl MemoryTransport {
+        // creates both ends of a connection
+        fn pair() -> (Self, Self) {
+            let a = sync::Arc::new(Mutex::new(Pipe::default()));
+            let b = sync::Arc::new(Mutex::new(Pipe::default()));
+            let first = MemoryTransport {
+                incoming: a.clone(),
+                outgoing: b.clone(),
+            };
+            let second = MemoryTransport {
+                incoming: b,
+                outgoing: a,
+            };
+            (first, second)
+        }
+    }
+
+    impl futures::io::AsyncRead for MemoryTransport {
+        fn poll_read(
+            self: std::pin::Pin<&mut Self>,
+            cx: &mut std::task::Context<'_>,
+            buf: &mut [u8],
+        ) -> std::task::Poll<io::Result<usize>> {
+            let mut pipe = self.incoming.lock().unwrap();
+            if pipe.buffer.is_empty() {
+                if pipe.closed {
+                    return std::task::Poll::Ready(Ok(0));
+                }
+                pipe.reader = Some(cx.waker().clone());
+                return std::task::Poll::Pending;
+            }
+            let len = buf.len().min(pipe.buffer.len());
+            for (byte, read) in buf.iter_mut().zip(pipe.buffer.drain(..len)) {
+                *byte = read;
+            }
+            std::task::Poll::Ready(Ok(len))
+        }
+    }
+
+    impl futures::io::AsyncWrite for MemoryTransport {
+        fn poll_write(
+            self: std::pin::Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+            buf: &[u8],
+        ) -> std::task::Poll<io::Result<usize>> {
+            let mut pipe = self.outgoing.lock().unwrap();
+            pipe.buffer.extend(buf);
+            if let Some(reader) = pipe.reader.take() {
+                reader.wake();
+            }
+            std::task::Poll::Ready(Ok(buf.len()))
+        }
+
+        fn poll_flush(
+            self: std::pin::Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<io::Result<()>> {
+            std::task::Poll::Ready(Ok(()))
+        }
+
+        fn poll_close(
+            self: std::pin::Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<io::Result<()>> {
+            let mut pipe = self.outgoing.lock().unwrap();
+            pipe.closed = true;
+            if let Some(reader) = pipe.reader.take() {
+                reader.wake();
+            }
+            std::task::Poll::Ready(Ok(()))
+        }
+    }
+
+    impl Transport for MemoryTransport {
+        fn clone_transport(&self) -> Box<dyn Transport> {
+            Box::new(self.clone())
+        }
+    }
+
+    // a step taken by the mock server
+    enum Mock {
+        // reads a request and responds to it with the given value
+        Respond(Value),
+        // like Respond, but waits for the given duration before responding
+        RespondAfter(Duration, Value),
+        // sends the given value, e.g. an event
+        Send(Value),
+        // sends the given bytes as a binary message
+        SendBinary(Vec<u8>),
+        // sends the previous message again
+        RepeatLast,
+    }
+
+    fn init(responses: Vec<Value>) -> (Obs, JoinHandle<Vec<Value>>) {
+        init_mock(responses.into_iter().map(Mock::Respond).collect())
+    }
+
+    fn init_mock(steps: Vec<Mock>) -> (Obs, JoinHandle<Vec<Value>>) {
+        init_mock_with_config(steps, ObsConfig::default())
+    }
+
+    fn init_mock_with_config(steps: Vec<Mock>, config: ObsConfig) -> (Obs, JoinHandle<Vec<Value>>) {
+        let (port, handle) = spawn_mock(steps);
+        let obs = init_without_server(port, config);
+        (obs, handle)
+    }
+
+    // starts a mock server that takes the given steps, returns its port
+    fn spawn_mock(steps: Vec<Mock>) -> (u16, JoinHandle<Vec<Value>>) {
+        let server = TcpListener::bind("localhost:0").expect("failed to bind");
+        let port = server.local_addr().expect("local addr").port();
+        log::info!("mock server started at {}", port);
+        let handle = spawn(move || {
+            let mut actual_requests = vec![];
+            let (stream, _) = server.accept().expect("accept");
+            log::info!("incoming connection");
+            let mut websocket = accept(stream).expect("failed to accept");
+            let mut last = None;
+            for step in steps {
+                let delay = match &step {
+                    Mock::RespondAfter(delay, _) => *delay,
+                    _ => Duration::from_secs(0),
+                };
+                let message = match step {
+                    Mock::Respond(mut response) | Mock::RespondAfter(_, mut response) => {
+                        let message = websocket.read_message().expect("failed to read message");
+                        log::info!("read message {:#?}", message);
+                        let parsed = serde_json::from_str::<Value>(&message.to_string())
+                            .expect("failed to deserialize");
+                        let message_id = parsed
+                            .as_object()
+                            .as_ref()
+                            .unwrap()
+                            .get("message-id")
+                            .unwrap()
+                            .clone();
+                        actual_requests.push(parsed);
+                        response
+                            .as_object_mut()
+                            .unwrap()
+                            .insert("message-id".to_string(), message_id);
+                        thread::sleep(delay);
+                        log::info!("responding with {:#?}", response);
+                        WebSocketMessage::Text(response.to_string())
+                    }
+                    Mock::Send(message) => {
+                        log::info!("sending {:#?}", message);
+                        WebSocketMessage::Text(message.to_string())
+                    }
+                    Mock::SendBinary(data) => {
+                        log::info!("sending {} bytes", data.len());
+                        WebSocketMessage::Binary(data)
+                    }
+                    Mock::RepeatLast => {
+                        log::info!("repeating the previous message");
+                        last.clone().expect("no previous message")
+                    }
+                };
+                last = Some(message.clone());
+                websocket.write_message(message).expect("failed to write");
+            }
+            log::info!("closing mock server");
+            websocket.close(None).expect("failed to close");
+            actual_requests
+        });
+        (port, handle)
+    }
+
+    // a scene item as sent by OBS
+    fn scene_item_json(id: i32, name: &str, item_type: &str) -> Value {
+        json!({
+            "cx": 100.0,
+            "cy": 100.0,
+            "id": id,
+            "locked": false,
+            "name": name,
+            "render": true,
+            "source_cx": 100,
+            "source_cy": 100,
+            "type": item_type,
+            "volume": 1.0,
+            "x": 0.0,
+            "y": 0.0,
+        })
+    }
+
+    // a GetSceneItemProperties response for an item with the given position, scale and source size
+    fn scene_item_properties_json(
+        name: &str,
+        position: (f64, f64),
+        scale: (f64, f64),
+        source_size: (i32, i32),
+    ) -> Value {
+        json!({
+            "status": "ok",
+            "name": name,
+            "position": { "x": position.0, "y": position.1, "alignment": 5 },
+            "rotation": 0.0,
+            "scale": { "x": scale.0, "y": scale.1 },
+            "crop": { "top": 0, "right": 0, "bottom": 0, "left": 0 },
+            "visible": true,
+            "locked": false,
+            "bounds": { "type": "OBS_BOUNDS_NONE", "alignment": 0, "x": 0.0, "y": 0.0 },
+            "sourceWidth": source_size.0,
+            "sourceHeight": source_size.1,
+            "width": f64::from(source_size.0) * scale.0.abs(),
+            "height": f64::from(source_size.1) * scale.1.abs(),
+        })
+    }
+
+    // runs the future until the timeout and drops it, e.g. while it's waiting for a response
+    fn drop_after<F: Future>(future: F, timeout: Duration) {
+        futures::pin_mut!(future);
+        smol::block_on(future::select(future, Timer::after(timeout)));
+    }
+
+    // checks that the connection is still usable once the response to a dropped request has arrived
+    fn assert_still_usable(obs: &Obs) {
+        thread::sleep(Duration::from_millis(300));
+        assert!(obs.is_connected());
+        smol::block_on(obs.request(&SetHeartbeat::builder().enable(false).build()))
+            .expect("request returned err");
+    }
+
+    fn output_json(name: &str, output_type: &str, active: bool) -> Value {
+        json!({
+            "name": name,
+            "type": output_type,
+            "width": 1920,
+            "height": 1080,
+            "flags": {
+                "rawValue": 6,
+                "audio": true,
+                "video": true,
+                "encoded": true,
+                "multiTrack": false,
+                "service": false,
+            },
+            "settings": {},
+            "active": active,
+            "reconnecting": false,
+            "congestion": 0.0,
+            "totalFrames": 0,
+            "droppedFrames": 0,
+            "totalBytes": 0,
+        })
+    }
+
+    fn request_test<T>(
+        expected_requests: Vec<Value>,
+        expected_responses: Vec<Value>,
+        request: T,
+        expected: T::Response,
+    ) where
+        T: Request + PartialEq + std::fmt::Debug,
+        T::Response: PartialEq + std::fmt::Debug,
+    {
+        let (obs, handle) = init(expected_responses);
+        let res = smol::block_on(obs.request(&request)).expect("request returned err");
+        let actual_requests = handle.join().expect("failed to join");
+        smol::block_on(obs.disconnect()).unwrap();
+        for (request, mut actual_request) in expected_requests.into_iter().zip(actual_requests) {
+            // ignore message-id in the comparison
+            actual_request
+                .as_object_mut()
+                .unwrap()
+                .remove("message-id")
+                .unwrap();
+            assert_eq!(
+                request, actual_request,
+                "request (left) did not match expected (right)"
+            );
+        }
+        assert_eq!(
+            res, expected,
+            "result (left) did not match expected (right)"
+        );
+    }
+
+    #[test]
+    fn memory_transport() {
+        init_logger();
+        let (client, server) = MemoryTransport::pair();
+        let handle = thread::spawn(move || {
+            smol::block_on(async move {
+                let mut websocket = async_tungstenite::accept_async(server)
+                    .await
+                    .expect("failed to accept");
+                let request = websocket
+                    .next()
+                    .await
+                    .expect("connection closed")
+                    .expect("failed to read message");
+                let request = serde_json::from_str::<Value>(&request.to_string())
+                    .expect("failed to deserialize");
+                let response = json!({
+                    "message-id": request["message-id"],
+                    "status": "ok",
+                    "version": 1.1,
+                    "obs-websocket-version": "4.8.0",
+                    "obs-studio-version": "25.0.8",
+                    "available-requests": "GetVersion",
+                });
+                websocket
+                    .send(WebSocketMessage::Text(response.to_string()))
+                    .await
+                    .expect("failed to write");
+                request
+            })
+        });
+        let obs = smol::block_on(Obs::connect_with_transport(
+            client,
+            "ws://localhost:4444",
+            ObsConfig::default(),
+        ))
+        .expect("failed to connect")
+        .0;
+        let version = smol::block_on(obs.request(&GetVersion::builder().build()))
+            .expect("request returned err");
+        let request = handle.join().expect("join");
+        smol::block_on(obs.disconnect()).unwrap();
+
+        assert_eq!(request["request-type"], "GetVersion");
+        assert_eq!(version.obs_websocket_version, "4.8.0");
+    }
+
+    #[test]
+    fn get_version() {
+        init_logger();
+
+        let request = json!({
+        "request-type": "GetVersion",
+        });
+        let response = json!({
+            "status": "ok",
+            "version": 1.1,
+            "obs-websocket-version": "4.7.0",
+            "obs-studio-version": "24.0.3",
+            "available-requests": "Request1,Request2"
+        });
+        let req = GetVersion::builder().build();
+        let expected = responses::GetVersion {
+            version: 1.1,
+            obs_websocket_version: "4.7.0".to_string(),
+            obs_studio_version: "24.0.3".to_string(),
+            available_requests: vec!["Request1".to_string(), "Request2".to_string()],
+        };
+        request_test(vec![request], vec![response], req, expected);
+    }
+
+    #[test]
+    fn send_raw_text() {
+        init_logger();
+        let (obs, handle) = init(vec![json!({
+            "status": "ok",
+            "version": 1.1,
+            "obs-websocket-version": "4.7.0",
+            "obs-studio-version": "24.0.3",
+            "available-requests": "GetVersion",
+        })]);
+        let text = r#"{"request-type":"GetVersion","message-id":"raw"}"#.to_string();
+        let response = smol::block_on(obs.send_raw_text(text, Duration::from_secs(5)))
+            .expect("request returned err")
+            .expect("no response");
+        let actual_requests = handle.join().expect("failed to join");
+        smol::block_on(obs.disconnect()).unwrap();
+
+        assert_eq!(
+            actual_requests[0],
+            json!({ "request-type": "GetVersion", "message-id": "raw" })
+        );
+        assert_eq!(response["obs-websocket-version"], "4.7.0");
+    }
+
+    #[test]
+    fn send_raw_text_late_response() {
+        init_logger();
+        let server = TcpListener::bind("localhost:0").expect("bind");
+        let port = server.local_addr().expect("local addr").port();
+        let handle = thread::spawn(move || {
+            let (stream, _) = server.accept().expect("accept");
+            let mut websocket = accept(stream).expect("failed to accept");
+            // the first response arrives after the client has stopped waiting for it
+            for delay in &[Duration::from_millis(300), Duration::from_millis(0)] {
+                let request = websocket.read_message().expect("failed to read message");
+                let request = serde_json::from_str::<Value>(&request.to_string()).unwrap();
+                thread::sleep(*delay);
+                let response = json!({
+                    "message-id": request["message-id"],
+                    "status": "ok",
+                });
+                websocket
+                    .write_message(WebSocketMessage::Text(response.to_string()))
+                    .expect("failed to write");
+            }
+            websocket
+        });
+        let obs = init_without_server(port, ObsConfig::default());
+        let text = r#"{"request-type":"GetVersion","message-id":"raw"}"#.to_string();
+        let timed_out = smol::block_on(obs.send_raw_text(text, Duration::from_millis(50)))
+            .expect("request returned err");
+        thread::sleep(Duration::from_millis(400));
+        let connected = obs.is_connected();
+        let res = smol::block_on(obs.request(&SetHeartbeat::builder().enable(false).build()));
+        let _websocket = handle.join().expect("join");
+        smol::block_on(obs.disconnect()).unwrap();
+
+        assert!(timed_out.is_none());
+        assert!(connected);
+        res.expect("request returned err");
+    }
+
+    #[test]
+    fn get_auth_required_true() {
+        init_logger();
+
+        let request = json!({
+            "request-type": "GetAuthRequired",
+        });
+        let response = json!({
+            "status": "ok",
+            "authRequired": true,
+            "challenge": "ch",
+            "salt": "sa",
+        });
+        let req = GetAuthRequired::builder().build();
+        let expected = responses::GetAuthRequired {
+            auth_required: true,
+            challenge: Some("ch".to_string()),
+            salt: Some("sa".to_string()),
+        };
+        request_test(vec![request], vec![response], req, expected);
+    }
+
+    #[test]
+    fn get_auth_required_false() {
+        init_logger();
+
+        let request = json!({
+            "request-type": "GetAuthRequired",
+        });
+        let response = json!({
+            "status": "ok",
+            "authRequired": false,
+        });
+        let req = GetAuthRequired::builder().build();
+        let expected = responses::GetAuthRequired {
+            auth_required: false,
+            challenge: None,
+            salt: None,
+        };
+        request_test(vec![request], vec![response], req, expected);
+    }
+
+    #[test]
+    fn authenticate() {
+        init_logger();
+
+        let requests = vec![
+            json!({
+                "request-type": "GetAuthRequired",
+            }),
+            json!({
+                "request-type": "Authenticate",
+                "auth": "Z69J+b7C5Zj7jIXlqVp/xjp36sFSmpJpxZ41GN/UTu4=",
+            }),
+        ];
+        let responses = vec![
+            json!({
+                "status": "ok",
+                "authRequired": true,
+                "challenge": "123",
+                "salt": "456",
+            }),
+            json!({
+                "status": "ok",
+            }),
+        ];
+        let expected = responses::Empty {};
+        let (obs, handle) = init(responses);
+        let res = smol::block_on(obs.authenticate("todo")).expect("authenticate");
+        let actual_requests = handle.join().expect("join");
+        smol::block_on(obs.disconnect()).unwrap();
+        for (request, mut actual_request) in requests.into_iter().zip(actual_requests) {
+            // remove message-id
+            actual_request
+                .as_object_mut()
+                .unwrap()
+                .remove("message-id")
+                .unwrap();
+            assert_eq!(
+                request, actual_request,
+                "request (left) did not match expected (right)"
+            );
+        }
+        assert_eq!(
+            res, expected,
+            "result (left) did not match expected (right)"
+        );
+    }
+
+    #[test]
+    fn get_stats() {
+        init_logger();
+
+        let request = json!({
+            "request-type": "GetStats",
+        });
+        let response = json!({
+            "status": "ok",
+            "stats": {
+                "fps": 0.0,
+                "render-total-frames": 1,
+                "render-missed-frames": 2,
+                "output-total-frames": 3,
+                "output-skipped-frames": 4,
+                "average-frame-time": 5.0,
+                "cpu-usage": 6.0,
+                "memory-usage": 7.0,
+                "free-disk-space": 8.0,
+            }
+        });
+        let req = GetStats::builder().build();
+        let expected = responses::GetStats {
+            stats: ObsStats {
+                fps: 0.0,
+                render_total_frames: 1,
+                render_missed_frames: 2,
+                output_total_frames: 3,
+                output_skipped_frames: 4,
+                average_frame_time: 5.0,
+                cpu_usage: 6.0,
+                memory_usage: 7.0,
+                free_disk_space: 8.0,
+            },
+        };
+        request_test(vec![request], vec![response], req, expected);
+    }
+
+    #[test]
+    fn get_video_info() {
+        init_logger();
+
+        let request = json!({
+            "request-type": "GetVideoInfo",
+        });
+        let response = json!({
+            "status": "ok",
+            "baseWidth": 0,
+            "baseHeight": 1,
+            "outputWidth": 2,
+            "outputHeight": 3,
+            "scaleType": "VIDEO_SCALE_BICUBIC",
+            "fps": 4.0,
+            "videoFormat": "VIDEO_FORMAT_NV12",
+            "colorSpace": "VIDEO_CS_601",
+            "colorRange": "VIDEO_RANGE_PARTIAL",
+        });
+        let req = GetVideoInfo::builder().build();
+        let expected = responses::GetVideoInfo {
+            base_width: 0,
+            base_height: 1,
+            output_width: 2,
+            output_height: 3,
+            scale_type: responses::ScaleType::Bicubic,
+            fps: 4.0,
+            video_format: responses::VideoFormat::NV12,
+            color_space: responses::ColorSpace::CS601,
+            color_range: responses::ColorRange::Partial,
+        };
+        request_test(vec![request], vec![response], req, expected);
+    }
+
+    #[test]
+    fn set_source_settings_typed() {
+        init_logger();
+        let settings = BrowserSourceSettings {
+            is_local_file: false,
+            local_file: String::new(),
+            url: "https://example.com".to_string(),
+            css: String::new(),
+            width: 1920,
+            height: 1080,
+            fps: 30,
+            shutdown: true,
+            restart_when_active: false,
+        };
+        let source_settings = json!({
+            "is_local_file": false,
+            "local_file": "",
+            "url": "https://example.com",
+            "css": "",
+            "width": 1920,
+            "height": 1080,
+            "fps": 30,
+            "shutdown": true,
+            "restart_when_active": false,
+        });
+        request_test(
+            vec![json!({
+                "request-type": "SetSourceSettings",
+                "sourceName": "browser",
+                "sourceType": "browser_source",
+                "sourceSettings": source_settings,
+            })],
+            vec![json!({
+                "status": "ok",
+                "sourceName": "browser",
+                "sourceType": "browser_source",
+                "sourceSettings": source_settings,
+            })],
+            SetSourceSettings::typed("browser", Some(SourceKind::BrowserSource), &settings)
+                .unwrap(),
+            responses::SetSourceSettings {
+                source_name: "browser".to_string(),
+                source_type: SourceKind::BrowserSource,
+                source_settings: source_settings.clone(),
+            },
+        );
+    }
+
+    #[test]
+    fn set_transition_duration_from_duration() {
+        init_logger();
+        request_test(
+            vec![json!({
+                "request-type": "SetTransitionDuration",
+                "duration": 500,
+            })],
+            vec![json!({ "status": "ok" })],
+            SetTransitionDuration::from_duration(Duration::from_millis(500)),
+            responses::Empty {},
+        );
+        assert_eq!(
+            SetTransitionDuration::from_duration(Duration::from_secs(u64::MAX)).duration,
+            i32::MAX
+        );
+    }
+
+    #[test]
+    fn open_projector_windowed() {
+        init_logger();
+        request_test(
+            vec![json!({
+                "request-type": "OpenProjector",
+                "type": "Scene",
+                "monitor": -1,
+                "geometry": null,
+                "name": "Scene 2",
+            })],
+            vec![json!({ "status": "ok" })],
+            OpenProjector::windowed(ProjectorType::Scene, Some("Scene 2")),
+            responses::Empty {},
+        );
+    }
+
+    #[test]
+    fn open_projector_fullscreen() {
+        init_logger();
+        request_test(
+            vec![json!({
+                "request-type": "OpenProjector",
+                "type": "Multiview",
+                "monitor": 1,
+                "geometry": null,
+                "name": null,
+            })],
+            vec![json!({ "status": "ok" })],
+            OpenProjector::fullscreen(ProjectorType::Multiview, 1, None),
+            responses::Empty {},
+        );
+    }
+
+    #[test]
+    fn list_outputs() {
+        init_logger();
+
+        let request = json!({
+            "request-type": "ListOutputs",
+        });
+        let response = json!({
+            "status": "ok",
+            "outputs": [
+                {
+                    "name": "simple_file_output",
+                    "type": "ffmpeg_muxer",
+                    "width": 0,
+                    "height": 1,
+                    "flags": {
+                        "rawValue": 6,
+                        "audio": true,
+                        "video": true,
+                        "encoded": true,
+                        "multiTrack": true,
+                        "service": true,
+                    },
+                    "settings": {},
+                    "active": false,
+                    "reconnecting": false,
+                    "congestion": 2.0,
+                    "totalFrames": 3,
+                    "droppedFrames": 4,
+                    "totalBytes": 5,
+                }
+            ],
+        });
+        let req = ListOutputs::builder().build();
+        let expected = responses::ListOutputs {
+            outputs: vec![responses::Output {
+                name: "simple_file_output".to_string(),
+                output_type: common_types::OutputKind::FfmpegMuxer,
+                width: 0,
+                height: 1,
+                flags: responses::Flags {
+                    raw_value: 6,
+                    audio: true,
+                    video: true,
+                    encoded: true,
+                    multi_track: true,
+                    service: true,
+                },
+                settings: Value::Object(serde_json::Map::new()),
+                active: false,
+                reconnecting: false,
+                congestion: 2.0,
+                total_frames: 3,
+                dropped_frames: 4,
+                total_bytes: 5,
+            }],
+        };
+        request_test(vec![request], vec![response], req, expected);
+    }
+
+    #[test]
+    fn get_output_info() {
+        init_logger();
+
+        let request = json!({
+            "request-type": "GetOutputInfo",
+            "outputName": "output1",
+        });
+        let response = json!({
+            "status": "ok",
+            "outputInfo": {
+                "name": "simple_file_output",
+                "type": "ffmpeg_muxer",
+                "width": 0,
+                "height": 1,
+                "flags": {
+                    "rawValue": 6,
+                    "audio": true,
+                    "video": true,
+                    "encoded": true,
+                    "multiTrack": true,
+                    "service": true,
+                },
+                "settings": {},
+                "active": false,
+                "reconnecting": false,
+                "congestion": 2.0,
+                "totalFrames": 3,
+                "droppedFrames": 4,
+                "totalBytes": 5,
+            },
+        });
+        let req = GetOutputInfo::builder().output_name("output1").build();
+        let expected = responses::GetOutputInfo {
+            output_info: responses::Output {
+                name: "simple_file_output".to_string(),
+                output_type: common_types::OutputKind::FfmpegMuxer,
+                width: 0,
+                height: 1,
+                flags: responses::Flags {
+                    raw_value: 6,
+                    audio: true,
+                    video: true,
+                    encoded: true,
+                    multi_track: true,
+                    service: true,
+                },
+                settings: Value::Object(serde_json::Map::new()),
+                active: false,
+                reconnecting: false,
+                congestion: 2.0,
+                total_frames: 3,
+                dropped_frames: 4,
+                total_bytes: 5,
+            },
+        };
+        request_test(vec![request], vec![response], req, expected);
+    }
+
+    #[test]
+    fn get_scene_item_properties() {
+        init_logger();
+
+        let request = json!({
+            "request-type": "GetSceneItemProperties",
+            "scene-name": "scene",
+            "item": "source"
+        });
+        let response = json!({
+            "status": "ok",
+            "name": "source",
+            "position": {
+                "x": 0,
+                "y": 1,
+                "alignment": 2,
+            },
+            "rotation": 3.0,
+            "scale": {
+                "x": 4.0,
+                "y": 5.0,
+            },
+            "crop": {
+                "top": 6,
+                "right": 7,
+                "bottom": 8,
+                "left": 9,
+            },
+            "visible": true,
+            "locked": true,
+            "bounds": {
+                "type": "OBS_BOUNDS_STRETCH",
+                "alignment": 10,
+                "x": 11.0,
+                "y": 12.0,
+            },
+            "sourceWidth": 13,
+            "sourceHeight": 14,
+            "width": 15.0,
+            "height": 16.0,
+        });
+        let req = GetSceneItemProperties::builder()
+            .scene_name("scene")
+            .item("source")
+            .build();
+        let expected = responses::GetSceneItemProperties {
+            name: "source".to_string(),
+            position: common_types::Position {
+                x: 0.0,
+                y: 1.0,
+                alignment: 2,
+            },
+            rotation: 3.0,
+            scale: common_types::Scale { x: 4.0, y: 5.0 },
+            crop: common_types::Crop {
+                top: 6,
+                right: 7,
+                bottom: 8,
+                left: 9,
+            },
+            visible: true,
+            locked: true,
+            bounds: common_types::Bounds {
+                bounds_type: common_types::BoundsType::Stretch,
+                alignment: 10,
+                x: 11.0,
+                y: 12.0,
+            },
+            source_width: 13,
+            source_height: 14,
+            width: 15.0,
+            height: 16.0,
+        };
+        request_test(vec![request], vec![response], req, expected);
+    }
+
+    #[test]
+    fn get_scene_item_list() {
+        init_logger();
+
+        let request = json!({
+            "request-type": "GetSceneItemList",
+            "sceneName": "scene",
+        });
+        let response = json!({
+            "status": "ok",
+            "sceneName": "scene",
+            "sceneItems": [
+                {
+                    "itemId": 1,
+                    "sourceKind": "image_source",
+                    "sourceName": "image",
+                },
+                {
+                    "itemId": 2,
+                    "sourceKind": "group",
+                    "sourceName": "group",
+                },
+                {
+                    "itemId": 3,
+                    "sourceKind": "vlc_source",
+                    "sourceName": "playlist",
+                },
+            ],
+        });
+        let req = GetSceneItemList::builder().scene_name("scene").build();
+        let item =
+            |item_id: i32, source_kind: &str, source_name: &str| responses::SceneItemListItem {
+                item_id,
+                source_kind: source_kind.to_string(),
+                source_name: source_name.to_string(),
+            };
+        let expected = responses::GetSceneItemList {
+            scene_name: "scene".to_string(),
+            scene_items: vec![
+                item(1, "image_source", "image"),
+                item(2, "group", "group"),
+                item(3, "vlc_source", "playlist"),
+            ],
+        };
+        request_test(vec![request], vec![response], req, expected);
+    }
+
+    #[test]
+    fn get_audio_monitor_type() {
+        init_logger();
+
+        let request = json!({
+            "request-type": "GetAudioMonitorType",
+            "sourceName": "Mic/Aux",
+        });
+        let response = json!({
+            "status": "ok",
+            "monitorType": "monitorAndOutput",
+        });
+        let req = GetAudioMonitorType::builder()
+            .source_name("Mic/Aux")
+            .build();
+        let expected = responses::GetAudioMonitorType {
+            monitor_type: common_types::MonitorType::MonitorAndOutput,
+        };
+        request_test(vec![request], vec![response], req, expected);
+    }
+
+    #[test]
+    fn trigger_hotkey_by_sequence() {
+        init_logger();
+
+        let request = json!({
+            "request-type": "TriggerHotkeyBySequence",
+            "keyId": "OBS_KEY_F1",
+            "keyModifiers": {
+                "shift": true,
+                "control": true,
+            },
+        });
+        let response = json!({ "status": "ok" });
+        let req = TriggerHotkeyBySequence::builder()
+            .key_id("OBS_KEY_F1")
+            .key_modifiers(KeyModifiers::builder().shift(true).control(true).build())
+            .build();
+        request_test(vec![request], vec![response], req, responses::Empty {});
+    }
+
+    #[test]
+    fn trigger_hotkey_by_sequence_without_modifiers() {
+        init_logger();
+
+        let request = json!({
+            "request-type": "TriggerHotkeyBySequence",
+            "keyId": "OBS_KEY_F1",
+        });
+        let response = json!({ "status": "ok" });
+        let req = TriggerHotkeyBySequence::builder()
+            .key_id("OBS_KEY_F1")
+            .build();
+        request_test(vec![request], vec![response], req, responses::Empty {});
+    }
+
+    #[test]
+    fn next_media() {
+        init_logger();
+
+        let request = json!({
+            "request-type": "NextMedia",
+            "sourceName": "playlist",
+        });
+        let response = json!({ "status": "ok" });
+        let req = NextMedia::builder().source_name("playlist").build();
+        request_test(vec![request], vec![response], req, responses::Empty {});
+    }
+
+    #[test]
+    fn previous_media() {
+        init_logger();
+
+        let request = json!({
+            "request-type": "PreviousMedia",
+            "sourceName": "playlist",
+        });
+        let response = json!({ "status": "ok" });
+        let req = PreviousMedia::builder().source_name("playlist").build();
+        request_test(vec![request], vec![response], req, responses::Empty {});
+    }
+
+    #[test]
+    fn get_media_duration() {
+        init_logger();
+
+        let request = json!({
+            "request-type": "GetMediaDuration",
+            "sourceName": "video",
+        });
+        // longer than i32::MAX milliseconds
+        let response = json!({
+            "status": "ok",
+            "mediaDuration": 3_000_000_000_i64,
+        });
+        let req = GetMediaDuration::builder().source_name("video").build();
+        let expected = responses::GetMediaDuration {
+            media_duration: 3_000_000_000,
+        };
+        request_test(vec![request], vec![response], req, expected);
+    }
+
+    #[test]
+    fn get_media_time() {
+        init_logger();
+
+        let request = json!({
+            "request-type": "GetMediaTime",
+            "sourceName": "video",
+        });
+        let response = json!({
+            "status": "ok",
+            "timestamp": 12345,
+        });
+        let req = GetMediaTime::builder().source_name("video").build();
+        let expected = responses::GetMediaTime { timestamp: 12345 };
+        request_test(vec![request], vec![response], req, expected);
+    }
+
+    #[test]
+    fn set_media_time() {
+        init_logger();
+
+        let request = json!({
+            "request-type": "SetMediaTime",
+            "sourceName": "video",
+            "timestamp": 3_000_000_000_i64,
+        });
+        let response = json!({ "status": "ok" });
+        let req = SetMediaTime::builder()
+            .source_name("video")
+            .timestamp(3_000_000_000)
+            .build();
+        request_test(vec![request], vec![response], req, responses::Empty {});
+    }
+
+    #[test]
+    fn scrub_media() {
+        init_logger();
+
+        let request = json!({
+            "request-type": "ScrubMedia",
+            "sourceName": "video",
+            "timeOffset": -5000,
+        });
+        let response = json!({ "status": "ok" });
+        let req = ScrubMedia::builder()
+            .source_name("video")
+            .time_offset(-5000)
+            .build();
+        request_test(vec![request], vec![response], req, responses::Empty {});
+    }
+
+    #[test]
+    fn item_exists() {
+        init_logger();
+        let (obs, handle) = init(vec![json!({
+            "status": "error",
+            "error": "specified scene item doesn't exist",
+        })]);
+        let exists = smol::block_on(obs.item_exists(Some("Scene"), "Missing"))
+            .expect("request returned err");
+        let actual_requests = handle.join().expect("failed to join");
+        smol::block_on(obs.disconnect()).unwrap();
+        assert_eq!(actual_requests[0]["request-type"], "GetSceneItemProperties");
+        assert_eq!(actual_requests[0]["item"], "Missing");
+        assert!(!exists);
+    }
+
+    #[test]
+    fn profile_exists() {
+        init_logger();
+        let profiles = json!({
+            "status": "ok",
+            "profiles": [
+                { "profile-name": "Streaming" },
+                { "profile-name": "Recording" },
+            ],
+        });
+        let (obs, handle) = init(vec![profiles.clone(), profiles]);
+        let exists = smol::block_on(obs.profile_exists("Recording")).unwrap();
+        let res = smol::block_on(obs.set_current_profile("Missing", true));
+        let actual_requests = handle.join().expect("failed to join");
+        smol::block_on(obs.disconnect()).unwrap();
+
+        assert!(exists);
+        assert!(matches!(res, Err(ObsError::ProfileNotFound(name)) if name == "Missing"));
+        // no SetCurrentProfile was sent
+        assert_eq!(actual_requests.len(), 2);
+    }
+
+    #[test]
+    fn scene_collection_exists() {
+        init_logger();
+        let scene_collections = json!({
+            "status": "ok",
+            "scene-collections": [{ "sc-name": "Untitled" }],
+        });
+        let (obs, handle) = init(vec![
+            scene_collections.clone(),
+            scene_collections,
+            json!({ "status": "ok" }),
+        ]);
+        let exists = smol::block_on(obs.scene_collection_exists("Missing")).unwrap();
+        smol::block_on(obs.set_current_scene_collection("Untitled", true))
+            .expect("request returned err");
+        let actual_requests = handle.join().expect("failed to join");
+        smol::block_on(obs.disconnect()).unwrap();
+
+        assert!(!exists);
+        assert_eq!(
+            actual_requests[2]["request-type"],
+            "SetCurrentSceneCollection"
+        );
+        assert_eq!(actual_requests[2]["sc-name"], "Untitled");
+    }
+
+    #[test]
+    fn scene_not_found() {
+        init_logger();
+        let (obs, handle) = init(vec![
+            json!({
+                "status": "ok",
+                "current-scene": "Scene 1",
+                "scenes": [{ "name": "Scene 1", "sources": [] }],
+            }),
+            json!({
+                "status": "error",
+                "error": "requested scene does not exist",
+            }),
+        ]);
+        let exists = smol::block_on(obs.scene_exists("Scene 2")).unwrap();
+        let set = SetCurrentScene::builder().scene_name("Scene 2").build();
+        let res = smol::block_on(obs.request(&set));
+        handle.join().expect("failed to join");
+        smol::block_on(obs.disconnect()).unwrap();
+
+        assert!(!exists);
+        assert!(matches!(res, Err(ObsError::SceneNotFound(_))));
+    }
+
+    #[test]
+    fn resolve_item_id() {
+        init_logger();
+        let mut group = scene_item_json(2, "Group", "group");
+        group["groupChildren"] = json!([scene_item_json(3, "Camera", "input")]);
+        let scene_list = json!({
+            "status": "ok",
+            "current-scene": "Scene 1",
+            "scenes": [
+                { "name": "Scene 1", "sources": [scene_item_json(1, "Text", "input")] },
+                { "name": "Scene 2", "sources": [scene_item_json(4, "Text", "input"), group] },
+            ],
+        });
+        let (obs, handle) = init(vec![
+            scene_list.clone(),
+            scene_list.clone(),
+            scene_list.clone(),
+            scene_list,
+        ]);
+        let current = smol::block_on(obs.resolve_item_id(None, "Text")).unwrap();
+        let other = smol::block_on(obs.resolve_item_id(Some("Scene 2"), "Text")).unwrap();
+        let grouped = smol::block_on(obs.resolve_item_id(Some("Scene 2"), "Camera")).unwrap();
+        let missing = smol::block_on(obs.resolve_item_id(Some("Scene 1"), "Camera")).unwrap_err();
+        handle.join().expect("failed to join");
+        smol::block_on(obs.disconnect()).unwrap();
+        assert_eq!(current, 1);
+        assert_eq!(other, 4);
+        assert_eq!(grouped, 3);
+        assert!(matches!(missing, ObsError::SceneItemNotFound(item) if item == "Camera"));
+    }
+
+    #[test]
+    fn output_status() {
+        init_logger();
+        let (obs, handle) = init(vec![
+            json!({
+                "status": "ok",
+                "outputInfo": output_json("simple_stream", "rtmp_output", true),
+            }),
+            json!({
+                "status": "error",
+                "error": "specified output doesn't exist",
+            }),
+            json!({
+                "status": "ok",
+                "outputs": [
+                    output_json("simple_stream", "rtmp_output", true),
+                    output_json("simple_file_output", "ffmpeg_muxer", false),
+                    output_json("virtualcam_output", "virtualcam_output", true),
+                ],
+            }),
+        ]);
+        let active = smol::block_on(obs.is_output_active("simple_stream")).unwrap();
+        let missing = smol::block_on(obs.is_output_active("missing")).unwrap();
+        let active_outputs = smol::block_on(obs.active_outputs()).unwrap();
+        handle.join().expect("failed to join");
+        smol::block_on(obs.disconnect()).unwrap();
+        assert!(active);
+        assert!(!missing);
+        assert_eq!(active_outputs, vec!["simple_stream", "virtualcam_output"]);
+    }
+
+    #[test]
+    fn start_outputs_of_kind() {
+        init_logger();
+        let ok = json!({ "status": "ok" });
+        let (obs, handle) = init(vec![
+            json!({
+                "status": "ok",
+                "outputs": [
+                    output_json("simple_stream", "rtmp_output", false),
+                    output_json("simple_file_output", "ffmpeg_muxer", false),
+                    output_json("adv_file_output", "ffmpeg_muxer", false),
+                ],
+            }),
+            ok.clone(),
+            ok,
+        ]);
+        let started = smol::block_on(obs.start_outputs_of_kind(OutputKind::FfmpegMuxer)).unwrap();
+        let requests = handle.join().expect("failed to join");
+        smol::block_on(obs.disconnect()).unwrap();
+        assert_eq!(started, vec!["simple_file_output", "adv_file_output"]);
+        let started_requests = requests[1..]
+            .iter()
+            .map(|req| (req["request-type"].clone(), req["outputName"].clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            started_requests,
+            vec![
+                (json!("StartOutput"), json!("simple_file_output")),
+                (json!("StartOutput"), json!("adv_file_output")),
+            ]
+        );
+    }
+
+    #[test]
+    fn mirror_transforms() {
+        init_logger();
+        let transform_changed = |scene: &str, item: &str, x: f64| {
+            // the transform has the same fields as the properties, apart from the status and name
+            let mut transform = scene_item_properties_json(item, (x, 20.0), (2.0, 2.0), (100, 100));
+            let object = transform.as_object_mut().unwrap();
+            object.remove("status");
+            object.remove("name");
+            transform["rotation"] = json!(90.0);
+            transform["crop"] = json!({ "top": 1, "right": 2, "bottom": 3, "left": 4 });
+            Mock::Send(json!({
+                "update-type": "SceneItemTransformChanged",
+                "scene-name": scene,
+                "item-name": item,
+                "item-id": 1,
+                "transform": transform,
+            }))
+        };
+        let (obs, handle) = init_mock(vec![
+            // the events are sent after a request so that the mirror is already listening
+            Mock::Respond(json!({ "status": "ok" })),
+            transform_changed("Main", "Missing", 0.0),
+            Mock::Respond(json!({
+                "status": "error",
+                "error": "specified scene item doesn't exist",
+            })),
+            transform_changed("Other", "Camera", 5.0),
+            transform_changed("Main", "Camera", 10.0),
+            Mock::Respond(json!({ "status": "ok" })),
+        ]);
+        smol::block_on(async {
+            let mirror = obs.mirror_transforms("Main", "Mirror");
+            futures::pin_mut!(mirror);
+            let trigger = async {
+                obs.request(&SetHeartbeat::builder().enable(false).build())
+                    .await
+                    .unwrap();
+                Timer::after(Duration::from_millis(300)).await;
+            };
+            futures::pin_mut!(trigger);
+            match future::select(mirror, trigger).await {
+                Either::Left((res, _)) => panic!("mirror stopped: {:?}", res),
+                Either::Right(_) => {}
+            }
+        });
+        let actual_requests = handle.join().expect("failed to join");
+        smol::block_on(obs.disconnect()).unwrap();
+
+        assert_eq!(actual_requests.len(), 3);
+        assert_eq!(actual_requests[1]["item"], "Missing");
+        let mirrored = &actual_requests[2];
+        assert_eq!(mirrored["request-type"], "SetSceneItemProperties");
+        assert_eq!(mirrored["scene-name"], "Mirror");
+        assert_eq!(mirrored["item"], "Camera");
+        assert_eq!(
+            mirrored["position"],
+            json!({ "x": 10.0, "y": 20.0, "alignment": 5 })
+        );
+        assert_eq!(mirrored["rotation"], 90.0);
+        assert_eq!(mirrored["scale"], json!({ "x": 2.0, "y": 2.0 }));
+        assert_eq!(
+            mirrored["crop"],
+            json!({ "top": 1, "right": 2, "bottom": 3, "left": 4 })
+        );
+    }
+
+    #[test]
+    fn request_by_item_name() {
+        init_logger();
+        let scene_list = |id: i32| {
+            json!({
+                "status": "ok",
+                "current-scene": "Scene",
+                "scenes": [{ "name": "Scene", "sources": [scene_item_json(id, "Camera", "input")] }],
+            })
+        };
+        let (obs, handle) = init(vec![
+            scene_list(1),
+            json!({
+                "status": "ok",
+                "scene": "Other",
+                "item": { "name": "Camera", "id": 2 },
+            }),
+            // the ids changed, e.g. because the scene collection was reloaded
+            scene_list(7),
+            json!({ "status": "ok" }),
+        ]);
+        let duplicate = smol::block_on(obs.duplicate_scene_item_by_name(
+            Some("Scene"),
+            Some("Other"),
+            "Camera",
+        ))
+        .expect("request returned err");
+        smol::block_on(obs.delete_scene_item_by_name(Some("Scene"), "Camera"))
+            .expect("request returned err");
+        let actual_requests = handle.join().expect("failed to join");
+        smol::block_on(obs.disconnect()).unwrap();
+
+        assert_eq!(duplicate.item.id, 2);
+        assert_eq!(actual_requests[1]["request-type"], "DuplicateSceneItem");
+        assert_eq!(actual_requests[1]["item"]["id"], 1);
+        assert_eq!(actual_requests[3]["request-type"], "DeleteSceneItem");
+        assert_eq!(actual_requests[3]["scene"], "Scene");
+        assert_eq!(actual_requests[3]["item"]["id"], 7);
+    }
+
+    #[test]
+    fn set_scene_item_properties() {
+        init_logger();
+
+        let request = json!({
+            "request-type": "SetSceneItemProperties",
+            "scene-name": "scene",
+            "item": "test",
+            "position": {
+                "x": 1.0,
+                "y": 2.0,
+                "alignment": 3,
+            },
+            "rotation": 4.0,
+            "scale": {
+                "x": 5.0,
+                "y": 6.0,
+            },
+            "crop": {
+                "top": 7,
+                "right": 8,
+                "bottom": 9,
+                "left": 10,
+            },
+            "visible": true,
+            "locked": true,
+            "bounds": {
+                "type": "OBS_BOUNDS_STRETCH",
+                "alignment": 11,
+                "x": 12.0,
+                "y": 13.0,
+            },
+        });
+        let response = json!({
+            "status": "ok",
+        });
+        let req = SetSceneItemProperties::builder()
+            .scene_name("scene")
+            .item("test")
+            .position_x(1.0)
+            .position_y(2.0)
+            .position_alignment(3)
+            .rotation(4.0)
+            .scale_x(5.0)
+            .scale_y(6.0)
+            .crop_top(7)
+            .crop_right(8)
+            .crop_bottom(9)
+            .crop_left(10)
+            .visible(true)
+            .locked(true)
+            .bounds_type(BoundsType::Stretch)
+            .bounds_alignment(11)
+            .bounds_x(12.0)
+            .bounds_y(13.0)
+            .build();
+        let expected = responses::Empty {};
+        request_test(vec![request], vec![response], req, expected);
+    }
+
+    #[test]
+    fn pool() {
+        init_logger();
+        let version = json!({
+            "status": "ok",
+            "version": 1.1,
+            "obs-websocket-version": "4.8.0",
+            "obs-studio-version": "25.0.8",
+            "available-requests": "GetVersion",
+        });
+        let event = |scene: &str| {
+            Mock::Send(json!({
+                "update-type": "SwitchScenes",
+                "scene-name": scene,
+                "sources": [],
+            }))
+        };
+        let (main, main_handle) = init_mock(vec![Mock::Respond(version.clone()), event("Main")]);
+        let (backup, backup_handle) = init_mock(vec![Mock::Respond(version), event("Backup")]);
+        let mut pool = crate::ObsPool::new();
+        pool.insert("main", main);
+        pool.insert("backup", backup);
+
+        let events = pool.events();
+        smol::block_on(pool.request("main", &GetVersion::builder().build())).unwrap();
+        smol::block_on(pool.request("backup", &GetVersion::builder().build())).unwrap();
+        let missing =
+            smol::block_on(pool.request("missing", &GetVersion::builder().build())).unwrap_err();
+        let mut events = smol::block_on(events.take(2).collect::<Vec<_>>());
+        main_handle.join().expect("failed to join");
+        backup_handle.join().expect("failed to join");
+        smol::block_on(pool.disconnect()).unwrap();
+
+        assert!(matches!(missing, ObsError::UnknownConnection(label) if label == "missing"));
+        events.sort_by(|a, b| a.0.cmp(&b.0));
+        let scenes = events
+            .into_iter()
+            .map(|(label, event)| match event.update_type {
+                EventType::SwitchScenes { scene_name, .. } => (label, scene_name),
+                other => panic!("unexpected event {:?}", other),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            scenes,
+            vec![
+                ("backup".to_string(), "Backup".to_string()),
+                ("main".to_string(), "Main".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn authenticate_retry() {
+        #[derive(Debug)]
+        struct Provider;
+
+        impl crate::PasswordProvider for Provider {
+            fn password(&self) -> String {
+                "todo".to_string()
+            }
+        }
+
+        init_logger();
+        let auth_required = json!({
+            "status": "ok",
+            "authRequired": true,
+            "challenge": "123",
+            "salt": "456",
+        });
+        let config = ObsConfig::builder()
+            .password_provider(Box::new(Provider))
+            .build();
+        let (obs, handle) = init_mock_with_config(
+            vec![
+                Mock::Respond(auth_required.clone()),
+                Mock::Respond(json!({ "status": "error", "error": "Authentication Failed." })),
+                Mock::Respond(auth_required),
+                Mock::Respond(json!({ "status": "ok" })),
+            ],
+            config,
+        );
+        smol::block_on(obs.authenticate("wrong")).expect("authenticate");
+        let actual_requests = handle.join().expect("join");
+        smol::block_on(obs.disconnect()).unwrap();
+
+        assert_eq!(actual_requests.len(), 4);
+        assert_eq!(actual_requests[3]["request-type"], "Authenticate");
+        assert_eq!(
+            actual_requests[3]["auth"],
+            "Z69J+b7C5Zj7jIXlqVp/xjp36sFSmpJpxZ41GN/UTu4="
+        );
+    }
+
+    #[test]
+    fn authenticate_failed() {
+        init_logger();
+        let (obs, handle) = init(vec![
+            json!({
+                "status": "ok",
+                "authRequired": true,
+                "challenge": "123",
+                "salt": "456",
+            }),
+            json!({ "status": "error", "error": "Authentication Failed." }),
+        ]);
+        let res = smol::block_on(obs.authenticate("wrong"));
+        handle.join().expect("join");
+        smol::block_on(obs.disconnect()).unwrap();
+        assert!(matches!(res, Err(ObsError::AuthenticationFailed)));
+    }
+
+    #[test]
+    fn diagnostics() {
+        init_logger();
+        let (obs, handle) = init(vec![
+            json!({
+                "status": "ok",
+                "version": 1.1,
+                "obs-websocket-version": "4.7.0",
+                "obs-studio-version": "24.0.3",
+                "available-requests": "GetVersion,GetStats",
+            }),
+            json!({
+                "status": "ok",
+                "stats": {
+                    "fps": 60.0,
+                    "render-total-frames": 100,
+                    "render-missed-frames": 1,
+                    "output-total-frames": 100,
+                    "output-skipped-frames": 2,
+                    "average-frame-time": 1.5,
+                    "cpu-usage": 10.0,
+                    "memory-usage": 256.0,
+                    "free-disk-space": 2048.0,
+                },
+            }),
+            json!({
+                "status": "ok",
+                "baseWidth": 1920,
+                "baseHeight": 1080,
+                "outputWidth": 1280,
+                "outputHeight": 720,
+                "scaleType": "VIDEO_SCALE_BICUBIC",
+                "fps": 60.0,
+                "videoFormat": "VIDEO_FORMAT_NV12",
+                "colorSpace": "VIDEO_CS_709",
+                "colorRange": "VIDEO_RANGE_PARTIAL",
+            }),
+            json!({
+                "status": "ok",
+                "streaming": true,
+                "recording": false,
+                "stream-timecode": "00:10:00.000",
+                "preview-only": false,
+            }),
+        ]);
+        let diagnostics = smol::block_on(obs.diagnostics()).expect("request returned err");
+        let actual_requests = handle.join().expect("failed to join");
+        smol::block_on(obs.disconnect()).unwrap();
+
+        let request_types = actual_requests
+            .iter()
+            .map(|req| req["request-type"].as_str().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            request_types,
+            vec![
+                "GetVersion",
+                "GetStats",
+                "GetVideoInfo",
+                "GetStreamingStatus"
+            ]
+        );
+        assert_eq!(diagnostics.version.obs_studio_version, "24.0.3");
+        assert_eq!(diagnostics.stats.output_skipped_frames, 2);
+        assert_eq!(diagnostics.video_info.base_width, 1920);
+        assert!(diagnostics.streaming_status.streaming);
+
+        let serialized = serde_json::to_value(&diagnostics).unwrap();
+        assert_eq!(serialized["video_info"]["scaleType"], "VIDEO_SCALE_BICUBIC");
+        assert_eq!(
+            serialized["streaming_status"]["stream-timecode"],
+            "00:10:00.000"
+        );
+    }
+
+    #[test]
+    fn update_stream_settings() {
+        init_logger();
+        let (obs, handle) = init(vec![
+            json!({
+                "status": "ok",
+                "type": "rtmp_custom",
+                "settings": {
+                    "server": "rtmp://localhost/live",
+                    "key": "old key",
+                    "use-auth": true,
+                    "username": "user",
+                    "password": "pass",
+                },
+            }),
+            json!({ "status": "ok" }),
+        ]);
+        smol::block_on(obs.update_stream_settings(|settings| {
+            settings.key = "new key".to_string();
+        }))
+        .expect("request returned err");
+        let mut actual_requests = handle.join().expect("failed to join");
+        smol::block_on(obs.disconnect()).unwrap();
+
+        let mut set = actual_requests.remove(1);
+        set.as_object_mut().unwrap().remove("message-id");
+        assert_eq!(
+            set,
+            json!({
+                "request-type": "SetStreamSettings",
+                "type": "rtmp_custom",
+                "settings": {
+                    "server": "rtmp://localhost/live",
+                    "key": "new key",
+                    "use-auth": true,
+                    "username": "user",
+                    "password": "pass",
+                },
+                "save": true,
+            })
+        );
+    }
+
+    #[test]
+    fn interned_names() {
+        init_logger();
+        let sources = json!({
+            "status": "ok",
+            "sources": [
+                { "name": "Scene 1", "typeId": "scene", "type": "scene" },
+                { "name": "Mic", "typeId": "pulse_input_capture", "type": "input" },
+            ],
+        });
+        let (obs, handle) = init(vec![
+            scene_list_json("Scene 1"),
+            scene_list_json("Scene 2"),
+            sources,
+        ]);
+        let first = smol::block_on(obs.scene_names()).expect("request returned err");
+        let second = smol::block_on(obs.scene_names()).expect("request returned err");
+        let sources = smol::block_on(obs.source_names()).expect("request returned err");
+        handle.join().expect("failed to join");
+        smol::block_on(obs.disconnect()).unwrap();
+
+        assert_eq!(
+            first.iter().map(|name| &**name).collect::<Vec<_>>(),
+            vec!["Scene 1", "Scene 2", "Scene 3"]
+        );
+        assert_eq!(first, second);
+        assert!(first
+            .iter()
+            .zip(&second)
+            .all(|(a, b)| sync::Arc::ptr_eq(a, b)));
+        assert_eq!(&*sources[1], "Mic");
+        assert!(sync::Arc::ptr_eq(&first[0], &sources[0]));
+    }
+
+    #[test]
+    fn set_filter_setting() {
+        init_logger();
+        let (obs, handle) = init(vec![json!({ "status": "ok" })]);
+        smol::block_on(obs.set_filter_setting("Camera", "Chroma Key", "similarity", 420))
+            .expect("request returned err");
+        let actual_requests = handle.join().expect("failed to join");
+        smol::block_on(obs.disconnect()).unwrap();
+        assert_eq!(
+            actual_requests[0]["request-type"],
+            "SetSourceFilterSettings"
+        );
+        assert_eq!(actual_requests[0]["sourceName"], "Camera");
+        assert_eq!(actual_requests[0]["filterName"], "Chroma Key");
+        assert_eq!(
+            actual_requests[0]["filterSettings"],
+            json!({ "similarity": 420 })
+        );
+    }
+
+    #[test]
+    fn export_scene_collection() {
+        init_logger();
+        let properties =
+            |name: &str, x: f64| scene_item_properties_json(name, (x, 0.0), (1.0, 1.0), (100, 100));
+        let settings = |name: &str, kind: &str, settings: Value| {
+            json!({
+                "status": "ok",
+                "sourceName": name,
+                "sourceType": kind,
+                "sourceSettings": settings,
+            })
+        };
+        let (obs, handle) = init(vec![
+            json!({ "status": "ok", "sc-name": "Main" }),
+            json!({
+                "status": "ok",
+                "current-scene": "Scene 1",
+                "scenes": [
+                    {
+                        "name": "Scene 1",
+                        "sources": [
+                            scene_item_json(1, "Camera", "input"),
+                            scene_item_json(2, "Text", "input"),
+                        ],
+                    },
+                    { "name": "Scene 2", "sources": [scene_item_json(1, "Camera", "input")] },
+                ],
+            }),
+            properties("Camera", 10.0),
+            settings(
+                "Camera",
+                "v4l2_input",
+                json!({ "device_id": "/dev/video0" }),
+            ),
+            properties("Text", 20.0),
+            settings("Text", "text_ft2_source_v2", json!({ "text": "hello" })),
+            properties("Camera", 30.0),
+        ]);
+        let export = smol::block_on(obs.export_scene_collection()).expect("request returned err");
+        let actual_requests = handle.join().expect("failed to join");
+        smol::block_on(obs.disconnect()).unwrap();
+
+        // the settings of Camera are only requested once
+        assert_eq!(actual_requests.len(), 7);
+        assert_eq!(actual_requests[4]["scene-name"], "Scene 1");
+        assert_eq!(actual_requests[4]["item"], "Text");
+        assert_eq!(actual_requests[6]["scene-name"], "Scene 2");
+        assert_eq!(export["scene-collection"], "Main");
+        assert_eq!(export["current-scene"], "Scene 1");
+        let scenes = export["scenes"].as_array().unwrap();
+        assert_eq!(scenes.len(), 2);
+        assert_eq!(scenes[0]["name"], "Scene 1");
+        assert_eq!(scenes[0]["items"][1]["name"], "Text");
+        assert_eq!(scenes[0]["items"][1]["properties"]["position"]["x"], 20.0);
+        assert_eq!(scenes[1]["items"][0]["properties"]["position"]["x"], 30.0);
+        assert_eq!(
+            export["sources"]["Camera"]["sourceSettings"],
+            json!({ "device_id": "/dev/video0" })
+        );
+        assert_eq!(
+            export["sources"]["Text"]["sourceType"],
+            "text_ft2_source_v2"
+        );
+    }
+
+    #[test]
+    fn profile_names() {
+        init_logger();
+        let (obs, handle) = init(vec![json!({
+            "status": "ok",
+            "profiles": [
+                { "profile-name": "Streaming" },
+                { "profile-name": "Recording" },
+            ],
+        })]);
+        let names = smol::block_on(obs.profile_names()).expect("request returned err");
+        let actual_requests = handle.join().expect("failed to join");
+        smol::block_on(obs.disconnect()).unwrap();
+        assert_eq!(actual_requests[0]["request-type"], "ListProfiles");
+        assert_eq!(names, vec!["Streaming", "Recording"]);
+    }
+
+    #[test]
+    fn scene_collection_names() {
+        init_logger();
+        let (obs, handle) = init(vec![json!({
+            "status": "ok",
+            "scene-collections": [
+                { "sc-name": "Main" },
+                { "sc-name": "Backup" },
+            ],
+        })]);
+        let names = smol::block_on(obs.scene_collection_names()).expect("request returned err");
+        let actual_requests = handle.join().expect("failed to join");
+        smol::block_on(obs.disconnect()).unwrap();
+        assert_eq!(actual_requests[0]["request-type"], "ListSceneCollections");
+        assert_eq!(names, vec!["Main", "Backup"]);
+    }
+
+    // a GetSceneList response with three scenes
+    fn scene_list_json(current_scene: &str) -> Value {
+        json!({
+            "status": "ok",
+            "current-scene": current_scene,
+            "scenes": [
+                { "name": "Scene 1", "sources": [] },
+                { "name": "Scene 2", "sources": [] },
+                { "name": "Scene 3", "sources": [] },
+            ],
+        })
+    }
+
+    // runs the given scene switch with the given current scene and returns the scene that was switched to
+    fn switch_scene<F>(current_scene: &str, switch: F) -> Value
+    where
+        F: FnOnce(&Obs) -> Result<(), ObsError>,
+    {
+        init_logger();
+        let (obs, handle) = init(vec![
+            scene_list_json(current_scene),
+            json!({ "status": "ok" }),
+        ]);
+        switch(&obs).expect("request returned err");
+        let actual_requests = handle.join().expect("failed to join");
+        smol::block_on(obs.disconnect()).unwrap();
+        assert_eq!(actual_requests[1]["request-type"], "SetCurrentScene");
+        actual_requests[1]["scene-name"].clone()
+    }
+
+    #[test]
+    fn set_scene_by_index() {
+        let scene = switch_scene("Scene 1", |obs| smol::block_on(obs.set_scene_by_index(2)));
+        assert_eq!(scene, "Scene 3");
+
+        let (obs, handle) = init(vec![scene_list_json("Scene 1")]);
+        let res = smol::block_on(obs.set_scene_by_index(3));
+        handle.join().expect("failed to join");
+        smol::block_on(obs.disconnect()).unwrap();
+        assert!(matches!(
+            res,
+            Err(ObsError::SceneIndexOutOfRange {
+                index: 3,
+                scene_count: 3
+            })
+        ));
+    }
+
+    #[test]
+    fn next_scene() {
+        let scene = switch_scene("Scene 2", |obs| smol::block_on(obs.next_scene()));
+        assert_eq!(scene, "Scene 3");
+        let scene = switch_scene("Scene 3", |obs| smol::block_on(obs.next_scene()));
+        assert_eq!(scene, "Scene 1");
+    }
+
+    #[test]
+    fn previous_scene() {
+        let scene = switch_scene("Scene 2", |obs| smol::block_on(obs.previous_scene()));
+        assert_eq!(scene, "Scene 1");
+        let scene = switch_scene("Scene 1", |obs| smol::block_on(obs.previous_scene()));
+        assert_eq!(scene, "Scene 3");
+    }
+
+    #[test]
+    fn set_and_get_text_gdi() {
+        init_logger();
+
+        let responses = vec![
+            json!({
+                "status": "ok",
+            }),
+            json!({
+                "status": "ok",
+                "source": "text",
+                "align": "center",
+                "bk-color": 0,
+                "bk-opacity": 1,
+                "chatlog": false,
+                "chatlog_lines": 2,
+                "color": 3,
+                "extents": false,
+                "extents_cx": 4,
+                "extents_cy": 5,
+                "file": "",
+                "read_from_file": false,
+                "font": {
+                    "face": "Arial",
+                    "flags": 1,
+                    "size": 6,
+                    "style": "Regular",
+                },
+                "gradient": false,
+                "gradient_color": 7,
+                "gradient_dir": 8.0,
+                "gradient_opacity": 9,
+                "outline": false,
+                "outline_color": 10,
+                "outline_size": 11,
+                "outline_opacity": 12,
+                "text": "new text",
+                "valign": "top",
+                "vertical": false,
+            }),
+        ];
+        let (obs, handle) = init(responses);
+        let req = SetTextGDIPlusProperties::builder()
+            .source("text")
+            .text("new text")
+            .build();
+        let res = smol::block_on(obs.set_and_get_text_gdi(&req)).expect("set and get");
+        let actual_requests = handle.join().expect("join");
+        smol::block_on(obs.disconnect()).unwrap();
+        assert_eq!(actual_requests.len(), 2);
+        assert_eq!(
+            actual_requests[0]["request-type"],
+            "SetTextGDIPlusProperties"
+        );
+        assert_eq!(actual_requests[0]["text"], "new text");
+        assert_eq!(
+            actual_requests[1]["request-type"],
+            "GetTextGDIPlusProperties"
+        );
+        assert_eq!(actual_requests[1]["source"], "text");
+        assert_eq!(res.text, "new text");
+        assert_eq!(res.align, responses::Align::Center);
+    }
+
+    #[test]
+    fn center_item() {
+        init_logger();
+
+        let responses = vec![
+            json!({
+                "status": "ok",
+                "baseWidth": 1920,
+                "baseHeight": 1080,
+                "outputWidth": 1920,
+                "outputHeight": 1080,
+                "scaleType": "VIDEO_SCALE_BICUBIC",
+                "fps": 60.0,
+                "videoFormat": "VIDEO_FORMAT_NV12",
+                "colorSpace": "VIDEO_CS_601",
+                "colorRange": "VIDEO_RANGE_PARTIAL",
+            }),
+            scene_item_properties_json("webcam", (0.0, 0.0), (0.5, 0.5), (1280, 720)),
+            json!({
+                "status": "ok",
+            }),
+        ];
+        let (obs, handle) = init(responses);
+        smol::block_on(obs.center_item(Some("scene"), "webcam")).expect("center item");
+        let actual_requests = handle.join().expect("join");
+        smol::block_on(obs.disconnect()).unwrap();
+        assert_eq!(actual_requests[1]["request-type"], "GetSceneItemProperties");
+        assert_eq!(actual_requests[1]["scene-name"], "scene");
+        let set = &actual_requests[2];
+        assert_eq!(set["request-type"], "SetSceneItemProperties");
+        assert_eq!(set["scene-name"], "scene");
+        assert_eq!(set["item"], "webcam");
+        // top left corner at (1920 - 640) / 2, (1080 - 360) / 2
+        assert_eq!(set["position"]["x"], 640.0);
+        assert_eq!(set["position"]["y"], 360.0);
+    }
+
+    #[test]
+    fn flip_item() {
+        init_logger();
+        let (obs, handle) = init(vec![
+            scene_item_properties_json("webcam", (0.0, 0.0), (0.5, -0.75), (1280, 720)),
+            json!({
+                "status": "ok",
+            }),
+        ]);
+        smol::block_on(obs.flip_item(Some("scene"), "webcam", true, true)).expect("flip item");
+        let actual_requests = handle.join().expect("join");
+        smol::block_on(obs.disconnect()).unwrap();
+        assert_eq!(actual_requests[0]["request-type"], "GetSceneItemProperties");
+        let set = &actual_requests[1];
+        assert_eq!(set["request-type"], "SetSceneItemProperties");
+        assert_eq!(set["scene-name"], "scene");
+        assert_eq!(set["item"], "webcam");
+        assert_eq!(set["scale"]["x"], -0.5);
+        // already flipped vertically, so it's flipped back
+        assert_eq!(set["scale"]["y"], 0.75);
+    }
+
+    #[test]
+    fn animate_item() {
+        init_logger();
+        let (obs, handle) = init((0..4).map(|_| json!({ "status": "ok" })).collect());
+        let from = Transform {
+            position_x: 0.0,
+            position_y: 100.0,
+            scale_x: 1.0,
+            scale_y: 1.0,
+            rotation: 0.0,
+        };
+        let to = Transform {
+            position_x: 300.0,
+            position_y: 100.0,
+            scale_x: 2.0,
+            scale_y: 2.0,
+            rotation: 90.0,
+        };
+        let animation = Animation::builder()
+            .scene_name("Scene")
+            .item("Lower third")
+            .from(from)
+            .to(to)
+            .duration(Duration::from_millis(30))
+            .steps(3)
+            .build();
+        smol::block_on(obs.animate_item(&animation)).expect("request returned err");
+        let actual_requests = handle.join().expect("failed to join");
+        smol::block_on(obs.disconnect()).unwrap();
+
+        assert_eq!(actual_requests.len(), 4);
+        let frames = actual_requests
+            .iter()
+            .map(|req| {
+                assert_eq!(req["request-type"], "SetSceneItemProperties");
+                assert_eq!(req["scene-name"], "Scene");
+                assert_eq!(req["item"], "Lower third");
+                (
+                    req["position"]["x"].as_f64().unwrap(),
+                    req["position"]["y"].as_f64().unwrap(),
+                    req["scale"]["x"].as_f64().unwrap(),
+                    req["rotation"].as_f64().unwrap(),
+                )
+            })
+            .collect::<Vec<_>>();
+        let expected = vec![
+            (0.0, 100.0, 1.0, 0.0),
+            (100.0, 100.0, 4.0 / 3.0, 30.0),
+            (200.0, 100.0, 5.0 / 3.0, 60.0),
+            (300.0, 100.0, 2.0, 90.0),
+        ];
+        for (frame, expected) in frames.iter().zip(&expected) {
+            assert!((frame.0 - expected.0).abs() < 1e-9, "{:?}", frame);
+            assert!((frame.1 - expected.1).abs() < 1e-9, "{:?}", frame);
+            assert!((frame.2 - expected.2).abs() < 1e-9, "{:?}", frame);
+            assert!((frame.3 - expected.3).abs() < 1e-9, "{:?}", frame);
+        }
+    }
+
+    #[test]
+    fn animate_item_dropped_mid_request() {
+        init_logger();
+        let (obs, handle) = init_mock(vec![
+            Mock::Respond(json!({ "status": "ok" })),
+            // the animation is dropped while waiting for this response
+            Mock::RespondAfter(Duration::from_millis(200), json!({ "status": "ok" })),
+            Mock::Respond(json!({ "status": "ok" })),
+        ]);
+        let transform = Transform {
+            position_x: 0.0,
+            position_y: 0.0,
+            scale_x: 1.0,
+            scale_y: 1.0,
+            rotation: 0.0,
+        };
+        let animation = Animation::builder()
+            .scene_name("Scene")
+            .item("Lower third")
+            .from(transform)
+            .to(transform)
+            .duration(Duration::from_millis(20))
+            .steps(2)
+            .build();
+        drop_after(obs.animate_item(&animation), Duration::from_millis(100));
+        assert_still_usable(&obs);
+        let actual_requests = handle.join().expect("failed to join");
+        smol::block_on(obs.disconnect()).unwrap();
+
+        assert_eq!(actual_requests[1]["request-type"], "SetSceneItemProperties");
+        assert_eq!(actual_requests[2]["request-type"], "SetHeartbeat");
+    }
+
+    #[test]
+    fn fit_item_to_screen() {
+        init_logger();
+
+        let responses = vec![
+            json!({
+                "status": "ok",
+                "baseWidth": 1920,
+                "baseHeight": 1080,
+                "outputWidth": 1280,
+                "outputHeight": 720,
+                "scaleType": "VIDEO_SCALE_BICUBIC",
+                "fps": 60.0,
+                "videoFormat": "VIDEO_FORMAT_NV12",
+                "colorSpace": "VIDEO_CS_601",
+                "colorRange": "VIDEO_RANGE_PARTIAL",
+            }),
+            json!({
+                "status": "ok",
+            }),
+        ];
+        let (obs, handle) = init(responses);
+        smol::block_on(obs.fit_item_to_screen(None, "capture")).expect("fit item to screen");
+        let actual_requests = handle.join().expect("join");
+        smol::block_on(obs.disconnect()).unwrap();
+        let set = &actual_requests[1];
+        assert_eq!(set["request-type"], "SetSceneItemProperties");
+        assert_eq!(set["scene-name"], Value::Null);
+        assert_eq!(set["item"], "capture");
+        assert_eq!(
+            set["position"],
+            json!({
+                "x": 0.0,
+                "y": 0.0,
+                "alignment": 5,
+            })
+        );
+        assert_eq!(
+            set["bounds"],
+            json!({
+                "type": "OBS_BOUNDS_SCALE_INNER",
+                "alignment": 0,
+                "x": 1920.0,
+                "y": 1080.0,
+            })
+        );
+    }
+
+    #[test]
+    fn mute_inactive_scene_audio() {
+        init_logger();
+
+        let responses = vec![
+            json!({
+                "status": "ok",
+                "current-scene": "Scene A",
+                "scenes": [
+                    {
+                        "name": "Scene A",
+                        "sources": [
+                            scene_item_json(1, "Mic A", "input"),
+                            scene_item_json(2, "Music", "ffmpeg_source"),
+                            scene_item_json(3, "Logo", "image_source"),
+                        ],
+                    },
+                    {
+                        "name": "Scene B",
+                        "sources": [
+                            scene_item_json(1, "Mic B", "input"),
+                            scene_item_json(2, "Music", "ffmpeg_source"),
+                        ],
+                    },
+                ],
+            }),
+            json!({
+                "status": "ok",
+            }),
+        ];
+        let (obs, handle) = init(responses);
+        smol::block_on(obs.mute_inactive_scene_audio("Scene A")).expect("mute");
+        let actual_requests = handle.join().expect("join");
+        smol::block_on(obs.disconnect()).unwrap();
+        let mutes = actual_requests[1..]
+            .iter()
+            .map(|req| {
+                assert_eq!(req["request-type"], "SetMute");
+                (
+                    req["source"].as_str().unwrap().to_string(),
+                    req["mute"].as_bool().unwrap(),
+                )
+            })
+            .collect::<HashSet<_>>();
+        // only the source that isn't in the active scene is muted and nothing is unmuted
+        let expected = vec![("Mic B".to_string(), true)]
+            .into_iter()
+            .collect::<HashSet<_>>();
+        assert_eq!(actual_requests.len(), 2);
+        assert_eq!(mutes, expected);
+    }
+
+    #[test]
+    fn active_sources() {
+        init_logger();
+        let hidden = |id: i32, name: &str, item_type: &str| {
+            let mut item = scene_item_json(id, name, item_type);
+            item["render"] = json!(false);
+            item
+        };
+        let mut group = scene_item_json(3, "Group", "group");
+        group["groupChildren"] = json!([
+            scene_item_json(4, "Logo", "image_source"),
+            hidden(5, "Banner", "image_source"),
+        ]);
+        let mut hidden_group = hidden(6, "Hidden Group", "group");
+        hidden_group["groupChildren"] = json!([scene_item_json(7, "Ticker", "text_gdiplus")]);
+        let (obs, handle) = init(vec![json!({
+            "status": "ok",
+            "current-scene": "Scene",
+            "scenes": [
+                {
+                    "name": "Scene",
+                    "sources": [
+                        scene_item_json(1, "Camera", "input"),
+                        hidden(2, "Overlay", "image_source"),
+                        group,
+                        hidden_group,
+                        scene_item_json(8, "Nested", "scene"),
+                    ],
+                },
+                {
+                    "name": "Nested",
+                    "sources": [
+                        scene_item_json(1, "Camera", "input"),
+                        scene_item_json(2, "Text", "text_gdiplus"),
+                    ],
+                },
+                {
+                    "name": "Other",
+                    "sources": [scene_item_json(1, "Slides", "input")],
+                },
+            ],
+        })]);
+        let sources = smol::block_on(obs.active_sources()).expect("request returned err");
+        handle.join().expect("failed to join");
+        smol::block_on(obs.disconnect()).unwrap();
+        assert_eq!(sources, vec!["Camera", "Group", "Logo", "Nested", "Text"]);
+    }
+
+    #[test]
+    fn save_replay_buffer_confirmed() {
+        init_logger();
+        let (obs, handle) = init_mock(vec![
+            Mock::Respond(json!({ "status": "ok" })),
+            Mock::Send(json!({
+                "update-type": "RecordingFileChanged",
+                "newFileName": "/home/user/Replay 2020-01-01 00-00-00.mkv",
+            })),
+        ]);
+        let path = smol::block_on(obs.save_replay_buffer_confirmed(Duration::from_secs(5)))
+            .expect("request returned err");
+        let actual_requests = handle.join().expect("failed to join");
+        smol::block_on(obs.disconnect()).unwrap();
+        assert_eq!(actual_requests[0]["request-type"], "SaveReplayBuffer");
+        assert_eq!(
+            path.as_deref(),
+            Some("/home/user/Replay 2020-01-01 00-00-00.mkv")
+        );
+    }
+
+    #[test]
+    fn toggle_recording_confirmed() {
+        init_logger();
+        let (obs, handle) = init_mock(vec![
+            Mock::Respond(json!({ "status": "ok" })),
+            Mock::Send(json!({ "update-type": "RecordingStarting" })),
+            Mock::Send(json!({ "update-type": "RecordingStarted" })),
+        ]);
+        let recording = smol::block_on(obs.toggle_recording_confirmed(Duration::from_secs(5)))
+            .expect("request returned err");
+        let actual_requests = handle.join().expect("failed to join");
+        smol::block_on(obs.disconnect()).unwrap();
+        assert_eq!(actual_requests[0]["request-type"], "StartStopRecording");
+        assert!(recording);
+    }
+
+    #[test]
+    fn saved_replays() {
+        init_logger();
+        let (obs, handle) = init_mock(vec![
+            Mock::Send(json!({
+                "update-type": "RecordingFileChanged",
+                "newFileName": "/home/user/Replay 2020-01-01 00-00-00.mkv",
+            })),
+            Mock::Send(json!({
+                "update-type": "RecordingFileChanged",
+                "newFileName": "/home/user/Replay 2020-01-01 00-01-00.mkv",
+            })),
+            // the events are handled before the response to the request
+            Mock::Respond(json!({ "status": "ok" })),
+        ]);
+        smol::block_on(obs.request(&SaveReplayBuffer::builder().build()))
+            .expect("request returned err");
+        handle.join().expect("failed to join");
+        let saved = obs.saved_replays();
+        smol::block_on(obs.disconnect()).unwrap();
+        assert_eq!(
+            saved,
+            vec![
+                "/home/user/Replay 2020-01-01 00-00-00.mkv",
+                "/home/user/Replay 2020-01-01 00-01-00.mkv",
+            ]
+        );
+    }
+
+    #[test]
+    fn streams_are_send_and_unpin() {
+        fn assert_send_unpin<T: Send + Unpin>(_: &T) {}
+
+        init_logger();
+        let (obs, handle) = init(vec![]);
+        assert_send_unpin(&obs.subscribe());
+        assert_send_unpin(&obs.events_of::<events::SwitchScenesEvent>());
+        assert_send_unpin(&obs.liveness_stream(Duration::from_secs(5)));
+        assert_send_unpin(&obs.source_thumbnails("source", Duration::from_secs(1), 100));
+        let mut pool = crate::pool::ObsPool::new();
+        pool.insert("main", obs);
+        assert_send_unpin(&pool.events());
+        handle.join().expect("failed to join");
+        smol::block_on(pool.disconnect()).unwrap();
+    }
+
+    #[test]
+    fn go_to_scene_studio_mode() {
+        init_logger();
+        let (obs, handle) = init(vec![
+            json!({ "status": "ok", "studio-mode": true }),
+            json!({ "status": "ok" }),
+            json!({ "status": "ok" }),
+        ]);
+        smol::block_on(obs.go_to_scene("Scene 2", Some(("Fade".to_string(), 500))))
+            .expect("request returned err");
+        let actual_requests = handle.join().expect("failed to join");
+        smol::block_on(obs.disconnect()).unwrap();
+        assert_eq!(actual_requests[1]["request-type"], "SetPreviewScene");
+        assert_eq!(actual_requests[1]["scene-name"], "Scene 2");
+        assert_eq!(actual_requests[2]["request-type"], "TransitionToProgram");
+        assert_eq!(actual_requests[2]["with-transition"]["name"], "Fade");
+        assert_eq!(actual_requests[2]["with-transition"]["duration"], "500");
+    }
+
+    #[test]
+    fn go_to_scene_without_studio_mode() {
+        init_logger();
+        let (obs, handle) = init(vec![
+            json!({ "status": "ok", "studio-mode": false }),
+            json!({ "status": "ok", "name": "Cut", "duration": 300 }),
+            json!({ "status": "ok" }),
+            json!({ "status": "ok" }),
+            json!({ "status": "ok" }),
+            json!({ "status": "ok" }),
+            json!({ "status": "ok" }),
+        ]);
+        smol::block_on(obs.go_to_scene("Scene 2", Some(("Fade".to_string(), 500))))
+            .expect("request returned err");
+        let actual_requests = handle.join().expect("failed to join");
+        smol::block_on(obs.disconnect()).unwrap();
+        assert_eq!(actual_requests[1]["request-type"], "GetCurrentTransition");
+        assert_eq!(actual_requests[2]["request-type"], "SetCurrentTransition");
+        assert_eq!(actual_requests[2]["transition-name"], "Fade");
+        assert_eq!(actual_requests[3]["request-type"], "SetTransitionDuration");
+        assert_eq!(actual_requests[3]["duration"], 500);
+        assert_eq!(actual_requests[4]["request-type"], "SetCurrentScene");
+        assert_eq!(actual_requests[4]["scene-name"], "Scene 2");
+        // the previous transition is restored
+        assert_eq!(actual_requests[5]["request-type"], "SetCurrentTransition");
+        assert_eq!(actual_requests[5]["transition-name"], "Cut");
+        assert_eq!(actual_requests[6]["request-type"], "SetTransitionDuration");
+        assert_eq!(actual_requests[6]["duration"], 300);
+    }
+
+    #[test]
+    fn go_to_scene_restores_transition_on_failure() {
+        init_logger();
+        let (obs, handle) = init(vec![
+            json!({ "status": "ok", "studio-mode": false }),
+            json!({ "status": "ok", "name": "Cut" }),
+            json!({ "status": "ok" }),
+            json!({ "status": "ok" }),
+            json!({ "status": "error", "error": "requested scene does not exist" }),
+            json!({ "status": "ok" }),
+        ]);
+        let res = smol::block_on(obs.go_to_scene("Missing", Some(("Fade".to_string(), 500))));
+        let actual_requests = handle.join().expect("failed to join");
+        smol::block_on(obs.disconnect()).unwrap();
+        assert!(res.is_err());
+        assert_eq!(actual_requests.len(), 6);
+        // Cut has no duration, so only the transition is restored
+        assert_eq!(actual_requests[5]["request-type"], "SetCurrentTransition");
+        assert_eq!(actual_requests[5]["transition-name"], "Cut");
+    }
+
+    #[test]
+    fn defer_until_authenticated() {
+        init_logger();
+        let config = ObsConfig::builder().defer_until_authenticated(true).build();
+        let (obs, handle) = init_mock_with_config(
+            vec![
+                Mock::Respond(json!({
+                    "status": "ok",
+                    "authRequired": true,
+                    "challenge": "123",
+                    "salt": "456",
+                })),
+                Mock::Respond(json!({ "status": "ok" })),
+                Mock::Respond(json!({
+                    "status": "ok",
+                    "name": "Scene 1",
+                    "sources": [],
+                })),
+            ],
+            config,
+        );
+        // the request is polled first but is only sent after authenticating
+        let get_current_scene = GetCurrentScene::builder().build();
+        let (current, auth) = smol::block_on(future::join(
+            obs.request(&get_current_scene),
+            obs.authenticate("password"),
+        ));
+        let actual_requests = handle.join().expect("failed to join");
+        smol::block_on(obs.disconnect()).unwrap();
+        auth.expect("authentication failed");
+        assert_eq!(current.expect("request returned err").name, "Scene 1");
+        let request_types = actual_requests
+            .iter()
+            .map(|request| request["request-type"].as_str().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            request_types,
+            vec!["GetAuthRequired", "Authenticate", "GetCurrentScene"]
+        );
+    }
+
+    #[test]
+    fn connect_no_events() {
+        init_logger();
+        // the event is sent after the first response so that the connection is fully set up by then
+        let current_scene = json!({
+            "status": "ok",
+            "name": "Scene 1",
+            "sources": [],
+        });
+        let (port, handle) = spawn_mock(vec![
+            Mock::Respond(current_scene.clone()),
+            Mock::Send(json!({ "update-type": "ScenesChanged" })),
+            Mock::Respond(current_scene),
+        ]);
+        let obs = smol::block_on(Obs::connect_no_events("localhost", port)).expect("connect");
+        // no event stream is kept around
+        assert!(obs.shared.event_subscribers.lock().unwrap().is_empty());
+        smol::block_on(obs.request(&GetCurrentScene::builder().build()))
+            .expect("request returned err");
+        let current = smol::block_on(obs.request(&GetCurrentScene::builder().build()))
+            .expect("request returned err");
+        handle.join().expect("failed to join");
+        let dropped_events = obs.shared.dropped_events.load(Ordering::SeqCst);
+        smol::block_on(obs.disconnect()).unwrap();
+
+        assert_eq!(current.name, "Scene 1");
+        assert_eq!(dropped_events, 1);
+    }
+
+    #[test]
+    fn events_of() {
+        init_logger();
+        let (obs, handle) = init_mock(vec![
+            Mock::Respond(json!({ "status": "ok" })),
+            Mock::Send(json!({
+                "update-type": "SwitchScenes",
+                "scene-name": "Scene 1",
+                "sources": [scene_item_json(1, "Camera", "input")],
+            })),
+            Mock::Send(json!({ "update-type": "ScenesChanged" })),
+            Mock::Send(json!({
+                "update-type": "SwitchScenes",
+                "scene-name": "Scene 2",
+                "sources": [],
+            })),
+        ]);
+        let switches = obs.events_of::<events::SwitchScenesEvent>();
+        smol::block_on(obs.request(&SetCurrentScene::builder().scene_name("Scene 1").build()))
+            .expect("request returned err");
+        let switches = smol::block_on(switches.take(2).collect::<Vec<_>>());
+        handle.join().expect("failed to join");
+        smol::block_on(obs.disconnect()).unwrap();
+
+        assert_eq!(switches.len(), 2);
+        assert_eq!(switches[0].scene_name, "Scene 1");
+        assert_eq!(switches[0].sources[0].name, "Camera");
+        assert_eq!(switches[1].scene_name, "Scene 2");
+        assert!(switches[1].sources.is_empty());
+    }
+
+    #[test]
+    fn unknown_event() {
+        init_logger();
+        let (obs, handle) = init_mock(vec![
+            Mock::Respond(json!({ "status": "ok" })),
+            Mock::Send(json!({
+                "update-type": "SwitchScenes",
+                "stream-timecode": "00:00:01.000",
+                "scene-name": "Scene 1",
+                "sources": "not a list",
+            })),
+            Mock::Send(json!({ "update-type": "ScenesChanged" })),
+        ]);
+        let events = obs.subscribe();
+        smol::block_on(obs.request(&SetCurrentScene::builder().scene_name("Scene 1").build()))
+            .expect("request returned err");
+        let events = smol::block_on(events.take(2).collect::<Vec<_>>());
+        handle.join().expect("failed to join");
+        smol::block_on(obs.disconnect()).unwrap();
+
+        assert_eq!(
+            events[0],
+            Event {
+                stream_timecode: Some("00:00:01.000".to_string()),
+                rec_timecode: None,
+                update_type: events::EventType::Unknown {
+                    update_type: "SwitchScenes".to_string(),
+                    data: json!({
+                        "scene-name": "Scene 1",
+                        "sources": "not a list",
+                    }),
+                },
+            }
+        );
+        assert_eq!(events[1].update_type, events::EventType::ScenesChanged);
+    }
+
+    #[test]
+    fn liveness_poll_fallback() {
+        init_logger();
+        let (obs, handle) = init(vec![
+            json!({
+                "status": "ok",
+                "version": 1.1,
+                "obs-websocket-version": "4.8.0",
+                "obs-studio-version": "25.0.8",
+                "available-requests": "GetVersion",
+            }),
+            json!({ "status": "error", "error": "error" }),
+        ]);
+        let liveness = obs.liveness_stream(Duration::from_millis(50));
+        let liveness = smol::block_on(liveness.take(2).collect::<Vec<_>>());
+        let actual_requests = handle.join().expect("failed to join");
+        smol::block_on(obs.disconnect()).unwrap();
+
+        assert!(actual_requests
+            .iter()
+            .all(|request| request["request-type"] == "GetVersion"));
+        assert_eq!(
+            liveness,
+            vec![
+                Liveness {
+                    alive: true,
+                    source: LivenessSource::Poll,
+                },
+                Liveness {
+                    alive: false,
+                    source: LivenessSource::Poll,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn liveness_slow_poll() {
+        init_logger();
+        let (obs, handle) = init_mock(vec![
+            // answered only after the poll has given up on it
+            Mock::RespondAfter(Duration::from_millis(200), json!({ "status": "ok" })),
+            Mock::Respond(json!({ "status": "ok" })),
+        ]);
+        let liveness = obs.liveness_stream(Duration::from_millis(50));
+        let liveness = smol::block_on(liveness.take(1).collect::<Vec<_>>());
+        thread::sleep(Duration::from_millis(300));
+        let connected = obs.is_connected();
+        let res = smol::block_on(obs.request(&SetHeartbeat::builder().enable(false).build()));
+        handle.join().expect("failed to join");
+        smol::block_on(obs.disconnect()).unwrap();
+
+        assert!(!liveness[0].alive);
+        assert!(connected);
+        res.expect("request returned err");
+    }
+
+    #[test]
+    fn liveness_after_connection_lost() {
+        init_logger();
+        // the mock server closes the connection once all steps are done
+        let (obs, handle) = init(vec![]);
+        handle.join().expect("failed to join");
+        let start = Instant::now();
+        while obs.is_connected() {
+            assert!(
+                start.elapsed() < Duration::from_secs(5),
+                "connection was not lost"
+            );
+            thread::sleep(Duration::from_millis(10));
+        }
+        // the stream keeps going instead of ending
+        let liveness = obs.liveness_stream(Duration::from_millis(50));
+        let liveness = smol::block_on(liveness.take(2).collect::<Vec<_>>());
+        let _ = smol::block_on(obs.disconnect());
+
+        assert_eq!(liveness.len(), 2);
+        assert!(liveness.iter().all(|liveness| !liveness.alive));
+    }
+
+    #[test]
+    fn source_thumbnails() {
+        init_logger();
+        let screenshot = |data: &[u8]| {
+            json!({
+                "status": "ok",
+                "sourceName": "Camera",
+                "img": format!("data:image/png;base64,{}", base64::encode(data)),
+            })
+        };
+        let (obs, handle) = init(vec![screenshot(b"first"), screenshot(b"second")]);
+        let thumbnails = obs.source_thumbnails("Camera", Duration::from_millis(10), 320);
+        let thumbnails = smol::block_on(thumbnails.take(2).collect::<Vec<_>>());
+        let actual_requests = handle.join().expect("failed to join");
+        smol::block_on(obs.disconnect()).unwrap();
+
+        assert_eq!(actual_requests.len(), 2);
+        for request in &actual_requests {
+            assert_eq!(request["request-type"], "TakeSourceScreenshot");
+            assert_eq!(request["sourceName"], "Camera");
+            assert_eq!(request["embedPictureFormat"], "png");
+            assert_eq!(request["width"], 320);
+        }
+        assert_eq!(thumbnails[0].as_ref().unwrap(), b"first");
+        assert_eq!(thumbnails[1].as_ref().unwrap(), b"second");
+    }
+
+    #[test]
+    fn status_tracking() {
+        init_logger();
+        let (obs, handle) = init_mock(vec![
+            Mock::Respond(json!({
+                "status": "ok",
+                "streaming": false,
+                "recording": true,
+                "recording-paused": false,
+                "preview-only": false,
+            })),
+            // the events are only sent after the second request so that the initial status can be checked
+            Mock::Respond(json!({
+                "status": "ok",
+                "version": 1.1,
+                "obs-websocket-version": "4.8.0",
+                "obs-studio-version": "25.0.8",
+                "available-requests": "GetVersion",
+            })),
+            Mock::Send(json!({ "update-type": "StreamStarted" })),
+            Mock::Send(json!({ "update-type": "RecordingPaused" })),
+            Mock::Send(json!({ "update-type": "ReplayStarted" })),
+        ]);
+        let mut events = obs.subscribe();
+        smol::block_on(obs.enable_status_tracking()).expect("request returned err");
+        assert!(!obs.is_streaming());
+        assert!(obs.is_recording());
+        assert!(!obs.is_recording_paused());
+        assert!(!obs.is_replay_active());
+        smol::block_on(obs.request(&GetVersion::builder().build())).expect("request returned err");
+
+        // the tracked status is updated before the events are passed on
+        let events = smol::block_on(async {
+            let mut received = vec![];
+            for _ in 0..3 {
+                received.push(events.next().await.unwrap().update_type);
+            }
+            received
+        });
+        assert_eq!(events[2], EventType::ReplayStarted);
+        assert!(obs.is_streaming());
+        assert!(obs.is_recording());
+        assert!(obs.is_recording_paused());
+        assert!(obs.is_replay_active());
+
+        handle.join().expect("failed to join");
+        smol::block_on(obs.disconnect()).unwrap();
+    }
+
+    #[test]
+    fn fade_volume_dropped_mid_request() {
+        init_logger();
+        let (obs, handle) = init_mock(vec![
+            Mock::Respond(json!({
+                "status": "ok",
+                "name": "Mic",
+                "volume": 0.2,
+                "muted": false,
+            })),
+            // the fade is dropped while waiting for this response
+            Mock::RespondAfter(Duration::from_millis(200), json!({ "status": "ok" })),
+            Mock::Respond(json!({ "status": "ok" })),
+        ]);
+        drop_after(
+            obs.fade_volume("Mic", 1.0, Duration::from_millis(20), 2),
+            Duration::from_millis(100),
+        );
+        assert_still_usable(&obs);
+        let actual_requests = handle.join().expect("failed to join");
+        smol::block_on(obs.disconnect()).unwrap();
+
+        assert_eq!(actual_requests[1]["request-type"], "SetVolume");
+        assert_eq!(actual_requests[2]["request-type"], "SetHeartbeat");
+    }
+
+    #[test]
+    fn volume_controller() {
+        init_logger();
+        let (obs, handle) = init(vec![json!({ "status": "ok" })]);
+        let controller = obs
+            .volume_controller("Mic", Duration::from_millis(100))
+            .unwrap();
+        controller.set(0.1);
+        controller.set(0.2);
+        controller.set(0.3);
+        let actual_requests = handle.join().expect("failed to join");
+        drop(controller);
+        smol::block_on(obs.disconnect()).unwrap();
+
+        assert_eq!(actual_requests.len(), 1);
+        assert_eq!(actual_requests[0]["request-type"], "SetVolume");
+        assert_eq!(actual_requests[0]["source"], "Mic");
+        assert_eq!(actual_requests[0]["volume"], 0.3);
+    }
+
+    #[test]
+    fn with_studio_mode() {
+        init_logger();
+        let ok = json!({ "status": "ok" });
+        let (obs, handle) = init(vec![
+            json!({ "status": "ok", "studio-mode": false }),
+            ok.clone(),
+            ok.clone(),
+            ok,
+        ]);
+        smol::block_on(obs.with_studio_mode(true, |obs| async move {
+            let set = SetPreviewScene::builder().scene_name("Scene 2").build();
+            obs.request(&set).await
+        }))
+        .expect("request returned err");
+        let actual_requests = handle.join().expect("failed to join");
+        smol::block_on(obs.disconnect()).unwrap();
+
+        let request_types = actual_requests
+            .iter()
+            .map(|req| req["request-type"].as_str().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            request_types,
+            vec![
+                "GetStudioModeStatus",
+                "EnableStudioMode",
+                "SetPreviewScene",
+                "DisableStudioMode",
+            ]
+        );
+        assert_eq!(actual_requests[2]["scene-name"], "Scene 2");
+    }
+
+    #[test]
+    fn set_filter_visibility_all() {
+        init_logger();
+        let (obs, handle) = init(vec![
+            json!({ "status": "ok" }),
+            json!({ "status": "error", "error": "specified filter doesn't exist" }),
+        ]);
+        let failures =
+            smol::block_on(obs.set_filter_visibility_all("Blur", true, &["Camera 1", "Camera 2"]));
+        let actual_requests = handle.join().expect("failed to join");
+        smol::block_on(obs.disconnect()).unwrap();
+
+        assert_eq!(actual_requests.len(), 2);
+        for (req, source) in actual_requests.iter().zip(&["Camera 1", "Camera 2"]) {
+            assert_eq!(req["request-type"], "SetSourceFilterVisibility");
+            assert_eq!(req["sourceName"], *source);
+            assert_eq!(req["filterName"], "Blur");
+            assert_eq!(req["filterEnabled"], true);
+        }
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].0, "Camera 2");
+        assert!(
+            matches!(&failures[0].1, ObsError::ObsError(message) if message.contains("filter"))
+        );
+    }
+
+    #[test]
+    fn wait_for_scene() {
+        init_logger();
+        let current_scene = |name: &str| {
+            Mock::Respond(json!({
+                "status": "ok",
+                "name": name,
+                "sources": [],
+            }))
+        };
+        let switch = |name: &str| {
+            Mock::Send(json!({
+                "update-type": "SwitchScenes",
+                "scene-name": name,
+                "sources": [],
+            }))
+        };
+        let (obs, handle) = init_mock(vec![
+            current_scene("Intro"),
+            current_scene("Intro"),
+            switch("Game"),
+            switch("Outro"),
+            current_scene("Outro"),
+        ]);
+        smol::block_on(obs.wait_for_scene("Intro", Duration::from_secs(5)))
+            .expect("scene is already active");
+        smol::block_on(obs.wait_for_scene("Outro", Duration::from_secs(5)))
+            .expect("scene became active");
+        let timeout = smol::block_on(obs.wait_for_scene("Break", Duration::from_millis(50)));
+        handle.join().expect("failed to join");
+        smol::block_on(obs.disconnect()).unwrap();
+
+        assert!(matches!(timeout, Err(ObsError::RequestTimeout)));
+    }
+
+    #[test]
+    fn wait_for_scene_timeout_mid_request() {
+        init_logger();
+        let (obs, handle) = init_mock(vec![
+            // the wait times out before the current scene is received
+            Mock::RespondAfter(
+                Duration::from_millis(200),
+                json!({
+                    "status": "ok",
+                    "name": "Intro",
+                    "sources": [],
+                }),
+            ),
+            Mock::Respond(json!({ "status": "ok" })),
+        ]);
+        let timeout = smol::block_on(obs.wait_for_scene("Intro", Duration::from_millis(50)));
+        assert_still_usable(&obs);
+        let actual_requests = handle.join().expect("failed to join");
+        smol::block_on(obs.disconnect()).unwrap();
+
+        assert!(matches!(timeout, Err(ObsError::RequestTimeout)));
+        assert_eq!(actual_requests[0]["request-type"], "GetCurrentScene");
+        assert_eq!(actual_requests[1]["request-type"], "SetHeartbeat");
+    }
+
+    #[test]
+    fn with_default_scene() {
+        init_logger();
+        let properties = scene_item_properties_json("Camera", (0.0, 0.0), (1.0, 1.0), (100, 100));
+        let (obs, handle) = init(vec![properties.clone(), properties]);
+        let scene = obs.with_default_scene("Overlay");
+        let unset = GetSceneItemProperties::builder().item("Camera").build();
+        let set = GetSceneItemProperties::builder()
+            .scene_name("Game")
+            .item("Camera")
+            .build();
+        smol::block_on(scene.request(&unset)).expect("request returned err");
+        smol::block_on(scene.request(&set)).expect("request returned err");
+        let actual_requests = handle.join().expect("failed to join");
+        smol::block_on(obs.disconnect()).unwrap();
+
+        assert_eq!(actual_requests[0]["scene-name"], "Overlay");
+        assert_eq!(actual_requests[1]["scene-name"], "Game");
+    }
+
+    #[test]
+    fn with_default_scene_item_list() {
+        init_logger();
+        let (obs, handle) = init(vec![json!({
             "status": "ok",
-            "baseWidth": 0,
-            "baseHeight": 1,
-            "outputWidth": 2,
-            "outputHeight": 3,
-            "scaleType": "VIDEO_SCALE_BICUBIC",
-            "fps": 4.0,
-            "videoFormat": "VIDEO_FORMAT_NV12",
-            "colorSpace": "VIDEO_CS_601",
-            "colorRange": "VIDEO_RANGE_PARTIAL",
+            "sceneName": "Overlay",
+            "sceneItems": [],
+        })]);
+        let scene = obs.with_default_scene("Overlay");
+        let item_list = smol::block_on(scene.request(&GetSceneItemList::builder().build()))
+            .expect("request returned err");
+        let actual_requests = handle.join().expect("failed to join");
+        smol::block_on(obs.disconnect()).unwrap();
+
+        assert_eq!(actual_requests[0]["sceneName"], "Overlay");
+        assert_eq!(item_list.scene_name, "Overlay");
+    }
+
+    #[test]
+    fn set_browser_source_properties_checked() {
+        let valid = SetBrowserSourceProperties::builder()
+            .source("Browser")
+            .url("https://example.com/overlay?theme=dark")
+            .build()
+            .checked();
+        assert!(valid.is_ok());
+
+        let invalid = SetBrowserSourceProperties::builder()
+            .source("Browser")
+            .url("example.com/overlay")
+            .build()
+            .checked();
+        assert!(
+            matches!(invalid, Err(ObsError::InvalidUrl { url, .. }) if url == "example.com/overlay")
+        );
+
+        // local files are not URLs
+        let local_file = SetBrowserSourceProperties::builder()
+            .source("Browser")
+            .is_local_file(true)
+            .url("overlay.html")
+            .build()
+            .checked();
+        assert!(local_file.is_ok());
+    }
+
+    #[test]
+    fn execute_batch() {
+        init_logger();
+        let (obs, handle) = init(vec![json!({
+            "status": "ok",
+            "results": [
+                {
+                    "message-id": "1",
+                    "status": "ok",
+                    "name": "Scene 1",
+                    "sources": [],
+                },
+                {
+                    "message-id": "2",
+                    "status": "error",
+                    "error": "specified source doesn't exist",
+                },
+            ],
+        })]);
+        let mut batch = ExecuteBatch::builder().build();
+        batch.push(&GetCurrentScene::builder().build());
+        batch.push(&GetSourceSettings::builder().source_name("Missing").build());
+        let response = smol::block_on(obs.request(&batch)).expect("request returned err");
+        let actual_requests = handle.join().expect("failed to join");
+        smol::block_on(obs.disconnect()).unwrap();
+
+        let sub_requests = actual_requests[0]["requests"].as_array().unwrap();
+        assert_eq!(sub_requests[0]["request-type"], "GetCurrentScene");
+        assert_eq!(sub_requests[1]["request-type"], "GetSourceSettings");
+        let mut results = response.into_results().into_iter();
+        let current_scene =
+            responses::ExecuteBatch::typed::<GetCurrentScene>(results.next().unwrap()).unwrap();
+        assert_eq!(current_scene.name, "Scene 1");
+        let missing = responses::ExecuteBatch::typed::<GetSourceSettings>(results.next().unwrap());
+        assert!(matches!(missing, Err(ObsError::ObsError(message)) if message.contains("source")));
+        assert!(results.next().is_none());
+    }
+
+    #[test]
+    fn execute_batch_scene_name_mapper() {
+        #[derive(Debug)]
+        struct Prefix;
+
+        impl crate::SceneNameMapper for Prefix {
+            fn obs_name(&self, name: &str) -> String {
+                format!("tenant/{}", name)
+            }
+
+            fn app_name(&self, name: &str) -> String {
+                name.trim_start_matches("tenant/").to_string()
+            }
+        }
+
+        init_logger();
+        let config = ObsConfig::builder()
+            .scene_name_mapper(Box::new(Prefix))
+            .message_id_prefix("batch-")
+            .build();
+        let (obs, handle) = init_mock_with_config(
+            vec![Mock::Respond(json!({
+                "status": "ok",
+                "results": [
+                    { "status": "ok" },
+                    {
+                        "status": "ok",
+                        "name": "tenant/Scene 1",
+                        "sources": [],
+                    },
+                ],
+            }))],
+            config,
+        );
+        let mut batch = ExecuteBatch::builder().build();
+        batch.push(&SetCurrentScene::builder().scene_name("Scene 1").build());
+        batch.push(&GetCurrentScene::builder().build());
+        let response = smol::block_on(obs.request(&batch)).expect("request returned err");
+        let actual_requests = handle.join().expect("failed to join");
+        smol::block_on(obs.disconnect()).unwrap();
+
+        let sub_requests = actual_requests[0]["requests"].as_array().unwrap();
+        assert_eq!(sub_requests[0]["scene-name"], "tenant/Scene 1");
+        assert!(sub_requests
+            .iter()
+            .all(|req| req["message-id"].as_str().unwrap().starts_with("batch-")));
+        let mut results = response.into_results().into_iter();
+        results.next().unwrap().expect("request returned err");
+        let current_scene =
+            responses::ExecuteBatch::typed::<GetCurrentScene>(results.next().unwrap()).unwrap();
+        assert_eq!(current_scene.name, "Scene 1");
+    }
+
+    #[test]
+    fn set_source_mixers() {
+        init_logger();
+        let mut responses = vec![json!({
+            "status": "ok",
+            "version": 1.1,
+            "obs-websocket-version": "4.9.1",
+            "obs-studio-version": "27.0.0",
+            "available-requests": "GetVersion,SetTracks",
+        })];
+        responses.extend((0..6).map(|_| json!({ "status": "ok" })));
+        let (obs, handle) = init(responses);
+        // mixers 1, 2 and 5
+        let mixers = SetSourceMixers::from_hex_mixers_value("Mic", 0xD3);
+        smol::block_on(obs.set_source_mixers(&mixers)).expect("request returned err");
+        let actual_requests = handle.join().expect("failed to join");
+        smol::block_on(obs.disconnect()).unwrap();
+
+        assert_eq!(mixers.mixers, [true, true, false, false, true, false]);
+        assert_eq!(actual_requests[0]["request-type"], "GetVersion");
+        let tracks = actual_requests[1..]
+            .iter()
+            .map(|req| {
+                assert_eq!(req["request-type"], "SetTracks");
+                assert_eq!(req["sourceName"], "Mic");
+                (
+                    req["track"].as_i64().unwrap(),
+                    req["active"].as_bool().unwrap(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            tracks,
+            vec![
+                (1, true),
+                (2, true),
+                (3, false),
+                (4, false),
+                (5, true),
+                (6, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn describe_source_settings() {
+        init_logger();
+        let (obs, handle) = init(vec![json!({
+            "status": "ok",
+            "sourceName": "Text",
+            "sourceType": "text_ft2_source_v2",
+            "sourceSettings": {
+                "text": "Hello",
+                "color1": 4294967295u32,
+            },
+        })]);
+        let settings =
+            smol::block_on(obs.describe_source_settings("Text")).expect("request returned err");
+        handle.join().expect("failed to join");
+        smol::block_on(obs.disconnect()).unwrap();
+
+        assert_eq!(
+            settings,
+            vec![
+                ("color1".to_string(), json!(4294967295u32)),
+                ("text".to_string(), json!("Hello")),
+            ]
+        );
+    }
+
+    #[test]
+    fn fade_volume() {
+        init_logger();
+        let mut responses = vec![json!({
+            "status": "ok",
+            "name": "Mic",
+            "volume": 0.2,
+            "muted": false,
+        })];
+        responses.extend((0..4).map(|_| json!({ "status": "ok" })));
+        let (obs, handle) = init(responses);
+        smol::block_on(obs.fade_volume("Mic", 1.0, Duration::from_millis(40), 4))
+            .expect("request returned err");
+        let actual_requests = handle.join().expect("failed to join");
+        smol::block_on(obs.disconnect()).unwrap();
+
+        assert_eq!(actual_requests.len(), 5);
+        assert_eq!(actual_requests[0]["request-type"], "GetVolume");
+        let volumes = actual_requests[1..]
+            .iter()
+            .map(|req| {
+                assert_eq!(req["request-type"], "SetVolume");
+                assert_eq!(req["source"], "Mic");
+                req["volume"].as_f64().unwrap()
+            })
+            .collect::<Vec<_>>();
+        assert!(volumes.windows(2).all(|w| w[0] < w[1]));
+        assert!(volumes[0] > 0.2);
+        assert_eq!(volumes[3], 1.0);
+    }
+
+    #[test]
+    fn reorder_scene_items() {
+        init_logger();
+
+        let request = json!({
+            "request-type": "ReorderSceneItems",
+            "scene": "s",
+            "items": [
+                {
+                    "name": "n",
+                },
+                {
+                    "id": 1,
+                },
+            ],
+        });
+        let response = json!({
+            "status": "ok",
+        });
+        let req = ReorderSceneItems::builder()
+            .scene("s")
+            .items(vec![ItemId::Name("n".to_string()), ItemId::Id(1)])
+            .build();
+        let expected = responses::Empty {};
+        request_test(vec![request], vec![response], req, expected);
+    }
+
+    #[test]
+    fn invalid_response() {
+        init_logger();
+        let (obs, handle) = init(vec![json!({
+            "status": "ok",
+            "version": 1.1,
+            "obs-websocket-version": 4.8,
+            "obs-studio-version": "25.0.8",
+            "available-requests": "GetVersion",
+        })]);
+        let error = smol::block_on(obs.request(&GetVersion::builder().build())).unwrap_err();
+        handle.join().expect("failed to join");
+        smol::block_on(obs.disconnect()).unwrap();
+
+        assert!(matches!(
+            &error,
+            ObsError::InvalidResponse { request_type, response, .. }
+                if request_type == "GetVersion" && response.contains("4.8")
+        ));
+        assert!(error.to_string().contains("GetVersion"));
+    }
+
+    #[test]
+    fn malformed_response() {
+        init_logger();
+        let (obs, handle) = init(vec![json!({
+            "status": "ok",
+            "name": "Mic/Aux",
+            "muted": false,
+        })]);
+        let error = smol::block_on(obs.request(&GetVolume::builder().source("Mic/Aux").build()))
+            .unwrap_err();
+        handle.join().expect("failed to join");
+        smol::block_on(obs.disconnect()).unwrap();
+
+        assert!(matches!(
+            &error,
+            ObsError::MalformedResponse { request_type, missing_field, .. }
+                if request_type == "GetVolume" && missing_field == "volume"
+        ));
+    }
+
+    #[test]
+    fn scene_name_mapper() {
+        #[derive(Debug)]
+        struct Prefix;
+
+        impl crate::SceneNameMapper for Prefix {
+            fn obs_name(&self, name: &str) -> String {
+                format!("tenant/{}", name)
+            }
+
+            fn app_name(&self, name: &str) -> String {
+                name.trim_start_matches("tenant/").to_string()
+            }
+        }
+
+        init_logger();
+        let config = ObsConfig::builder()
+            .scene_name_mapper(Box::new(Prefix))
+            .build();
+        let (obs, handle) = init_mock_with_config(
+            vec![
+                Mock::Respond(json!({ "status": "ok" })),
+                Mock::Respond(json!({
+                    "status": "ok",
+                    "name": "tenant/Scene 1",
+                    "sources": [scene_item_json(1, "Camera", "input")],
+                })),
+                Mock::Send(json!({
+                    "update-type": "SwitchScenes",
+                    "scene-name": "tenant/Scene 1",
+                    "sources": [],
+                })),
+            ],
+            config,
+        );
+        let switches = obs.events_of::<events::SwitchScenesEvent>();
+        smol::block_on(obs.request(&SetCurrentScene::builder().scene_name("Scene 1").build()))
+            .expect("request returned err");
+        let current = smol::block_on(obs.request(&GetCurrentScene::builder().build()))
+            .expect("request returned err");
+        let switches = smol::block_on(switches.take(1).collect::<Vec<_>>());
+        let actual_requests = handle.join().expect("failed to join");
+        smol::block_on(obs.disconnect()).unwrap();
+
+        assert_eq!(actual_requests[0]["scene-name"], "tenant/Scene 1");
+        assert_eq!(current.name, "Scene 1");
+        // source names are not rewritten
+        assert_eq!(current.sources[0].name, "Camera");
+        assert_eq!(switches[0].scene_name, "Scene 1");
+    }
+
+    #[test]
+    fn audit_log() {
+        init_logger();
+        let path =
+            std::env::temp_dir().join(format!("obs-websocket-audit-{}.log", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let config = ObsConfig::builder().audit_log(path.clone()).build();
+        let (obs, handle) =
+            init_mock_with_config(vec![Mock::Respond(json!({ "status": "ok" }))], config);
+        smol::block_on(obs.request(&Authenticate::builder().auth("secret").build()))
+            .expect("request returned err");
+        handle.join().expect("failed to join");
+        smol::block_on(obs.disconnect()).unwrap();
+
+        let log = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let lines = log
+            .lines()
+            .map(|line| serde_json::from_str::<Value>(line).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["direction"], "request");
+        assert_eq!(lines[0]["message"]["request-type"], "Authenticate");
+        assert_eq!(lines[0]["message"]["auth"], "<redacted>");
+        assert!(lines[0]["timestamp"].as_u64().is_some());
+        assert_eq!(lines[1]["direction"], "response");
+        assert_eq!(lines[1]["message"]["status"], "ok");
+        assert!(!log.contains("secret"));
+    }
+
+    #[test]
+    fn interceptor() {
+        #[derive(Debug, Default)]
+        struct Counter {
+            requests: AtomicUsize,
+            errors: AtomicUsize,
+        }
+
+        #[derive(Debug)]
+        struct CountingInterceptor(sync::Arc<Counter>);
+
+        impl crate::Interceptor for CountingInterceptor {
+            fn before(&self, request_type: &str, value: &mut Value) {
+                assert_eq!(request_type, "GetVersion");
+                self.0.requests.fetch_add(1, Ordering::SeqCst);
+                value["token"] = json!("secret");
+            }
+
+            fn after(&self, request_type: &str, result: &Result<Value, String>) {
+                assert_eq!(request_type, "GetVersion");
+                if result.is_err() {
+                    self.0.errors.fetch_add(1, Ordering::SeqCst);
+                }
+            }
+        }
+
+        init_logger();
+        let counter = sync::Arc::new(Counter::default());
+        let config = ObsConfig::builder()
+            .interceptors(vec![Box::new(CountingInterceptor(counter.clone()))])
+            .build();
+        let (obs, handle) = init_mock_with_config(
+            vec![
+                Mock::Respond(json!({
+                    "status": "ok",
+                    "version": 1.1,
+                    "obs-websocket-version": "4.8.0",
+                    "obs-studio-version": "25.0.8",
+                    "available-requests": "GetVersion",
+                })),
+                Mock::Respond(json!({ "status": "error", "error": "error" })),
+            ],
+            config,
+        );
+        smol::block_on(obs.request(&GetVersion::builder().build())).expect("request returned err");
+        smol::block_on(obs.request(&GetVersion::builder().build())).unwrap_err();
+        let actual_requests = handle.join().expect("failed to join");
+        smol::block_on(obs.disconnect()).unwrap();
+
+        assert_eq!(counter.requests.load(Ordering::SeqCst), 2);
+        assert_eq!(counter.errors.load(Ordering::SeqCst), 1);
+        assert!(actual_requests.iter().all(|req| req["token"] == "secret"));
+    }
+
+    #[test]
+    fn request_latency() {
+        init_logger();
+        let latencies = sync::Arc::new(Mutex::new(vec![]));
+        let recorded = latencies.clone();
+        let config = ObsConfig::builder()
+            .on_request_complete(Box::new(move |request_type: &str, elapsed: Duration| {
+                recorded
+                    .lock()
+                    .unwrap()
+                    .push((request_type.to_string(), elapsed));
+            }))
+            .build();
+        let (obs, handle) = init_mock_with_config(
+            vec![Mock::Respond(
+                json!({ "status": "error", "error": "error" }),
+            )],
+            config,
+        );
+        smol::block_on(obs.request(&GetVersion::builder().build())).unwrap_err();
+        handle.join().expect("failed to join");
+        smol::block_on(obs.disconnect()).unwrap();
+
+        let latencies = latencies.lock().unwrap();
+        assert_eq!(latencies.len(), 1);
+        assert_eq!(latencies[0].0, "GetVersion");
+        assert!(latencies[0].1 > Duration::from_secs(0));
+    }
+
+    #[test]
+    fn duplicate_response() {
+        init_logger();
+        let version = |obs_studio_version: &str| {
+            Mock::Respond(json!({
+                "status": "ok",
+                "version": 1.1,
+                "obs-websocket-version": "4.8.0",
+                "obs-studio-version": obs_studio_version,
+                "available-requests": "GetVersion",
+            }))
+        };
+        let (obs, handle) = init_mock(vec![version("1"), Mock::RepeatLast, version("2")]);
+        let first = smol::block_on(obs.request(&GetVersion::builder().build()))
+            .expect("request returned err");
+        let second = smol::block_on(obs.request(&GetVersion::builder().build()))
+            .expect("request returned err");
+        handle.join().expect("failed to join");
+        let unexpected_responses = obs.shared.unexpected_responses.load(Ordering::Relaxed);
+        smol::block_on(obs.disconnect()).unwrap();
+
+        assert_eq!(first.obs_studio_version, "1");
+        assert_eq!(second.obs_studio_version, "2");
+        assert_eq!(unexpected_responses, 1);
+    }
+
+    #[test]
+    fn orphan_response_threshold() {
+        init_logger();
+        let server = TcpListener::bind("localhost:0").expect("bind");
+        let port = server.local_addr().expect("local addr").port();
+        let (done_sender, done_receiver) = std::sync::mpsc::channel::<()>();
+        let handle = thread::spawn(move || {
+            let (stream, _) = server.accept().expect("accept");
+            let mut websocket = accept(stream).expect("failed to accept");
+            // read the request but only respond to requests that were never sent
+            websocket.read_message().expect("failed to read message");
+            for message_id in &["orphan-1", "orphan-2"] {
+                let orphan = json!({ "message-id": message_id, "status": "ok" });
+                websocket
+                    .write_message(WebSocketMessage::Text(orphan.to_string()))
+                    .expect("failed to write");
+            }
+            let _ = done_receiver.recv();
+        });
+        let config = ObsConfig::builder().orphan_response_threshold(2).build();
+        let obs = init_without_server(port, config);
+        let res = smol::block_on(obs.request(&GetVersion::builder().build()));
+        let orphan_response_count = obs.orphan_response_count();
+        let _ = done_sender.send(());
+        handle.join().expect("join");
+        let _ = smol::block_on(obs.disconnect());
+
+        assert!(matches!(res, Err(ObsError::ConnectionLost)));
+        assert_eq!(orphan_response_count, 2);
+    }
+
+    #[test]
+    fn reconnect_after_failed_handshake() {
+        init_logger();
+        let server = TcpListener::bind("localhost:0").expect("bind");
+        let port = server.local_addr().expect("local addr").port();
+        let (failed_sender, failed_receiver) = std::sync::mpsc::channel::<()>();
+        let handle = thread::spawn(move || {
+            // the first connection is lost right away
+            let (stream, _) = server.accept().expect("accept");
+            let mut websocket = accept(stream).expect("failed to accept");
+            websocket.close(None).expect("failed to close");
+            let _ = websocket.write_pending();
+
+            // the first reconnect never gets a handshake response
+            let (stream, _) = server.accept().expect("accept");
+            let _ = failed_receiver.recv();
+            drop(stream);
+
+            // the second reconnect succeeds
+            let (stream, _) = server.accept().expect("accept");
+            let mut websocket = accept(stream).expect("failed to accept");
+            let request = websocket.read_message().expect("failed to read message");
+            let request = serde_json::from_str::<Value>(&request.to_string()).unwrap();
+            let response = json!({
+                "message-id": request["message-id"],
+                "status": "ok",
+            });
+            websocket
+                .write_message(WebSocketMessage::Text(response.to_string()))
+                .expect("failed to write");
+            request
+        });
+        let mut obs = init_without_server(port, ObsConfig::default());
+        let start = Instant::now();
+        while obs.is_connected() {
+            assert!(
+                start.elapsed() < Duration::from_secs(5),
+                "connection was not lost"
+            );
+            thread::sleep(Duration::from_millis(10));
+        }
+        let failed = smol::block_on(obs.reconnect());
+        let _ = failed_sender.send(());
+        let set = SetCurrentScene::builder().scene_name("Scene 2").build();
+        let not_connected = smol::block_on(obs.request(&set));
+        let _events = smol::block_on(obs.reconnect()).expect("failed to reconnect");
+        let res = smol::block_on(obs.request(&set));
+        let request = handle.join().expect("join");
+        smol::block_on(obs.disconnect()).unwrap();
+
+        assert!(matches!(failed, Err(ObsError::TungsteniteTimeout)));
+        assert!(matches!(not_connected, Err(ObsError::NotConnected)));
+        res.expect("request returned err");
+        assert_eq!(request["request-type"], "SetCurrentScene");
+    }
+
+    #[test]
+    fn reconnect() {
+        // reads a request and responds to it
+        fn respond(
+            websocket: &mut tungstenite::WebSocket<std::net::TcpStream>,
+            mut response: Value,
+        ) {
+            let request = websocket.read_message().expect("failed to read message");
+            let request = serde_json::from_str::<Value>(&request.to_string()).unwrap();
+            response["message-id"] = request["message-id"].clone();
+            websocket
+                .write_message(WebSocketMessage::Text(response.to_string()))
+                .expect("failed to write");
+        }
+
+        let streaming_status = |recording: bool| {
+            json!({
+                "status": "ok",
+                "streaming": false,
+                "recording": recording,
+                "preview-only": false,
+            })
+        };
+        let switch_scenes = json!({
+            "update-type": "SwitchScenes",
+            "scene-name": "Scene 1",
+            "sources": [],
+        });
+
+        init_logger();
+        let server = TcpListener::bind("localhost:0").expect("bind");
+        let port = server.local_addr().expect("local addr").port();
+        let (done_sender, done_receiver) = std::sync::mpsc::channel::<()>();
+        let handle = thread::spawn(move || {
+            // the first connection is lost after an event
+            let (stream, _) = server.accept().expect("accept");
+            let mut websocket = accept(stream).expect("failed to accept");
+            respond(&mut websocket, streaming_status(false));
+            websocket
+                .write_message(WebSocketMessage::Text(switch_scenes.to_string()))
+                .expect("failed to write");
+            websocket.close(None).expect("failed to close");
+            let _ = websocket.write_pending();
+
+            // the status is requested again after reconnecting
+            let (stream, _) = server.accept().expect("accept");
+            let mut websocket = accept(stream).expect("failed to accept");
+            respond(&mut websocket, streaming_status(true));
+            let stream_started = json!({ "update-type": "StreamStarted" });
+            websocket
+                .write_message(WebSocketMessage::Text(stream_started.to_string()))
+                .expect("failed to write");
+            let _ = done_receiver.recv();
+        });
+        let mut obs = init_without_server(port, ObsConfig::default());
+        let events = obs.subscribe();
+        smol::block_on(obs.enable_status_tracking()).expect("request returned err");
+        let start = Instant::now();
+        while obs.is_connected() {
+            assert!(
+                start.elapsed() < Duration::from_secs(5),
+                "connection was not lost"
+            );
+            thread::sleep(Duration::from_millis(10));
+        }
+        let _events = smol::block_on(obs.reconnect()).expect("failed to reconnect");
+        let events = smol::block_on(events.take(2).collect::<Vec<_>>());
+        let _ = done_sender.send(());
+        handle.join().expect("join");
+        let recording = obs.is_recording();
+        let streaming = obs.is_streaming();
+        smol::block_on(obs.disconnect()).unwrap();
+
+        assert!(matches!(
+            events[0].update_type,
+            events::EventType::SwitchScenes { .. }
+        ));
+        assert_eq!(events[1].update_type, events::EventType::StreamStarted);
+        assert!(recording);
+        assert!(streaming);
+    }
+
+    #[test]
+    fn defer_until_authenticated_after_reconnect() {
+        // reads a request and responds to it, returning the request type
+        fn respond(
+            websocket: &mut tungstenite::WebSocket<std::net::TcpStream>,
+            mut response: Value,
+        ) -> String {
+            let request = websocket.read_message().expect("failed to read message");
+            let request = serde_json::from_str::<Value>(&request.to_string()).unwrap();
+            response["message-id"] = request["message-id"].clone();
+            websocket
+                .write_message(WebSocketMessage::Text(response.to_string()))
+                .expect("failed to write");
+            request["request-type"].as_str().unwrap().to_string()
+        }
+
+        let auth_not_required = json!({
+            "status": "ok",
+            "authRequired": false,
         });
-        let req = GetVideoInfo::builder().build();
-        let expected = responses::GetVideoInfo {
-            base_width: 0,
-            base_height: 1,
-            output_width: 2,
-            output_height: 3,
-            scale_type: responses::ScaleType::Bicubic,
-            fps: 4.0,
-            video_format: responses::VideoFormat::NV12,
-            color_space: responses::ColorSpace::CS601,
-            color_range: responses::ColorRange::Partial,
-        };
-        request_test(vec![request], vec![response], req, expected);
+
+        init_logger();
+        let server = TcpListener::bind("localhost:0").expect("bind");
+        let port = server.local_addr().expect("local addr").port();
+        let handle = thread::spawn(move || {
+            // the first connection is lost after authenticating
+            let (stream, _) = server.accept().expect("accept");
+            let mut websocket = accept(stream).expect("failed to accept");
+            respond(&mut websocket, auth_not_required.clone());
+            websocket.close(None).expect("failed to close");
+            let _ = websocket.write_pending();
+
+            let (stream, _) = server.accept().expect("accept");
+            let mut websocket = accept(stream).expect("failed to accept");
+            let current_scene = json!({
+                "status": "ok",
+                "name": "Scene 1",
+                "sources": [],
+            });
+            let first = respond(&mut websocket, auth_not_required);
+            let second = respond(&mut websocket, current_scene);
+            vec![first, second]
+        });
+        let config = ObsConfig::builder().defer_until_authenticated(true).build();
+        let mut obs = init_without_server(port, config);
+        // the gate opens even though no authentication was required
+        let auth = smol::block_on(obs.authenticate("password"));
+        assert!(matches!(auth, Err(ObsError::NoAuthRequired)));
+        let start = Instant::now();
+        while obs.is_connected() {
+            assert!(
+                start.elapsed() < Duration::from_secs(5),
+                "connection was not lost"
+            );
+            thread::sleep(Duration::from_millis(10));
+        }
+        let _events = smol::block_on(obs.reconnect()).expect("failed to reconnect");
+        // the request is polled first but is only sent after authenticating again
+        let get_current_scene = GetCurrentScene::builder().build();
+        let (current, auth) = smol::block_on(future::join(
+            obs.request(&get_current_scene),
+            obs.authenticate("password"),
+        ));
+        let request_types = handle.join().expect("join");
+        smol::block_on(obs.disconnect()).unwrap();
+
+        assert!(matches!(auth, Err(ObsError::NoAuthRequired)));
+        assert_eq!(current.expect("request returned err").name, "Scene 1");
+        assert_eq!(request_types, vec!["GetAuthRequired", "GetCurrentScene"]);
     }
 
     #[test]
-    fn list_outputs() {
+    fn requests_right_after_connect() {
+        const REQUESTS: usize = 20;
+
         init_logger();
+        let server = TcpListener::bind("localhost:0").expect("bind");
+        let port = server.local_addr().expect("local addr").port();
+        let handle = thread::spawn(move || {
+            let (stream, _) = server.accept().expect("accept");
+            let mut websocket = accept(stream).expect("failed to accept");
+            // sent right after the handshake response, likely in the same read on the client
+            let event = json!({ "update-type": "ScenesChanged" });
+            websocket
+                .write_message(WebSocketMessage::Text(event.to_string()))
+                .expect("failed to write");
+            let requests = (0..REQUESTS)
+                .map(|_| {
+                    let request = websocket.read_message().expect("failed to read message");
+                    serde_json::from_str::<Value>(&request.to_string()).expect("invalid request")
+                })
+                .collect::<Vec<_>>();
+            // respond in reverse order so that the responses have to be matched by message-id
+            for request in requests.iter().rev() {
+                let response = json!({
+                    "message-id": request["message-id"],
+                    "status": "ok",
+                    "name": request["source"],
+                    "muted": false,
+                });
+                websocket
+                    .write_message(WebSocketMessage::Text(response.to_string()))
+                    .expect("failed to write");
+            }
+            requests
+        });
+        let (obs, mut events) =
+            smol::block_on(Obs::connect("localhost", port)).expect("failed to connect");
+        let requests = (0..REQUESTS)
+            .map(|i| GetMute::builder().source(i.to_string()).build())
+            .collect::<Vec<_>>();
+        let responses = smol::block_on(future::join_all(
+            requests.iter().map(|req| obs.request(req)),
+        ));
+        let event = smol::block_on(events.next());
+        let actual_requests = handle.join().expect("join");
+        smol::block_on(obs.disconnect()).unwrap();
 
-        let request = json!({
-            "request-type": "ListOutputs",
+        assert_eq!(actual_requests.len(), REQUESTS);
+        for (i, response) in responses.into_iter().enumerate() {
+            assert_eq!(response.expect("request returned err").name, i.to_string());
+        }
+        assert_eq!(
+            event.expect("no event").update_type,
+            events::EventType::ScenesChanged
+        );
+    }
+
+    #[test]
+    fn max_concurrent_requests() {
+        const REQUESTS: usize = 6;
+        const MAX_CONCURRENT_REQUESTS: usize = 2;
+
+        init_logger();
+        let server = TcpListener::bind("localhost:0").expect("bind");
+        let port = server.local_addr().expect("local addr").port();
+        let handle = thread::spawn(move || {
+            let (stream, _) = server.accept().expect("accept");
+            stream
+                .set_read_timeout(Some(Duration::from_millis(50)))
+                .expect("failed to set timeout");
+            let mut websocket = accept(stream).expect("failed to accept");
+            // keeps reading requests until none arrive for a while before responding to the oldest one,
+            // so that every request the client is willing to send is outstanding at the same time
+            let mut outstanding = std::collections::VecDeque::new();
+            let mut max_outstanding = 0;
+            let mut responded = 0;
+            while responded < REQUESTS {
+                match websocket.read_message() {
+                    Ok(request) => {
+                        let request = serde_json::from_str::<Value>(&request.to_string())
+                            .expect("invalid request");
+                        outstanding.push_back(request["message-id"].clone());
+                        max_outstanding = max_outstanding.max(outstanding.len());
+                    }
+                    Err(tungstenite::Error::Io(e))
+                        if e.kind() == std::io::ErrorKind::WouldBlock
+                            || e.kind() == std::io::ErrorKind::TimedOut =>
+                    {
+                        if let Some(message_id) = outstanding.pop_front() {
+                            let response = json!({
+                                "message-id": message_id,
+                                "status": "ok",
+                            });
+                            websocket
+                                .write_message(WebSocketMessage::Text(response.to_string()))
+                                .expect("failed to write");
+                            responded += 1;
+                        }
+                    }
+                    Err(e) => panic!("failed to read message: {}", e),
+                }
+            }
+            max_outstanding
         });
-        let response = json!({
-            "status": "ok",
-            "outputs": [
-                {
-                    "name": "simple_file_output",
-                    "type": "ffmpeg_muxer",
-                    "width": 0,
-                    "height": 1,
-                    "flags": {
-                        "rawValue": 6,
-                        "audio": true,
-                        "video": true,
-                        "encoded": true,
-                        "multiTrack": true,
-                        "service": true,
-                    },
-                    "settings": {},
-                    "active": false,
-                    "reconnecting": false,
-                    "congestion": 2.0,
-                    "totalFrames": 3,
-                    "droppedFrames": 4,
-                    "totalBytes": 5,
+        let config = ObsConfig::builder()
+            .max_concurrent_requests(MAX_CONCURRENT_REQUESTS)
+            .build();
+        let obs = init_without_server(port, config);
+        let requests = (0..REQUESTS)
+            .map(|i| SetCurrentScene::builder().scene_name(i.to_string()).build())
+            .collect::<Vec<_>>();
+        let responses = smol::block_on(future::join_all(
+            requests.iter().map(|req| obs.request(req)),
+        ));
+        let max_outstanding = handle.join().expect("join");
+        smol::block_on(obs.disconnect()).unwrap();
+
+        assert!(responses.iter().all(Result::is_ok));
+        assert_eq!(max_outstanding, MAX_CONCURRENT_REQUESTS);
+    }
+
+    #[test]
+    fn handler_panic() {
+        #[derive(Debug)]
+        struct PanickingInterceptor;
+
+        impl crate::Interceptor for PanickingInterceptor {
+            fn before(&self, request_type: &str, _value: &mut Value) {
+                if request_type == "GetStats" {
+                    panic!("interceptor panicked");
                 }
-            ],
+            }
+        }
+
+        init_logger();
+        let server = TcpListener::bind("localhost:0").expect("bind");
+        let port = server.local_addr().expect("local addr").port();
+        let (done_sender, done_receiver) = std::sync::mpsc::channel::<()>();
+        let handle = thread::spawn(move || {
+            let (stream, _) = server.accept().expect("accept");
+            let mut websocket = accept(stream).expect("failed to accept");
+            // read the first request but never respond to it
+            websocket.read_message().expect("failed to read message");
+            let _ = done_receiver.recv();
         });
-        let req = ListOutputs::builder().build();
-        let expected = responses::ListOutputs {
-            outputs: vec![responses::Output {
-                name: "simple_file_output".to_string(),
-                output_type: "ffmpeg_muxer".to_string(),
-                width: 0,
-                height: 1,
-                flags: responses::Flags {
-                    raw_value: 6,
-                    audio: true,
-                    video: true,
-                    encoded: true,
-                    multi_track: true,
-                    service: true,
-                },
-                settings: Value::Object(serde_json::Map::new()),
-                active: false,
-                reconnecting: false,
-                congestion: 2.0,
-                total_frames: 3,
-                dropped_frames: 4,
-                total_bytes: 5,
-            }],
-        };
-        request_test(vec![request], vec![response], req, expected);
+        let config = ObsConfig::builder()
+            .interceptors(vec![Box::new(PanickingInterceptor)])
+            .catch_handler_panics(true)
+            .build();
+        let obs = init_without_server(port, config);
+        let get_version = GetVersion::builder().build();
+        let get_stats = GetStats::builder().build();
+        let (pending, panicking) = smol::block_on(future::join(
+            obs.request(&get_version),
+            obs.request(&get_stats),
+        ));
+        let _ = done_sender.send(());
+        handle.join().expect("join");
+        let _ = smol::block_on(obs.disconnect());
+
+        assert!(matches!(pending, Err(ObsError::ConnectionLost)));
+        assert!(panicking.is_err());
     }
 
     #[test]
-    fn get_output_info() {
+    fn binary_message() {
+        #[derive(Debug)]
+        struct BinaryInterceptor(sync::Arc<Mutex<Vec<Vec<u8>>>>);
+
+        impl crate::Interceptor for BinaryInterceptor {
+            fn binary(&self, data: &[u8]) {
+                self.0.lock().unwrap().push(data.to_vec());
+            }
+        }
+
         init_logger();
+        let received = sync::Arc::new(Mutex::new(vec![]));
+        let config = ObsConfig::builder()
+            .interceptors(vec![Box::new(BinaryInterceptor(received.clone()))])
+            .build();
+        let (obs, handle) = init_mock_with_config(
+            vec![
+                Mock::SendBinary(vec![1, 2, 3]),
+                Mock::Respond(json!({
+                    "status": "ok",
+                    "version": 1.1,
+                    "obs-websocket-version": "4.8.0",
+                    "obs-studio-version": "25.0.8",
+                    "available-requests": "GetVersion",
+                })),
+            ],
+            config,
+        );
+        smol::block_on(obs.request(&GetVersion::builder().build())).expect("request returned err");
+        handle.join().expect("failed to join");
+        smol::block_on(obs.disconnect()).unwrap();
 
-        let request = json!({
-            "request-type": "GetOutputInfo",
-            "outputName": "output1",
-        });
-        let response = json!({
-            "status": "ok",
-            "outputInfo": {
-                "name": "simple_file_output",
-                "type": "ffmpeg_muxer",
-                "width": 0,
-                "height": 1,
-                "flags": {
-                    "rawValue": 6,
-                    "audio": true,
-                    "video": true,
-                    "encoded": true,
-                    "multiTrack": true,
-                    "service": true,
+        assert_eq!(*received.lock().unwrap(), vec![vec![1, 2, 3]]);
+    }
+
+    // responds to a GetVersion request with a response of roughly the given size
+    fn large_response_test(
+        size: usize,
+        config: ObsConfig,
+    ) -> Result<responses::GetVersion, ObsError> {
+        init_logger();
+        let (obs, handle) = init_mock_with_config(
+            vec![Mock::Respond(json!({
+                "status": "ok",
+                "version": 1.1,
+                "obs-websocket-version": "4.8.0",
+                "obs-studio-version": "a".repeat(size),
+                "available-requests": "GetVersion",
+            }))],
+            config,
+        );
+        let result = smol::block_on(obs.request(&GetVersion::builder().build()));
+        handle.join().expect("failed to join");
+        let _ = smol::block_on(obs.disconnect());
+        result
+    }
+
+    #[test]
+    fn large_message() {
+        let config = ObsConfig::builder()
+            .max_message_size(1024 * 1024)
+            .max_frame_size(1024 * 1024)
+            .build();
+        let version = large_response_test(512 * 1024, config).expect("request returned err");
+        assert_eq!(version.obs_studio_version.len(), 512 * 1024);
+    }
+
+    #[test]
+    fn message_too_large() {
+        let config = ObsConfig::builder()
+            .max_message_size(1024 * 1024)
+            .max_frame_size(1024 * 1024)
+            .build();
+        let error = large_response_test(2 * 1024 * 1024, config).unwrap_err();
+        assert!(matches!(error, ObsError::ConnectionLost));
+    }
+
+    #[test]
+    fn pretty_json() {
+        init_logger();
+
+        let server = TcpListener::bind("localhost:0").expect("bind");
+        let port = server.local_addr().expect("local addr").port();
+        let handle = thread::spawn(move || {
+            let (stream, _) = server.accept().expect("accept");
+            let mut websocket = accept(stream).expect("failed to accept");
+            let text = websocket
+                .read_message()
+                .expect("failed to read message")
+                .into_text()
+                .expect("not text");
+            let request = serde_json::from_str::<Value>(&text).expect("failed to deserialize");
+            let response = json!({
+                "message-id": request["message-id"],
+                "status": "ok",
+                "stats": {
+                    "fps": 60.0,
+                    "render-total-frames": 0,
+                    "render-missed-frames": 0,
+                    "output-total-frames": 0,
+                    "output-skipped-frames": 0,
+                    "average-frame-time": 0.0,
+                    "cpu-usage": 0.0,
+                    "memory-usage": 0.0,
+                    "free-disk-space": 0.0,
                 },
-                "settings": {},
-                "active": false,
-                "reconnecting": false,
-                "congestion": 2.0,
-                "totalFrames": 3,
-                "droppedFrames": 4,
-                "totalBytes": 5,
-            },
+            });
+            websocket
+                .write_message(WebSocketMessage::Text(response.to_string()))
+                .expect("failed to write");
+            websocket.close(None).expect("failed to close");
+            text
         });
-        let req = GetOutputInfo::builder().output_name("output1").build();
-        let expected = responses::GetOutputInfo {
-            output_info: responses::Output {
-                name: "simple_file_output".to_string(),
-                output_type: "ffmpeg_muxer".to_string(),
-                width: 0,
-                height: 1,
-                flags: responses::Flags {
-                    raw_value: 6,
-                    audio: true,
-                    video: true,
-                    encoded: true,
-                    multi_track: true,
-                    service: true,
-                },
-                settings: Value::Object(serde_json::Map::new()),
-                active: false,
-                reconnecting: false,
-                congestion: 2.0,
-                total_frames: 3,
-                dropped_frames: 4,
-                total_bytes: 5,
-            },
+        let config = ObsConfig::builder().pretty_json(true).build();
+        let obs = init_without_server(port, config);
+        smol::block_on(obs.request(&GetStats::builder().build())).expect("request returned err");
+        let text = handle.join().expect("join");
+        smol::block_on(obs.disconnect()).unwrap();
+        assert!(text.contains('\n'));
+        assert!(text.contains("\"request-type\": \"GetStats\""));
+    }
+
+    #[test]
+    fn message_id_prefix() {
+        init_logger();
+        let config = ObsConfig::builder().message_id_prefix("client-").build();
+        let (obs, handle) = init_mock_with_config(
+            vec![Mock::Respond(json!({
+                "status": "ok",
+                "version": 1.1,
+                "obs-websocket-version": "4.7.0",
+                "obs-studio-version": "24.0.3",
+                "available-requests": "GetVersion",
+            }))],
+            config,
+        );
+        smol::block_on(obs.request(&GetVersion::builder().build())).expect("request returned err");
+        let actual_requests = handle.join().expect("failed to join");
+        smol::block_on(obs.disconnect()).unwrap();
+        let message_id = actual_requests[0]["message-id"].as_str().unwrap();
+        assert!(message_id.starts_with("client-"), "{}", message_id);
+        assert!(message_id["client-".len()..].parse::<u32>().is_ok());
+    }
+
+    #[test]
+    fn custom_message_id_with_prefix() {
+        // a request with a custom message-id that starts with the default prefix
+        #[derive(Debug)]
+        struct CustomId;
+
+        impl Request for CustomId {
+            const REQUEST_TYPE: &'static str = "GetStats";
+            type Response = responses::Empty;
+
+            fn to_json(&self, _message_id_prefix: &str) -> (String, Value) {
+                let message_id = "_mine".to_string();
+                let value = json!({
+                    "request-type": Self::REQUEST_TYPE,
+                    "message-id": message_id,
+                });
+                (message_id, value)
+            }
+        }
+
+        init_logger();
+        let config = ObsConfig::builder().message_id_prefix("client-").build();
+        let (obs, handle) =
+            init_mock_with_config(vec![Mock::Respond(json!({ "status": "ok" }))], config);
+        smol::block_on(obs.request(&CustomId)).expect("request returned err");
+        let actual_requests = handle.join().expect("failed to join");
+        smol::block_on(obs.disconnect()).unwrap();
+        assert_eq!(actual_requests[0]["message-id"], "_mine");
+    }
+
+    #[test]
+    fn require_request() {
+        init_logger();
+        let (obs, handle) = init(vec![json!({
+            "status": "ok",
+            "version": 1.1,
+            "obs-websocket-version": "4.7.0",
+            "obs-studio-version": "24.0.3",
+            "available-requests": "GetVersion,GetStats",
+        })]);
+        smol::block_on(obs.require_request::<GetStats>()).expect("GetStats is available");
+        let res = smol::block_on(obs.require_request::<OpenProjector>());
+        let actual_requests = handle.join().expect("failed to join");
+        smol::block_on(obs.disconnect()).unwrap();
+
+        // GetVersion is only requested once
+        assert_eq!(actual_requests.len(), 1);
+        match res {
+            Err(ObsError::Unsupported {
+                request_type,
+                obs_websocket_version,
+            }) => {
+                assert_eq!(request_type, "OpenProjector");
+                assert_eq!(obs_websocket_version, "4.7.0");
+            }
+            other => panic!("unexpected result {:?}", other),
+        }
+    }
+
+    #[test]
+    fn require_request_unknown_version() {
+        init_logger();
+        let (obs, handle) = init(vec![json!({
+            "status": "ok",
+            "version": { "major": 5 },
+            "available-requests": ["GetVersion"],
+        })]);
+        smol::block_on(obs.require_request::<GetStats>()).expect("version is unknown");
+        smol::block_on(obs.require_request::<OpenProjector>()).expect("version is unknown");
+        let actual_requests = handle.join().expect("failed to join");
+        smol::block_on(obs.disconnect()).unwrap();
+
+        // GetVersion is only requested once
+        assert_eq!(actual_requests.len(), 1);
+    }
+
+    #[test]
+    fn check_compatibility() {
+        init_logger();
+        let check = |obs_websocket_version: &str| {
+            let (obs, handle) = init(vec![json!({
+                "status": "ok",
+                "version": 1.1,
+                "obs-websocket-version": obs_websocket_version,
+                "obs-studio-version": "26.1.0",
+                "available-requests": "GetVersion",
+            })]);
+            let compatibility = smol::block_on(obs.check_compatibility());
+            handle.join().expect("failed to join");
+            smol::block_on(obs.disconnect()).unwrap();
+            compatibility
         };
-        request_test(vec![request], vec![response], req, expected);
+
+        assert_eq!(check("4.9.0").unwrap(), Compatibility::Ok);
+        assert_eq!(check("4.9.1").unwrap(), Compatibility::Ok);
+        assert_eq!(check("4.10.0").unwrap(), Compatibility::ServerNewer);
+        assert_eq!(check("5.0.0").unwrap(), Compatibility::ServerNewer);
+        assert_eq!(check("4.8.0").unwrap(), Compatibility::ServerOlder);
+        assert!(matches!(
+            check("unknown"),
+            Err(ObsError::UnknownServerVersion)
+        ));
     }
 
     #[test]
-    fn get_scene_item_properties() {
+    fn health_check_tolerates_unexpected_version() {
         init_logger();
 
-        let request = json!({
-            "request-type": "GetSceneItemProperties",
-            "scene-name": "scene",
-            "item": "source"
+        let unexpected_version = json!({
+            "status": "ok",
+            "version": "1.1",
         });
-        let response = json!({
+        let ok_version = json!({
             "status": "ok",
-            "name": "source",
-            "position": {
-                "x": 0,
-                "y": 1,
-                "alignment": 2,
-            },
-            "rotation": 3.0,
-            "scale": {
-                "x": 4.0,
-                "y": 5.0,
-            },
-            "crop": {
-                "top": 6,
-                "right": 7,
-                "bottom": 8,
-                "left": 9,
-            },
-            "visible": true,
-            "locked": true,
-            "bounds": {
-                "type": "OBS_BOUNDS_STRETCH",
-                "alignment": 10,
-                "x": 11.0,
-                "y": 12.0,
-            },
-            "sourceWidth": 13,
-            "sourceHeight": 14,
-            "width": 15.0,
-            "height": 16.0,
+            "version": 1.1,
+            "obs-websocket-version": "4.8.0",
+            "obs-studio-version": "25.0.8",
+            "available-requests": "GetVersion",
         });
-        let req = GetSceneItemProperties::builder()
-            .scene_name("scene")
-            .item("source")
+        let config = ObsConfig::builder()
+            .health_check_interval(Duration::from_millis(50))
             .build();
-        let expected = responses::GetSceneItemProperties {
-            name: "source".to_string(),
-            position: common_types::Position {
-                x: 0.0,
-                y: 1.0,
-                alignment: 2,
-            },
-            rotation: 3.0,
-            scale: common_types::Scale { x: 4.0, y: 5.0 },
-            crop: common_types::Crop {
-                top: 6,
-                right: 7,
-                bottom: 8,
-                left: 9,
-            },
-            visible: true,
-            locked: true,
-            bounds: common_types::Bounds {
-                bounds_type: common_types::BoundsType::Stretch,
-                alignment: 10,
-                x: 11.0,
-                y: 12.0,
-            },
-            source_width: 13,
-            source_height: 14,
-            width: 15.0,
-            height: 16.0,
-        };
-        request_test(vec![request], vec![response], req, expected);
+        // the health check is still running after the unexpected response if the second one arrives
+        let (obs, handle) = init_mock_with_config(
+            vec![Mock::Respond(unexpected_version), Mock::Respond(ok_version)],
+            config,
+        );
+        let actual_requests = handle.join().expect("failed to join");
+        assert_eq!(actual_requests.len(), 2);
+        assert!(actual_requests
+            .iter()
+            .all(|request| request["request-type"] == "GetVersion"));
+        let _ = smol::block_on(obs.disconnect());
     }
 
     #[test]
-    fn set_scene_item_properties() {
+    fn health_check_detects_unresponsive_server() {
         init_logger();
 
-        let request = json!({
-            "request-type": "SetSceneItemProperties",
-            "scene-name": "scene",
-            "item": "test",
-            "position": {
-                "x": 1.0,
-                "y": 2.0,
-                "alignment": 3,
-            },
-            "rotation": 4.0,
-            "scale": {
-                "x": 5.0,
-                "y": 6.0,
-            },
-            "crop": {
-                "top": 7,
-                "right": 8,
-                "bottom": 9,
-                "left": 10,
-            },
-            "visible": true,
-            "locked": true,
-            "bounds": {
-                "type": "OBS_BOUNDS_STRETCH",
-                "alignment": 11,
-                "x": 12.0,
-                "y": 13.0,
-            },
-        });
-        let response = json!({
-            "status": "ok",
+        let server = TcpListener::bind("localhost:0").expect("bind");
+        let port = server.local_addr().expect("local addr").port();
+        let handle = thread::spawn(move || {
+            let (stream, _) = server.accept().expect("accept");
+            let mut websocket = accept(stream).expect("failed to accept");
+            // read the health check but never respond to it
+            websocket.read_message().expect("failed to read message");
+            websocket
         });
-        let req = SetSceneItemProperties::builder()
-            .scene_name("scene")
-            .item("test")
-            .position_x(1.0)
-            .position_y(2.0)
-            .position_alignment(3)
-            .rotation(4.0)
-            .scale_x(5.0)
-            .scale_y(6.0)
-            .crop_top(7)
-            .crop_right(8)
-            .crop_bottom(9)
-            .crop_left(10)
-            .visible(true)
-            .locked(true)
-            .bounds_type(BoundsType::Stretch)
-            .bounds_alignment(11)
-            .bounds_x(12.0)
-            .bounds_y(13.0)
+        let config = ObsConfig::builder()
+            .health_check_interval(Duration::from_millis(50))
             .build();
-        let expected = responses::Empty {};
-        request_test(vec![request], vec![response], req, expected);
+        let (obs, events) =
+            smol::block_on(Obs::connect_with_config("localhost", port, config)).expect("connect");
+        assert!(obs.is_connected());
+        thread::sleep(Duration::from_millis(300));
+        assert!(!obs.is_connected());
+        assert!(smol::block_on(obs.request(&GetVersion::builder().build())).is_err());
+        // the event receiver gets the failure, the server is gone so reconnecting keeps failing
+        let events = smol::block_on(events.take(1).collect::<Vec<_>>());
+        let _websocket = handle.join().expect("join");
+
+        assert!(matches!(
+            events[0].update_type,
+            events::EventType::HealthCheckFailed { .. }
+        ));
     }
 
     #[test]
-    fn reorder_scene_items() {
+    fn health_check_reconnects() {
         init_logger();
 
-        let request = json!({
-            "request-type": "ReorderSceneItems",
-            "scene": "s",
-            "items": [
-                {
-                    "name": "n",
-                },
-                {
-                    "id": 1,
-                },
-            ],
-        });
-        let response = json!({
-            "status": "ok",
+        let server = TcpListener::bind("localhost:0").expect("bind");
+        let port = server.local_addr().expect("local addr").port();
+        let handle = thread::spawn(move || {
+            let (stream, _) = server.accept().expect("accept");
+            let mut unresponsive = accept(stream).expect("failed to accept");
+            // read the health check but never respond to it
+            unresponsive.read_message().expect("failed to read message");
+            // the second connection responds to everything until it's closed
+            let (stream, _) = server.accept().expect("accept");
+            let mut websocket = accept(stream).expect("failed to accept");
+            let mut request_types = vec![];
+            while let Ok(WebSocketMessage::Text(text)) = websocket.read_message() {
+                let request: Value = serde_json::from_str(&text).expect("invalid request");
+                let response = json!({
+                    "status": "ok",
+                    "message-id": request["message-id"],
+                    "version": 1.1,
+                    "obs-websocket-version": "4.8.0",
+                    "obs-studio-version": "25.0.8",
+                    "available-requests": "GetVersion,SetHeartbeat",
+                });
+                request_types.push(request["request-type"].clone());
+                let _ = websocket.write_message(WebSocketMessage::Text(response.to_string()));
+            }
+            request_types
         });
-        let req = ReorderSceneItems::builder()
-            .scene("s")
-            .items(vec![ItemId::Name("n".to_string()), ItemId::Id(1)])
+        let config = ObsConfig::builder()
+            .health_check_interval(Duration::from_millis(50))
             .build();
-        let expected = responses::Empty {};
-        request_test(vec![request], vec![response], req, expected);
+        let (obs, events) =
+            smol::block_on(Obs::connect_with_config("localhost", port, config)).expect("connect");
+        // the failure is reported and the same event receiver keeps receiving events after reconnecting
+        let events = smol::block_on(events.take(1).collect::<Vec<_>>());
+        thread::sleep(Duration::from_millis(300));
+        assert!(obs.is_connected());
+        smol::block_on(obs.request(&SetHeartbeat::builder().enable(false).build()))
+            .expect("request returned err");
+        smol::block_on(obs.disconnect()).unwrap();
+        let request_types = handle.join().expect("join");
+
+        assert!(matches!(
+            events[0].update_type,
+            events::EventType::HealthCheckFailed { .. }
+        ));
+        assert!(request_types.contains(&json!("SetHeartbeat")));
+    }
+
+    #[test]
+    fn parse_url() {
+        let url = |tls, host: &str, port| ObsUrl {
+            tls,
+            host: host.to_string(),
+            port,
+        };
+        assert_eq!(
+            ObsUrl::parse("ws://localhost").unwrap(),
+            url(false, "localhost", 4444)
+        );
+        assert_eq!(
+            ObsUrl::parse("ws://host:4455").unwrap(),
+            url(false, "host", 4455)
+        );
+        assert_eq!(
+            ObsUrl::parse("wss://host").unwrap(),
+            url(true, "host", 4444)
+        );
+        assert_eq!(
+            ObsUrl::parse("ws://[::1]:4455/").unwrap(),
+            url(false, "::1", 4455)
+        );
+        assert!(ObsUrl::parse("http://host").is_err());
+        assert!(ObsUrl::parse("ws://host:port").is_err());
+        assert!(ObsUrl::parse("ws://").is_err());
+    }
+
+    #[test]
+    fn connect_url_rejects_tls() {
+        let res = smol::block_on(Obs::connect_url("wss://localhost"));
+        assert!(matches!(res, Err(ObsError::TlsNotSupported)));
+    }
+
+    #[test]
+    fn close() {
+        init_logger();
+        let (mut obs, handle) = init(vec![]);
+        smol::block_on(obs.close()).unwrap();
+        handle.join().expect("failed to join");
+        assert!(!obs.is_connected());
+        let res = smol::block_on(obs.request(&GetVersion::builder().build()));
+        assert!(matches!(res, Err(ObsError::NotConnected)));
+        let res = smol::block_on(obs.close());
+        assert!(matches!(res, Err(ObsError::NotConnected)));
     }
 
     #[test]
@@ -927,6 +6678,256 @@ mod test {
         assert!(smol::block_on(obs.request(&GetVersion::builder().build())).is_err());
     }
 
+    #[test]
+    fn send_failure_resolves_pending_requests() {
+        init_logger();
+
+        let server = TcpListener::bind("localhost:0").expect("bind");
+        let port = server.local_addr().expect("local addr").port();
+        let (done_sender, done_receiver) = std::sync::mpsc::channel::<()>();
+        let handle = thread::spawn(move || {
+            let (stream, _) = server.accept().expect("accept");
+            let mut websocket = accept(stream).expect("failed to accept");
+            // read the first request but never respond to it
+            websocket.read_message().expect("failed to read message");
+            // keep the connection open until the test is done
+            let _ = done_receiver.recv();
+        });
+        let addr = format!("localhost:{}", port)
+            .to_socket_addrs()
+            .expect("failed to resolve")
+            .next()
+            .expect("no address");
+        let tcp_stream =
+            smol::block_on(Async::<TcpStream>::connect(addr)).expect("failed to connect");
+        let tcp_stream = Arc::new(tcp_stream);
+        let obs = smol::block_on(Obs::connect_with_transport(
+            tcp_stream.clone(),
+            &format!("ws://localhost:{}", port),
+            ObsConfig::default(),
+        ))
+        .expect("failed to connect")
+        .0;
+
+        let get_version = GetVersion::builder().build();
+        let get_stats = GetStats::builder().build();
+        let (pending, failed) = smol::block_on(future::join(obs.request(&get_version), async {
+            Timer::after(Duration::from_millis(50)).await;
+            // make the next send fail
+            tcp_stream
+                .get_ref()
+                .shutdown(std::net::Shutdown::Write)
+                .expect("failed to shut down");
+            obs.request(&get_stats).await
+        }));
+        assert!(matches!(failed, Err(ObsError::ConnectionLost)));
+        assert!(matches!(pending, Err(ObsError::ConnectionLost)));
+        done_sender.send(()).unwrap();
+        handle.join().expect("join");
+    }
+
+    #[test]
+    fn interrupted_flush_is_retried() {
+        // a transport whose next flush is interrupted once armed
+        #[derive(Clone)]
+        struct FlakyTransport {
+            inner: MemoryTransport,
+            armed: sync::Arc<AtomicBool>,
+        }
+
+        impl futures::io::AsyncRead for FlakyTransport {
+            fn poll_read(
+                mut self: std::pin::Pin<&mut Self>,
+                cx: &mut std::task::Context<'_>,
+                buf: &mut [u8],
+            ) -> std::task::Poll<io::Result<usize>> {
+                std::pin::Pin::new(&mut self.inner).poll_read(cx, buf)
+            }
+        }
+
+        impl futures::io::AsyncWrite for FlakyTransport {
+            fn poll_write(
+                mut self: std::pin::Pin<&mut Self>,
+                cx: &mut std::task::Context<'_>,
+                buf: &[u8],
+            ) -> std::task::Poll<io::Result<usize>> {
+                std::pin::Pin::new(&mut self.inner).poll_write(cx, buf)
+            }
+
+            fn poll_flush(
+                mut self: std::pin::Pin<&mut Self>,
+                cx: &mut std::task::Context<'_>,
+            ) -> std::task::Poll<io::Result<()>> {
+                if self.armed.swap(false, Ordering::SeqCst) {
+                    return std::task::Poll::Ready(Err(io::ErrorKind::Interrupted.into()));
+                }
+                std::pin::Pin::new(&mut self.inner).poll_flush(cx)
+            }
+
+            fn poll_close(
+                mut self: std::pin::Pin<&mut Self>,
+                cx: &mut std::task::Context<'_>,
+            ) -> std::task::Poll<io::Result<()>> {
+                std::pin::Pin::new(&mut self.inner).poll_close(cx)
+            }
+        }
+
+        impl Transport for FlakyTransport {
+            fn clone_transport(&self) -> Box<dyn Transport> {
+                Box::new(self.clone())
+            }
+        }
+
+        init_logger();
+        let (client, server) = MemoryTransport::pair();
+        let handle = thread::spawn(move || {
+            smol::block_on(async move {
+                let mut websocket = async_tungstenite::accept_async(server)
+                    .await
+                    .expect("failed to accept");
+                let mut requests = vec![];
+                while let Some(Ok(message)) = websocket.next().await {
+                    if let Ok(request) = serde_json::from_str::<Value>(&message.to_string()) {
+                        let response = json!({
+                            "status": "ok",
+                            "message-id": request["message-id"],
+                        });
+                        requests.push(request);
+                        websocket
+                            .send(WebSocketMessage::Text(response.to_string()))
+                            .await
+                            .expect("failed to send");
+                    }
+                }
+                requests
+            })
+        });
+        let armed = sync::Arc::new(AtomicBool::new(false));
+        let client = FlakyTransport {
+            inner: client,
+            armed: armed.clone(),
+        };
+        let obs = smol::block_on(Obs::connect_with_transport(
+            client,
+            "ws://localhost:4444",
+            ObsConfig::default(),
+        ))
+        .expect("failed to connect")
+        .0;
+        // the first attempt at sending the request fails with the interrupted flush
+        armed.store(true, Ordering::SeqCst);
+        smol::block_on(obs.request(&SetHeartbeat::builder().enable(false).build()))
+            .expect("request returned err");
+        smol::block_on(obs.disconnect()).unwrap();
+        let requests = handle.join().expect("join");
+
+        assert!(!armed.load(Ordering::SeqCst));
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0]["request-type"], "SetHeartbeat");
+    }
+
+    #[test]
+    fn interrupted_write_is_not_retried() {
+        // a transport that writes half of a message and then fails once armed, like a connection lost mid-write
+        #[derive(Clone)]
+        struct InterruptingTransport {
+            inner: MemoryTransport,
+            armed: sync::Arc<AtomicBool>,
+            interrupted: sync::Arc<AtomicBool>,
+        }
+
+        impl futures::io::AsyncRead for InterruptingTransport {
+            fn poll_read(
+                mut self: std::pin::Pin<&mut Self>,
+                cx: &mut std::task::Context<'_>,
+                buf: &mut [u8],
+            ) -> std::task::Poll<io::Result<usize>> {
+                std::pin::Pin::new(&mut self.inner).poll_read(cx, buf)
+            }
+        }
+
+        impl futures::io::AsyncWrite for InterruptingTransport {
+            fn poll_write(
+                mut self: std::pin::Pin<&mut Self>,
+                cx: &mut std::task::Context<'_>,
+                buf: &[u8],
+            ) -> std::task::Poll<io::Result<usize>> {
+                if self.interrupted.load(Ordering::SeqCst) {
+                    let mut pipe = self.inner.outgoing.lock().unwrap();
+                    pipe.closed = true;
+                    if let Some(reader) = pipe.reader.take() {
+                        reader.wake();
+                    }
+                    return std::task::Poll::Ready(Err(io::ErrorKind::Interrupted.into()));
+                }
+                if buf.len() > 1 && self.armed.swap(false, Ordering::SeqCst) {
+                    self.interrupted.store(true, Ordering::SeqCst);
+                    let half = buf.len() / 2;
+                    return std::pin::Pin::new(&mut self.inner).poll_write(cx, &buf[..half]);
+                }
+                std::pin::Pin::new(&mut self.inner).poll_write(cx, buf)
+            }
+
+            fn poll_flush(
+                mut self: std::pin::Pin<&mut Self>,
+                cx: &mut std::task::Context<'_>,
+            ) -> std::task::Poll<io::Result<()>> {
+                std::pin::Pin::new(&mut self.inner).poll_flush(cx)
+            }
+
+            fn poll_close(
+                mut self: std::pin::Pin<&mut Self>,
+                cx: &mut std::task::Context<'_>,
+            ) -> std::task::Poll<io::Result<()>> {
+                std::pin::Pin::new(&mut self.inner).poll_close(cx)
+            }
+        }
+
+        impl Transport for InterruptingTransport {
+            fn clone_transport(&self) -> Box<dyn Transport> {
+                Box::new(self.clone())
+            }
+        }
+
+        init_logger();
+        let (client, server) = MemoryTransport::pair();
+        let handle = thread::spawn(move || {
+            smol::block_on(async move {
+                let mut websocket = async_tungstenite::accept_async(server)
+                    .await
+                    .expect("failed to accept");
+                let mut requests = vec![];
+                while let Some(Ok(message)) = websocket.next().await {
+                    if let Ok(request) = serde_json::from_str::<Value>(&message.to_string()) {
+                        requests.push(request);
+                    }
+                }
+                requests
+            })
+        });
+        let armed = sync::Arc::new(AtomicBool::new(false));
+        let client = InterruptingTransport {
+            inner: client,
+            armed: armed.clone(),
+            interrupted: sync::Arc::new(AtomicBool::new(false)),
+        };
+        let obs = smol::block_on(Obs::connect_with_transport(
+            client,
+            "ws://localhost:4444",
+            ObsConfig::default(),
+        ))
+        .expect("failed to connect")
+        .0;
+        armed.store(true, Ordering::SeqCst);
+        let failed = smol::block_on(obs.request(&GetStats::builder().build()));
+        let requests = handle.join().expect("join");
+        let _ = smol::block_on(obs.disconnect());
+
+        assert!(matches!(failed, Err(ObsError::ConnectionLost)));
+        // the partial frame is not followed by the rest of it or a second copy of the message
+        assert!(requests.is_empty());
+    }
+
     #[test]
     fn obs_crash_after_accept() {
         init_logger();
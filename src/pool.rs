@@ -0,0 +1,76 @@
+//! Multiple connections to OBS, see `ObsPool`.
+
+use crate::{error::ObsError, events::Event, obs::Obs, requests::Request};
+
+use futures::stream::{self, Stream, StreamExt};
+use std::collections::HashMap;
+
+/// Holds multiple connections to OBS identified by labels, e.g. for controlling a main and a backup instance.
+#[derive(Default)]
+pub struct ObsPool {
+    connections: HashMap<String, Obs>,
+}
+
+impl ObsPool {
+    /// Creates an empty pool.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a connection with the given label. Returns the connection that previously had the label, if any.
+    pub fn insert(&mut self, label: &str, obs: Obs) -> Option<Obs> {
+        self.connections.insert(label.to_string(), obs)
+    }
+
+    /// Removes the connection with the given label from the pool without disconnecting it.
+    pub fn remove(&mut self, label: &str) -> Option<Obs> {
+        self.connections.remove(label)
+    }
+
+    /// Returns the connection with the given label.
+    pub fn get(&self, label: &str) -> Option<&Obs> {
+        self.connections.get(label)
+    }
+
+    /// Returns the labels of the connections in the pool, in no particular order.
+    pub fn labels(&self) -> impl Iterator<Item = &str> {
+        self.connections.keys().map(String::as_str)
+    }
+
+    /// Sends a request to the connection with the given label.
+    pub async fn request<T>(&self, label: &str, req: &T) -> Result<T::Response, ObsError>
+    where
+        T: Request + std::fmt::Debug,
+    {
+        let obs = self
+            .get(label)
+            .ok_or_else(|| ObsError::UnknownConnection(label.to_string()))?;
+        obs.request(req).await
+    }
+
+    /// Returns a stream of the events from all the connections currently in the pool, tagged with their labels.
+    /// Connections added to the pool afterwards are not included.
+    pub fn events(&self) -> impl Stream<Item = (String, Event)> + Send + Unpin {
+        stream::select_all(self.connections.iter().map(|(label, obs)| {
+            let label = label.clone();
+            obs.subscribe()
+                .map(move |event| (label.clone(), event))
+                .boxed()
+        }))
+    }
+
+    /// Disconnects all the connections in the pool.
+    /// Every connection is disconnected even if some fail, after which the first error is returned.
+    pub async fn disconnect(self) -> Result<(), ObsError> {
+        let mut result = Ok(());
+        for (label, obs) in self.connections {
+            if let Err(e) = obs.disconnect().await {
+                log::warn!("Failed to disconnect {}: {}", label, e);
+                if result.is_ok() {
+                    result = Err(e);
+                }
+            }
+        }
+        result
+    }
+}
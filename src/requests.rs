@@ -1,15 +1,19 @@
 //! Request types. Sent to the server using the Obs struct.
 //!
 //! The request types will generate a running message-id by default, but they also support defining custom message-ids.
-//! When using custom message-ids, avoid reusing them and if also using default message-ids, avoid using custom ones in the form `_{integer}` to avoid clashing which may cause responses to be parsed incorrectly.
+//! When using custom message-ids, avoid reusing them and if also using default message-ids, avoid using custom ones in the form `{prefix}{integer}`, where the prefix is `ObsConfig::message_id_prefix` (`_` by default), to avoid clashing which may cause responses to be parsed incorrectly.
 //!
 //! To find the response type of a given request, see the impl Request for the type in its docs.
 
-use crate::{common_types::*, responses};
+use crate::{common_types::*, error::ObsError, responses};
 
 use serde::{de::DeserializeOwned, Serialize};
 use serde_json::{json, Value};
-use std::sync::atomic::{AtomicU32, Ordering};
+use std::{
+    convert::TryFrom,
+    sync::atomic::{AtomicU32, Ordering},
+    time::Duration,
+};
 use typed_builder::TypedBuilder;
 
 static RUNNING_MESSAGE_ID: AtomicU32 = AtomicU32::new(0);
@@ -23,13 +27,17 @@ pub trait Request {
     type Response: DeserializeOwned;
 
     // converts the struct into a JSON value
-    // returns the generated message id and the JSON
-    fn to_json(&self) -> (String, Value);
+    // returns the message id, generated with the given prefix, and the JSON
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value);
 }
 
-// creates a default value for message-id, using a running id
-fn make_message_id() -> String {
-    format!("_{}", RUNNING_MESSAGE_ID.fetch_add(1, Ordering::Relaxed))
+// creates a default value for message-id, using the prefix and a running id
+fn make_message_id(message_id_prefix: &str) -> String {
+    format!(
+        "{}{}",
+        message_id_prefix,
+        RUNNING_MESSAGE_ID.fetch_add(1, Ordering::Relaxed)
+    )
 }
 
 /// Returns the latest version of the plugin and the API.
@@ -40,8 +48,8 @@ impl Request for GetVersion {
     const REQUEST_TYPE: &'static str = "GetVersion";
     type Response = responses::GetVersion;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -60,8 +68,8 @@ impl Request for GetAuthRequired {
     const REQUEST_TYPE: &'static str = "GetAuthRequired";
     type Response = responses::GetAuthRequired;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -84,8 +92,8 @@ impl Request for Authenticate {
     const REQUEST_TYPE: &'static str = "Authenticate";
     type Response = responses::Empty;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -108,8 +116,8 @@ impl Request for SetHeartbeat {
     const REQUEST_TYPE: &'static str = "SetHeartbeat";
     type Response = responses::Empty;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -133,8 +141,8 @@ impl Request for SetFilenameFormatting {
     const REQUEST_TYPE: &'static str = "SetFilenameFormatting";
     type Response = responses::Empty;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -154,8 +162,8 @@ impl Request for GetFilenameFormatting {
     const REQUEST_TYPE: &'static str = "GetFilenameFormatting";
     type Response = responses::GetFilenameFormatting;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -174,8 +182,8 @@ impl Request for GetStats {
     const REQUEST_TYPE: &'static str = "GetStats";
     type Response = responses::GetStats;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -200,8 +208,8 @@ impl Request for BroadcastCustomMessage {
     const REQUEST_TYPE: &'static str = "BroadcastCustomMessage";
     type Response = responses::Empty;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -222,8 +230,8 @@ impl Request for GetVideoInfo {
     const REQUEST_TYPE: &'static str = "GetVideoInfo";
     type Response = responses::GetVideoInfo;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -234,6 +242,316 @@ impl Request for GetVideoInfo {
     }
 }
 
+/// The kind of projector opened with `OpenProjector`, i.e. what it displays.
+/// The projector is opened on `OpenProjector::monitor` if it's set, otherwise it's sent as `-1`,
+/// which makes OBS open a windowed projector.
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub enum ProjectorType {
+    /// Opens a projector of the preview, which is the program output unless Studio Mode is enabled.
+    Preview,
+    /// Opens a projector of the source given with `OpenProjector::name`.
+    Source,
+    /// Opens a projector of the scene given with `OpenProjector::name`.
+    Scene,
+    /// Opens a projector of the program output in Studio Mode.
+    StudioProgram,
+    /// Opens a multiview projector, which shows the preview, the program and a grid of the scenes.
+    Multiview,
+}
+
+/// Open a projector window or create a projector on a monitor. Requires OBS v24.0.4 or newer.
+#[derive(TypedBuilder, Debug, PartialEq, Eq)]
+pub struct OpenProjector {
+    /// Type of projector.
+    pub projector_type: ProjectorType,
+    /// Monitor to open the projector on. If not set, `-1` is sent and a windowed projector is opened.
+    #[builder(default, setter(strip_option))]
+    pub monitor: Option<u32>,
+    /// Size and position of the projector window (only if windowed). Encoded in Base64 using Qt's geometry encoding.
+    #[builder(default, setter(strip_option, into))]
+    pub geometry: Option<String>,
+    /// Name of the source or scene to be displayed (ignored for other projector types).
+    #[builder(default, setter(strip_option, into))]
+    pub name: Option<String>,
+}
+
+impl OpenProjector {
+    /// Opens a windowed projector.
+    pub fn windowed(projector_type: ProjectorType, name: Option<&str>) -> Self {
+        Self {
+            projector_type,
+            monitor: None,
+            geometry: None,
+            name: name.map(str::to_string),
+        }
+    }
+
+    /// Opens a fullscreen projector on the given monitor.
+    pub fn fullscreen(projector_type: ProjectorType, monitor: u32, name: Option<&str>) -> Self {
+        Self {
+            projector_type,
+            monitor: Some(monitor),
+            geometry: None,
+            name: name.map(str::to_string),
+        }
+    }
+}
+
+impl Request for OpenProjector {
+    const REQUEST_TYPE: &'static str = "OpenProjector";
+    type Response = responses::Empty;
+
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
+        // OBS uses -1 for windowed projectors
+        let monitor = self.monitor.map(i64::from).unwrap_or(-1);
+        (
+            message_id.clone(),
+            json!({
+                "request-type": Self::REQUEST_TYPE,
+                "message-id": message_id,
+                "type": self.projector_type,
+                "monitor": monitor,
+                "geometry": self.geometry,
+                "name": self.name,
+            }),
+        )
+    }
+}
+
+/// Modifier keys held down with a key, see `TriggerHotkeyBySequence`.
+#[derive(TypedBuilder, Serialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct KeyModifiers {
+    /// Trigger Shift Key
+    #[builder(default)]
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub shift: bool,
+    /// Trigger Alt Key
+    #[builder(default)]
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub alt: bool,
+    /// Trigger Control (Ctrl) Key
+    #[builder(default)]
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub control: bool,
+    /// Trigger Command Key (Mac)
+    #[builder(default)]
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub command: bool,
+}
+
+/// Executes hotkey routine, identified by bound combination of keys. A single key combination might trigger multiple hotkey routines depending on user settings. Requires obs-websocket 4.9.1.
+#[derive(TypedBuilder, Debug, PartialEq, Eq)]
+pub struct TriggerHotkeyBySequence {
+    /// Main key identifier (e.g. `OBS_KEY_A` for key "A"). Available identifiers are listed in OBS's libobs/obs-hotkeys.h.
+    #[builder(setter(into))]
+    pub key_id: String,
+    /// Optional key modifiers object. False entries can be omitted.
+    #[builder(default, setter(strip_option))]
+    pub key_modifiers: Option<KeyModifiers>,
+}
+
+impl Request for TriggerHotkeyBySequence {
+    const REQUEST_TYPE: &'static str = "TriggerHotkeyBySequence";
+    type Response = responses::Empty;
+
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
+        let mut value = json!({
+            "request-type": Self::REQUEST_TYPE,
+            "message-id": message_id,
+            "keyId": self.key_id,
+        });
+        // the modifiers are omitted instead of sent as null
+        if let Some(key_modifiers) = &self.key_modifiers {
+            value["keyModifiers"] = json!(key_modifiers);
+        }
+        (message_id, value)
+    }
+}
+
+/// Skip to the next media item in the playlist. Supports only vlc media source (as of OBS v25.0.8).
+#[derive(TypedBuilder, Debug, PartialEq, Eq)]
+pub struct NextMedia {
+    /// Source name.
+    #[builder(setter(into))]
+    pub source_name: String,
+}
+
+impl Request for NextMedia {
+    const REQUEST_TYPE: &'static str = "NextMedia";
+    type Response = responses::Empty;
+
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
+        (
+            message_id.clone(),
+            json!({
+                "request-type": Self::REQUEST_TYPE,
+                "message-id": message_id,
+                "sourceName": self.source_name,
+            }),
+        )
+    }
+}
+
+/// Go to the previous media item in the playlist. Supports only vlc media source (as of OBS v25.0.8).
+#[derive(TypedBuilder, Debug, PartialEq, Eq)]
+pub struct PreviousMedia {
+    /// Source name.
+    #[builder(setter(into))]
+    pub source_name: String,
+}
+
+impl Request for PreviousMedia {
+    const REQUEST_TYPE: &'static str = "PreviousMedia";
+    type Response = responses::Empty;
+
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
+        (
+            message_id.clone(),
+            json!({
+                "request-type": Self::REQUEST_TYPE,
+                "message-id": message_id,
+                "sourceName": self.source_name,
+            }),
+        )
+    }
+}
+
+/// Get the length of media in milliseconds. Supports ffmpeg and vlc media sources (as of OBS v25.0.8).
+#[derive(TypedBuilder, Debug, PartialEq, Eq)]
+pub struct GetMediaDuration {
+    /// Source name.
+    #[builder(setter(into))]
+    pub source_name: String,
+}
+
+impl Request for GetMediaDuration {
+    const REQUEST_TYPE: &'static str = "GetMediaDuration";
+    type Response = responses::GetMediaDuration;
+
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
+        (
+            message_id.clone(),
+            json!({
+                "request-type": Self::REQUEST_TYPE,
+                "message-id": message_id,
+                "sourceName": self.source_name,
+            }),
+        )
+    }
+}
+
+/// Get the current timestamp of media in milliseconds. Supports ffmpeg and vlc media sources (as of OBS v25.0.8).
+#[derive(TypedBuilder, Debug, PartialEq, Eq)]
+pub struct GetMediaTime {
+    /// Source name.
+    #[builder(setter(into))]
+    pub source_name: String,
+}
+
+impl Request for GetMediaTime {
+    const REQUEST_TYPE: &'static str = "GetMediaTime";
+    type Response = responses::GetMediaTime;
+
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
+        (
+            message_id.clone(),
+            json!({
+                "request-type": Self::REQUEST_TYPE,
+                "message-id": message_id,
+                "sourceName": self.source_name,
+            }),
+        )
+    }
+}
+
+/// Get the current playing state of a media source. Supports ffmpeg and vlc media sources (as of OBS v25.0.8).
+#[derive(TypedBuilder, Debug, PartialEq, Eq)]
+pub struct GetMediaState {
+    /// Source name.
+    #[builder(setter(into))]
+    pub source_name: String,
+}
+
+impl Request for GetMediaState {
+    const REQUEST_TYPE: &'static str = "GetMediaState";
+    type Response = responses::GetMediaState;
+
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
+        (
+            message_id.clone(),
+            json!({
+                "request-type": Self::REQUEST_TYPE,
+                "message-id": message_id,
+                "sourceName": self.source_name,
+            }),
+        )
+    }
+}
+
+/// Set the timestamp of a media source. Supports ffmpeg and vlc media sources (as of OBS v25.0.8).
+#[derive(TypedBuilder, Debug, PartialEq, Eq)]
+pub struct SetMediaTime {
+    /// Source name.
+    #[builder(setter(into))]
+    pub source_name: String,
+    /// Milliseconds to set the timestamp to.
+    pub timestamp: i64,
+}
+
+impl Request for SetMediaTime {
+    const REQUEST_TYPE: &'static str = "SetMediaTime";
+    type Response = responses::Empty;
+
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
+        (
+            message_id.clone(),
+            json!({
+                "request-type": Self::REQUEST_TYPE,
+                "message-id": message_id,
+                "sourceName": self.source_name,
+                "timestamp": self.timestamp,
+            }),
+        )
+    }
+}
+
+/// Scrub media using a supplied offset. Supports ffmpeg and vlc media sources (as of OBS v25.0.8).
+/// Due to processing/network delays, this request is not perfect. The processing rate of this request has also not been tested.
+#[derive(TypedBuilder, Debug, PartialEq, Eq)]
+pub struct ScrubMedia {
+    /// Source name.
+    #[builder(setter(into))]
+    pub source_name: String,
+    /// Millisecond offset (positive or negative) to offset the current media position.
+    pub time_offset: i64,
+}
+
+impl Request for ScrubMedia {
+    const REQUEST_TYPE: &'static str = "ScrubMedia";
+    type Response = responses::Empty;
+
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
+        (
+            message_id.clone(),
+            json!({
+                "request-type": Self::REQUEST_TYPE,
+                "message-id": message_id,
+                "sourceName": self.source_name,
+                "timeOffset": self.time_offset,
+            }),
+        )
+    }
+}
+
 /// List existing outputs
 #[derive(TypedBuilder, Debug, PartialEq, Eq)]
 pub struct ListOutputs {}
@@ -242,8 +560,8 @@ impl Request for ListOutputs {
     const REQUEST_TYPE: &'static str = "ListOutputs";
     type Response = responses::ListOutputs;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -266,8 +584,8 @@ impl Request for GetOutputInfo {
     const REQUEST_TYPE: &'static str = "GetOutputInfo";
     type Response = responses::GetOutputInfo;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -291,8 +609,8 @@ impl Request for StartOutput {
     const REQUEST_TYPE: &'static str = "StartOutput";
     type Response = responses::Empty;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -319,8 +637,8 @@ impl Request for StopOutput {
     const REQUEST_TYPE: &'static str = "StopOutput";
     type Response = responses::Empty;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -345,8 +663,8 @@ impl Request for SetCurrentProfile {
     const REQUEST_TYPE: &'static str = "SetCurrentProfile";
     type Response = responses::Empty;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -366,8 +684,8 @@ impl Request for GetCurrentProfile {
     const REQUEST_TYPE: &'static str = "GetCurrentProfile";
     type Response = responses::GetCurrentProfile;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -386,8 +704,8 @@ impl Request for ListProfiles {
     const REQUEST_TYPE: &'static str = "ListProfiles";
     type Response = responses::ListProfiles;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -406,8 +724,8 @@ impl Request for StartStopRecording {
     const REQUEST_TYPE: &'static str = "StartStopRecording";
     type Response = responses::Empty;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -426,8 +744,8 @@ impl Request for StartRecording {
     const REQUEST_TYPE: &'static str = "StartRecording";
     type Response = responses::Empty;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -446,8 +764,8 @@ impl Request for StopRecording {
     const REQUEST_TYPE: &'static str = "StopRecording";
     type Response = responses::Empty;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -466,8 +784,8 @@ impl Request for PauseRecording {
     const REQUEST_TYPE: &'static str = "PauseRecording";
     type Response = responses::Empty;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -486,8 +804,8 @@ impl Request for ResumeRecording {
     const REQUEST_TYPE: &'static str = "ResumeRecording";
     type Response = responses::Empty;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -510,8 +828,8 @@ impl Request for SetRecordingFolder {
     const REQUEST_TYPE: &'static str = "SetRecordingFolder";
     type Response = responses::Empty;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -531,8 +849,8 @@ impl Request for GetRecordingFolder {
     const REQUEST_TYPE: &'static str = "GetRecordingFolder";
     type Response = responses::GetRecordingFolder;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -551,8 +869,8 @@ impl Request for StartStopReplayBuffer {
     const REQUEST_TYPE: &'static str = "StartStopReplayBuffer";
     type Response = responses::Empty;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -571,8 +889,8 @@ impl Request for StartReplayBuffer {
     const REQUEST_TYPE: &'static str = "StartReplayBuffer";
     type Response = responses::Empty;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -591,8 +909,8 @@ impl Request for StopReplayBuffer {
     const REQUEST_TYPE: &'static str = "StopReplayBuffer";
     type Response = responses::Empty;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -611,8 +929,8 @@ impl Request for SaveReplayBuffer {
     const REQUEST_TYPE: &'static str = "SaveReplayBuffer";
     type Response = responses::Empty;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -635,8 +953,8 @@ impl Request for SetCurrentSceneCollection {
     const REQUEST_TYPE: &'static str = "SetCurrentSceneCollection";
     type Response = responses::Empty;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -656,8 +974,8 @@ impl Request for GetCurrentSceneCollection {
     const REQUEST_TYPE: &'static str = "GetCurrentSceneCollection";
     type Response = responses::GetCurrentSceneCollection;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -676,13 +994,38 @@ impl Request for ListSceneCollections {
     const REQUEST_TYPE: &'static str = "ListSceneCollections";
     type Response = responses::ListSceneCollections;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
+        (
+            message_id.clone(),
+            json!({
+                "request-type": Self::REQUEST_TYPE,
+                "message-id": message_id,
+            }),
+        )
+    }
+}
+
+/// Get a list of all scene items in a scene, including the items in groups.
+#[derive(TypedBuilder, Debug, PartialEq, Eq)]
+pub struct GetSceneItemList {
+    /// Name of the scene to get the list of scene items from. Defaults to the current scene.
+    #[builder(default, setter(strip_option, into))]
+    pub scene_name: Option<String>,
+}
+
+impl Request for GetSceneItemList {
+    const REQUEST_TYPE: &'static str = "GetSceneItemList";
+    type Response = responses::GetSceneItemList;
+
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
                 "request-type": Self::REQUEST_TYPE,
                 "message-id": message_id,
+                "sceneName": self.scene_name,
             }),
         )
     }
@@ -703,8 +1046,8 @@ impl Request for GetSceneItemProperties {
     const REQUEST_TYPE: &'static str = "GetSceneItemProperties";
     type Response = responses::GetSceneItemProperties;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -780,8 +1123,8 @@ impl Request for SetSceneItemProperties {
     const REQUEST_TYPE: &'static str = "SetSceneItemProperties";
     type Response = responses::Empty;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -833,8 +1176,8 @@ impl Request for ResetSceneItem {
     const REQUEST_TYPE: &'static str = "ResetSceneItem";
     type Response = responses::Empty;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -862,10 +1205,10 @@ impl Request for DeleteSceneItem {
     const REQUEST_TYPE: &'static str = "DeleteSceneItem";
     type Response = responses::Empty;
 
-    fn to_json(&self) -> (String, Value) {
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
         let item_id = self.item_id.as_ref().and_then(ItemId::to_id);
         let item_name = self.item_id.as_ref().and_then(ItemId::to_name);
-        let message_id = make_message_id();
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -899,10 +1242,10 @@ impl Request for DuplicateSceneItem {
     const REQUEST_TYPE: &'static str = "DuplicateSceneItem";
     type Response = responses::DuplicateSceneItem;
 
-    fn to_json(&self) -> (String, Value) {
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
         let item_name = self.item_id.as_ref().and_then(ItemId::to_name);
         let item_id = self.item_id.as_ref().and_then(ItemId::to_id);
-        let message_id = make_message_id();
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -931,8 +1274,8 @@ impl Request for SetCurrentScene {
     const REQUEST_TYPE: &'static str = "SetCurrentScene";
     type Response = responses::Empty;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -952,8 +1295,8 @@ impl Request for GetCurrentScene {
     const REQUEST_TYPE: &'static str = "GetCurrentScene";
     type Response = responses::GetCurrentScene;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -972,8 +1315,8 @@ impl Request for GetSceneList {
     const REQUEST_TYPE: &'static str = "GetSceneList";
     type Response = responses::GetSceneList;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -999,7 +1342,7 @@ impl Request for ReorderSceneItems {
     const REQUEST_TYPE: &'static str = "ReorderSceneItems";
     type Response = responses::Empty;
 
-    fn to_json(&self) -> (String, Value) {
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
         let items = self
             .items
             .as_ref()
@@ -1014,7 +1357,7 @@ impl Request for ReorderSceneItems {
                 }),
             })
             .collect::<Vec<_>>();
-        let message_id = make_message_id();
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -1035,8 +1378,8 @@ impl Request for GetSourcesList {
     const REQUEST_TYPE: &'static str = "GetSourcesList";
     type Response = responses::GetSourcesList;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -1055,8 +1398,8 @@ impl Request for GetSourceTypesList {
     const REQUEST_TYPE: &'static str = "GetSourceTypesList";
     type Response = responses::GetSourceTypesList;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -1079,8 +1422,8 @@ impl Request for GetVolume {
     const REQUEST_TYPE: &'static str = "GetVolume";
     type Response = responses::GetVolume;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -1106,8 +1449,8 @@ impl Request for SetVolume {
     const REQUEST_TYPE: &'static str = "SetVolume";
     type Response = responses::Empty;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -1132,8 +1475,8 @@ impl Request for GetMute {
     const REQUEST_TYPE: &'static str = "GetMute";
     type Response = responses::GetMute;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -1159,8 +1502,8 @@ impl Request for SetMute {
     const REQUEST_TYPE: &'static str = "SetMute";
     type Response = responses::Empty;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -1173,6 +1516,129 @@ impl Request for SetMute {
     }
 }
 
+/// Sets whether a source is routed to an audio track. Requires obs-websocket 4.9.1.
+#[derive(TypedBuilder, Debug, PartialEq, Eq)]
+pub struct SetTracks {
+    /// Source name.
+    #[builder(setter(into))]
+    pub source_name: String,
+    /// Audio track, from 1 to 6.
+    pub track: i32,
+    /// Whether the source is routed to the track.
+    pub active: bool,
+}
+
+impl Request for SetTracks {
+    const REQUEST_TYPE: &'static str = "SetTracks";
+    type Response = responses::Empty;
+
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
+        (
+            message_id.clone(),
+            json!({
+                "request-type": Self::REQUEST_TYPE,
+                "message-id": message_id,
+                "sourceName": self.source_name,
+                "track": self.track,
+                "active": self.active,
+            }),
+        )
+    }
+}
+
+/// Get the audio monitoring type of the specified source.
+#[derive(TypedBuilder, Debug, PartialEq, Eq)]
+pub struct GetAudioMonitorType {
+    /// Source name.
+    #[builder(setter(into))]
+    pub source_name: String,
+}
+
+impl Request for GetAudioMonitorType {
+    const REQUEST_TYPE: &'static str = "GetAudioMonitorType";
+    type Response = responses::GetAudioMonitorType;
+
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
+        (
+            message_id.clone(),
+            json!({
+                "request-type": Self::REQUEST_TYPE,
+                "message-id": message_id,
+                "sourceName": self.source_name,
+            }),
+        )
+    }
+}
+
+/// Set the audio monitoring type of the specified source.
+#[derive(TypedBuilder, Debug, PartialEq, Eq)]
+pub struct SetAudioMonitorType {
+    /// Source name.
+    #[builder(setter(into))]
+    pub source_name: String,
+    /// The monitor type to use.
+    pub monitor_type: MonitorType,
+}
+
+impl Request for SetAudioMonitorType {
+    const REQUEST_TYPE: &'static str = "SetAudioMonitorType";
+    type Response = responses::Empty;
+
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
+        (
+            message_id.clone(),
+            json!({
+                "request-type": Self::REQUEST_TYPE,
+                "message-id": message_id,
+                "sourceName": self.source_name,
+                "monitorType": self.monitor_type,
+            }),
+        )
+    }
+}
+
+/// The audio mixer routing of a source, set with `Obs::set_source_mixers` using one `SetTracks` request per mixer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SetSourceMixers {
+    /// Source name.
+    pub source_name: String,
+    /// Whether the source is routed to each of the six mixers.
+    pub mixers: [bool; 6],
+}
+
+impl SetSourceMixers {
+    /// Creates the routing from raw mixer flags like `hex_mixers_value` in `EventType::SourceAudioMixersChanged`,
+    /// one bit per mixer starting from the least significant bit.
+    pub fn from_hex_mixers_value(source_name: &str, hex_mixers_value: u8) -> Self {
+        let mut mixers = [false; 6];
+        for (i, mixer) in mixers.iter_mut().enumerate() {
+            *mixer = hex_mixers_value & (1 << i) != 0;
+        }
+        Self {
+            source_name: source_name.to_string(),
+            mixers,
+        }
+    }
+
+    /// The `SetTracks` requests for the routing, one for each mixer.
+    pub fn requests(&self) -> Vec<SetTracks> {
+        self.mixers
+            .iter()
+            .zip(1..)
+            .map(|(active, track)| {
+                SetTracks::builder()
+                    .source_name(self.source_name.as_str())
+                    .track(track)
+                    .active(*active)
+                    .build()
+            })
+            .collect()
+    }
+}
+
 /// Inverts the mute status of a specified source.
 #[derive(TypedBuilder, Debug, PartialEq, Eq)]
 pub struct ToggleMute {
@@ -1185,8 +1651,8 @@ impl Request for ToggleMute {
     const REQUEST_TYPE: &'static str = "ToggleMute";
     type Response = responses::Empty;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -1212,8 +1678,8 @@ impl Request for SetSyncOffset {
     const REQUEST_TYPE: &'static str = "SetSyncOffset";
     type Response = responses::Empty;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -1238,8 +1704,8 @@ impl Request for GetSyncOffset {
     const REQUEST_TYPE: &'static str = "GetSyncOffset";
     type Response = responses::GetSyncOffset;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -1266,8 +1732,8 @@ impl Request for GetSourceSettings {
     const REQUEST_TYPE: &'static str = "GetSourceSettings";
     type Response = responses::GetSourceSettings;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -1294,12 +1760,27 @@ pub struct SetSourceSettings {
     pub source_settings: Value,
 }
 
+impl SetSourceSettings {
+    /// Serializes the given typed settings, e.g. `BrowserSourceSettings`, into the source settings.
+    pub fn typed<T: Serialize>(
+        source_name: &str,
+        source_type: Option<SourceKind>,
+        settings: &T,
+    ) -> Result<Self, serde_json::Error> {
+        Ok(Self {
+            source_name: source_name.to_string(),
+            source_type,
+            source_settings: serde_json::to_value(settings)?,
+        })
+    }
+}
+
 impl Request for SetSourceSettings {
     const REQUEST_TYPE: &'static str = "SetSourceSettings";
     type Response = responses::SetSourceSettings;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -1325,8 +1806,8 @@ impl Request for GetTextGDIPlusProperties {
     const REQUEST_TYPE: &'static str = "GetTextGDIPlusProperties";
     type Response = responses::GetTextGDIPlusProperties;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -1439,8 +1920,8 @@ impl Request for SetTextGDIPlusProperties {
     const REQUEST_TYPE: &'static str = "SetTextGDIPlusProperties";
     type Response = responses::Empty;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -1493,8 +1974,8 @@ impl Request for GetTextFreetype2Properties {
     const REQUEST_TYPE: &'static str = "GetTextFreetype2Properties";
     type Response = responses::GetTextFreetype2Properties;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -1560,8 +2041,8 @@ impl Request for SetTextFreetype2Properties {
     const REQUEST_TYPE: &'static str = "SetTextFreetype2Properties";
     type Response = responses::Empty;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -1601,8 +2082,8 @@ impl Request for GetBrowserSourceProperties {
     const REQUEST_TYPE: &'static str = "GetBrowserSourceProperties";
     type Response = responses::GetBrowserSourceProperties;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -1649,12 +2130,29 @@ pub struct SetBrowserSourceProperties {
     pub render: Option<bool>,
 }
 
+impl SetBrowserSourceProperties {
+    /// Checks that the URL is valid unless a local file is used. OBS accepts any URL, but an invalid one
+    /// results in a blank browser source.
+    /// Returns `ObsError::InvalidUrl` if the URL is invalid.
+    pub fn checked(self) -> Result<Self, ObsError> {
+        if let (Some(url), false) = (&self.url, self.is_local_file.unwrap_or(false)) {
+            if let Err(source) = url::Url::parse(url) {
+                return Err(ObsError::InvalidUrl {
+                    url: url.clone(),
+                    source,
+                });
+            }
+        }
+        Ok(self)
+    }
+}
+
 impl Request for SetBrowserSourceProperties {
     const REQUEST_TYPE: &'static str = "SetBrowserSourceProperties";
     type Response = responses::Empty;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -1683,8 +2181,8 @@ impl Request for GetSpecialSources {
     const REQUEST_TYPE: &'static str = "GetSpecialSources";
     type Response = responses::GetSpecialSources;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -1707,8 +2205,8 @@ impl Request for GetSourceFilters {
     const REQUEST_TYPE: &'static str = "GetSourceFilters";
     type Response = responses::GetSourceFilters;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -1735,8 +2233,8 @@ impl Request for GetSourceFilterInfo {
     const REQUEST_TYPE: &'static str = "GetSourceFilterInfo";
     type Response = responses::GetSourceFilterInfo;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -1770,8 +2268,8 @@ impl Request for AddFilterToSource {
     const REQUEST_TYPE: &'static str = "AddFilterToSource";
     type Response = responses::Empty;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -1801,8 +2299,8 @@ impl Request for RemoveFilterFromSource {
     const REQUEST_TYPE: &'static str = "RemoveFilterFromSource";
     type Response = responses::Empty;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -1832,8 +2330,8 @@ impl Request for ReorderSourceFilter {
     const REQUEST_TYPE: &'static str = "ReorderSourceFilter";
     type Response = responses::Empty;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -1873,8 +2371,8 @@ impl Request for MoveSourceFilter {
     const REQUEST_TYPE: &'static str = "MoveSourceFilter";
     type Response = responses::Empty;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -1906,8 +2404,8 @@ impl Request for SetSourceFilterSettings {
     const REQUEST_TYPE: &'static str = "SetSourceFilterSettings";
     type Response = responses::Empty;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -1938,8 +2436,8 @@ impl Request for SetSourceFilterVisibility {
     const REQUEST_TYPE: &'static str = "SetSourceFilterVisibility";
     type Response = responses::Empty;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -1993,8 +2491,8 @@ impl Request for TakeSourceScreenshot {
     const REQUEST_TYPE: &'static str = "TakeSourceScreenshot";
     type Response = responses::TakeSourceScreenshot;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -2018,8 +2516,8 @@ impl Request for GetStreamingStatus {
     const REQUEST_TYPE: &'static str = "GetStreamingStatus";
     type Response = responses::GetStreamingStatus;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -2038,8 +2536,8 @@ impl Request for StartStopStreaming {
     const REQUEST_TYPE: &'static str = "StartStopStreaming";
     type Response = responses::Empty;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -2080,8 +2578,8 @@ impl Request for StartStreaming {
     const REQUEST_TYPE: &'static str = "StartStreaming";
     type Response = responses::Empty;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -2111,8 +2609,8 @@ impl Request for StopStreaming {
     const REQUEST_TYPE: &'static str = "StopStreaming";
     type Response = responses::Empty;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -2136,8 +2634,8 @@ pub struct SetStreamSettings {
     #[builder(default, setter(strip_option, into))]
     pub key: Option<String>,
     /// Indicates whether authentication should be used when connecting to the streaming server.
-    #[builder(default, setter(strip_option, into))]
-    pub use_auth: Option<String>,
+    #[builder(default, setter(strip_option))]
+    pub use_auth: Option<bool>,
     /// The username for the streaming service.
     #[builder(default, setter(strip_option, into))]
     pub username: Option<String>,
@@ -2152,8 +2650,8 @@ impl Request for SetStreamSettings {
     const REQUEST_TYPE: &'static str = "SetStreamSettings";
     type Response = responses::Empty;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -2181,8 +2679,8 @@ impl Request for GetStreamSettings {
     const REQUEST_TYPE: &'static str = "GetStreamSettings";
     type Response = responses::GetStreamSettings;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -2201,8 +2699,8 @@ impl Request for SaveStreamSettings {
     const REQUEST_TYPE: &'static str = "SaveStreamSettings";
     type Response = responses::Empty;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -2225,8 +2723,8 @@ impl Request for SendCaptions {
     const REQUEST_TYPE: &'static str = "SendCaptions";
     type Response = responses::Empty;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -2246,8 +2744,8 @@ impl Request for GetStudioModeStatus {
     const REQUEST_TYPE: &'static str = "GetStudioModeStatus";
     type Response = responses::GetStudioModeStatus;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -2266,8 +2764,8 @@ impl Request for GetPreviewScene {
     const REQUEST_TYPE: &'static str = "GetPreviewScene";
     type Response = responses::GetPreviewScene;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -2290,8 +2788,8 @@ impl Request for SetPreviewScene {
     const REQUEST_TYPE: &'static str = "SetPreviewScene";
     type Response = responses::Empty;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -2318,8 +2816,8 @@ impl Request for TransitionToProgram {
     const REQUEST_TYPE: &'static str = "TransitionToProgram";
     type Response = responses::Empty;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -2342,8 +2840,8 @@ impl Request for EnableStudioMode {
     const REQUEST_TYPE: &'static str = "EnableStudioMode";
     type Response = responses::Empty;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -2362,8 +2860,8 @@ impl Request for DisableStudioMode {
     const REQUEST_TYPE: &'static str = "DisableStudioMode";
     type Response = responses::Empty;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -2382,8 +2880,8 @@ impl Request for ToggleStudioMode {
     const REQUEST_TYPE: &'static str = "ToggleStudioMode";
     type Response = responses::Empty;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -2402,8 +2900,8 @@ impl Request for GetTransitionList {
     const REQUEST_TYPE: &'static str = "GetTransitionList";
     type Response = responses::GetTransitionList;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -2422,8 +2920,8 @@ impl Request for GetCurrentTransition {
     const REQUEST_TYPE: &'static str = "GetCurrentTransition";
     type Response = responses::GetCurrentTransition;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -2446,8 +2944,8 @@ impl Request for SetCurrentTransition {
     const REQUEST_TYPE: &'static str = "SetCurrentTransition";
     type Response = responses::Empty;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -2466,12 +2964,21 @@ pub struct SetTransitionDuration {
     pub duration: i32,
 }
 
+impl SetTransitionDuration {
+    /// Creates the request from a `Duration`, saturating at `i32::MAX` milliseconds.
+    pub fn from_duration(duration: Duration) -> Self {
+        Self {
+            duration: i32::try_from(duration.as_millis()).unwrap_or(i32::MAX),
+        }
+    }
+}
+
 impl Request for SetTransitionDuration {
     const REQUEST_TYPE: &'static str = "SetTransitionDuration";
     type Response = responses::Empty;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
         (
             message_id.clone(),
             json!({
@@ -2491,13 +2998,65 @@ impl Request for GetTransitionDuration {
     const REQUEST_TYPE: &'static str = "GetTransitionDuration";
     type Response = responses::GetTransitionDuration;
 
-    fn to_json(&self) -> (String, Value) {
-        let message_id = make_message_id();
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
+        (
+            message_id.clone(),
+            json!({
+                "request-type": Self::REQUEST_TYPE,
+                "message-id": message_id,
+            }),
+        )
+    }
+}
+
+/// Executes a list of requests sequentially. Requires obs-websocket 4.9.
+/// The results of the individual requests are returned in order, see `responses::ExecuteBatch::into_results`.
+/// When the batch is sent, the requests in it get the same treatment as individual requests: the ones without a message-id
+/// get one with `ObsConfig::message_id_prefix`, and `ObsConfig::scene_name_mapper` and `ObsConfig::interceptors` apply to them.
+#[derive(TypedBuilder, Debug, PartialEq)]
+pub struct ExecuteBatch {
+    /// The JSON of the requests to execute. Usually added with `push`.
+    #[builder(default)]
+    pub requests: Vec<Value>,
+    /// Stop processing the requests after one fails. Requires obs-websocket 4.9.1.
+    #[builder(default, setter(strip_option))]
+    pub abort_on_fail: Option<bool>,
+}
+
+impl ExecuteBatch {
+    /// Adds a request to the batch.
+    pub fn push<T: Request>(&mut self, req: &T) {
+        // the message-id is generated with the connection's prefix when the batch is sent
+        let (_message_id, mut value) = req.to_json("");
+        if let Value::Object(object) = &mut value {
+            object.remove("message-id");
+        }
+        self.requests.push(value);
+    }
+}
+
+impl Request for ExecuteBatch {
+    const REQUEST_TYPE: &'static str = "ExecuteBatch";
+    type Response = responses::ExecuteBatch;
+
+    fn to_json(&self, message_id_prefix: &str) -> (String, Value) {
+        let message_id = make_message_id(message_id_prefix);
+        let mut requests = self.requests.clone();
+        for request in &mut requests {
+            if let Value::Object(request) = request {
+                request
+                    .entry("message-id")
+                    .or_insert_with(|| Value::from(make_message_id(message_id_prefix)));
+            }
+        }
         (
             message_id.clone(),
             json!({
                 "request-type": Self::REQUEST_TYPE,
                 "message-id": message_id,
+                "requests": requests,
+                "abortOnFail": self.abort_on_fail,
             }),
         )
     }
@@ -1,10 +1,17 @@
 //! Response types. Received from the server in response to requests.
 //! For documentation on which response corresponds to which request, see the requests type.
 
-use crate::common_types::*;
+use crate::{common_types::*, error::ObsError, requests::Request};
 
-use serde::{de, Deserialize, Deserializer};
+use serde::{
+    de::{self, DeserializeOwned},
+    Deserialize, Deserializer, Serialize,
+};
 use serde_json::Value;
+use std::{collections::HashSet, convert::TryFrom, time::Duration};
+
+// how much of a response that failed to deserialize is included in the error
+const MAX_ERROR_RESPONSE_LENGTH: usize = 500;
 
 #[derive(Debug, Deserialize, PartialEq)]
 pub(crate) struct ResponseWrapper {
@@ -51,7 +58,45 @@ where
     d.deserialize_str(V {})
 }
 
-#[derive(Deserialize, Debug, PartialEq)]
+// deserializes the response to a request, including the response in the error if it fails
+// responses that are only missing a field, e.g. due to a server quirk, get a more specific error
+pub(crate) fn deserialize_response<T: Request>(
+    message_id: String,
+    response: &Value,
+) -> Result<T::Response, ObsError> {
+    T::Response::deserialize(response).map_err(|source| match missing_field(&source) {
+        Some(missing_field) => ObsError::MalformedResponse {
+            request_type: T::REQUEST_TYPE.to_string(),
+            message_id,
+            missing_field,
+        },
+        None => ObsError::InvalidResponse {
+            request_type: T::REQUEST_TYPE.to_string(),
+            message_id,
+            response: truncate(response.to_string(), MAX_ERROR_RESPONSE_LENGTH),
+            source,
+        },
+    })
+}
+
+// extracts the name of the missing field from a serde error like "missing field `volume`"
+fn missing_field(error: &serde_json::Error) -> Option<String> {
+    let message = error.to_string();
+    let field = message.strip_prefix("missing field `")?;
+    let end = field.find('`')?;
+    Some(field[..end].to_string())
+}
+
+// truncates the text to at most the given number of characters
+fn truncate(mut text: String, max_chars: usize) -> String {
+    if let Some((index, _)) = text.char_indices().nth(max_chars) {
+        text.truncate(index);
+        text.push_str("...");
+    }
+    text
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "kebab-case")]
 pub struct GetVersion {
     /// OBSRemote compatible API version. Fixed to 1.1 for retrocompatibility.
@@ -81,13 +126,13 @@ pub struct GetFilenameFormatting {
     pub filename_formatting: String,
 }
 
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
 pub struct GetStats {
     /// OBS stats
     pub stats: ObsStats,
 }
 
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct GetVideoInfo {
     /// Base (canvas) width
@@ -110,6 +155,26 @@ pub struct GetVideoInfo {
     pub color_range: ColorRange,
 }
 
+#[derive(Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct GetMediaDuration {
+    /// The total length of media in milliseconds.
+    pub media_duration: i64,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Eq)]
+pub struct GetMediaTime {
+    /// The time in milliseconds since the start of the media.
+    pub timestamp: i64,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct GetMediaState {
+    /// The media state of the provided source.
+    pub media_state: MediaState,
+}
+
 #[derive(Deserialize, Debug, PartialEq)]
 pub struct ListOutputs {
     /// Outputs list
@@ -157,7 +222,27 @@ pub struct ListSceneCollections {
     pub scene_collections: Vec<SceneCollection>,
 }
 
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct GetSceneItemList {
+    /// Name of the requested (or current) scene.
+    pub scene_name: String,
+    /// Array of scene items.
+    pub scene_items: Vec<SceneItemListItem>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SceneItemListItem {
+    /// Unique item id of the source item.
+    pub item_id: i32,
+    /// ID if the scene item's source. For example `vlc_source` or `image_source`.
+    pub source_kind: String,
+    /// Name of the scene item's source.
+    pub source_name: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct GetSceneItemProperties {
     /// The name of the source.
@@ -182,6 +267,66 @@ pub struct GetSceneItemProperties {
     pub height: f64,
 }
 
+impl GetSceneItemProperties {
+    /// The horizontal scale computed from the scaled and base width.
+    /// Falls back to the scale reported by OBS if the source has no width.
+    pub fn effective_scale_x(&self) -> f64 {
+        if self.source_width == 0 {
+            self.scale.x
+        } else {
+            self.width / f64::from(self.source_width)
+        }
+    }
+
+    /// The vertical scale computed from the scaled and base height.
+    /// Falls back to the scale reported by OBS if the source has no height.
+    pub fn effective_scale_y(&self) -> f64 {
+        if self.source_height == 0 {
+            self.scale.y
+        } else {
+            self.height / f64::from(self.source_height)
+        }
+    }
+
+    /// Whether or not the item is flipped horizontally or vertically, i.e. has a negative scale.
+    pub fn is_flipped(&self) -> bool {
+        self.scale.x < 0.0 || self.scale.y < 0.0
+    }
+
+    /// The position of the item on the canvas. The positions of items in groups are relative to the group,
+    /// so the transform of the group is needed for them, e.g. from a `SceneItemTransformChanged` event.
+    /// The positions of items that are not in a group are already relative to the canvas.
+    pub fn absolute_position(&self, group_transform: Option<&SceneItemTransform>) -> Position {
+        let group = match group_transform {
+            Some(group) => group,
+            None => return self.position.clone(),
+        };
+        // the group's position refers to the point on the group determined by its alignment,
+        // while the positions of its items are relative to its top left corner
+        let align = group.position.alignment;
+        let offset = |align_start: i32, align_end: i32, size: i32| {
+            if align & align_start != 0 {
+                0.0
+            } else if align & align_end != 0 {
+                f64::from(size)
+            } else {
+                f64::from(size) / 2.0
+            }
+        };
+        let x = (self.position.x - offset(alignment::LEFT, alignment::RIGHT, group.source_width))
+            * group.scale.x;
+        let y = (self.position.y - offset(alignment::TOP, alignment::BOTTOM, group.source_height))
+            * group.scale.y;
+        // rotated clockwise around the group's position
+        let (sin, cos) = group.rotation.to_radians().sin_cos();
+        Position {
+            x: group.position.x + x * cos - y * sin,
+            y: group.position.y + x * sin + y * cos,
+            alignment: self.position.alignment,
+        }
+    }
+}
+
 #[derive(Deserialize, Debug, PartialEq, Eq)]
 pub struct DuplicateSceneItem {
     /// Name of the scene where the new item was created
@@ -207,6 +352,56 @@ pub struct GetSceneList {
     pub scenes: Vec<Scene>,
 }
 
+impl GetSceneList {
+    /// Compares the scenes to a later scene list by name, e.g. for keeping an external UI in sync.
+    /// Changes to the items in the scenes are not considered.
+    pub fn diff(&self, other: &GetSceneList) -> SceneListDiff {
+        let before = self.scene_names().collect::<HashSet<_>>();
+        let after = other.scene_names().collect::<HashSet<_>>();
+        let added = other
+            .scene_names()
+            .filter(|name| !before.contains(name))
+            .map(str::to_string)
+            .collect();
+        let removed = self
+            .scene_names()
+            .filter(|name| !after.contains(name))
+            .map(str::to_string)
+            .collect();
+        // the scenes in both lists are reordered if they're in a different relative order
+        let kept_before = self.scene_names().filter(|name| after.contains(name));
+        let kept_after = other.scene_names().filter(|name| before.contains(name));
+        let reordered = !kept_before.eq(kept_after);
+        SceneListDiff {
+            added,
+            removed,
+            reordered,
+        }
+    }
+
+    fn scene_names(&self) -> impl Iterator<Item = &str> {
+        self.scenes.iter().map(|scene| scene.name.as_str())
+    }
+}
+
+/// Differences between two scene lists, see `GetSceneList::diff`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SceneListDiff {
+    /// Names of the scenes that were added, in the order of the later list.
+    pub added: Vec<String>,
+    /// Names of the scenes that were removed, in the order of the earlier list.
+    pub removed: Vec<String>,
+    /// Whether the scenes that are in both lists are in a different order.
+    pub reordered: bool,
+}
+
+impl SceneListDiff {
+    /// Returns true if the scene lists have the same scenes in the same order.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && !self.reordered
+    }
+}
+
 #[derive(Deserialize, Debug, PartialEq, Eq)]
 pub struct GetSourcesList {
     /// Array of sources
@@ -229,6 +424,13 @@ pub struct GetVolume {
     pub muted: bool,
 }
 
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GetAudioMonitorType {
+    /// The monitor type in use.
+    pub monitor_type: MonitorType,
+}
+
 #[derive(Deserialize, Debug, PartialEq)]
 pub struct GetMute {
     /// Source name.
@@ -246,7 +448,7 @@ pub struct GetSyncOffset {
 }
 
 // TODO: deserialize source_settings
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct GetSourceSettings {
     /// Source name
@@ -383,6 +585,21 @@ pub struct GetSourceFilters {
     pub filters: Vec<Filter>,
 }
 
+impl GetSourceFilters {
+    /// Deserializes the settings of the filter with the given name into `T`.
+    /// Returns `None` if there is no such filter.
+    pub fn typed_filter<T: DeserializeOwned>(
+        &self,
+        name: &str,
+    ) -> Result<Option<T>, serde_json::Error> {
+        self.filters
+            .iter()
+            .find(|filter| filter.name == name)
+            .map(|filter| T::deserialize(&filter.settings))
+            .transpose()
+    }
+}
+
 // TODO: deserialize settings
 #[derive(Deserialize, Debug, PartialEq)]
 pub struct GetSourceFilterInfo {
@@ -403,12 +620,12 @@ pub struct TakeSourceScreenshot {
     /// Source name
     pub source_name: String,
     /// Image Data URI (if embedPictureFormat was specified in the request)
-    pub img: String,
+    pub img: Option<String>,
     /// Absolute path to the saved image file (if saveToFilePath was specified in the request)
-    pub image_file: String,
+    pub image_file: Option<String>,
 }
 
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
 #[serde(rename_all = "kebab-case")]
 pub struct GetStreamingStatus {
     /// Current streaming status.
@@ -419,6 +636,8 @@ pub struct GetStreamingStatus {
     pub stream_timecode: Option<String>,
     /// Time elapsed since recording started (only present if currently recording).
     pub rec_timecode: Option<String>,
+    /// Current recording paused status. Requires obs-websocket v4.9.0 or newer.
+    pub recording_paused: Option<bool>,
     // ignore field preview-only: always false
 }
 
@@ -426,6 +645,7 @@ pub struct GetStreamingStatus {
 #[serde(rename_all = "kebab-case")]
 pub struct GetStreamSettings {
     /// The type of streaming service configuration.
+    #[serde(rename = "type")]
     pub stream_type: StreamType,
     /// Stream settings object.
     pub settings: StreamSettings,
@@ -458,6 +678,9 @@ pub struct GetTransitionList {
 pub struct GetCurrentTransition {
     /// Name of the selected transition.
     pub name: String,
+    /// Type of the selected transition, if provided by the server.
+    #[serde(rename = "type", default)]
+    pub transition_type: Option<TransitionKind>,
     /// Transition duration (in milliseconds) if supported by the transition.
     pub duration: Option<i32>,
 }
@@ -468,9 +691,61 @@ pub struct GetTransitionDuration {
     pub duration: i32,
 }
 
+impl GetTransitionDuration {
+    /// Duration of the current transition. Negative durations are treated as zero.
+    pub fn as_duration(&self) -> Duration {
+        Duration::from_millis(u64::try_from(self.duration).unwrap_or(0))
+    }
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+pub struct ExecuteBatch {
+    /// Results of the requests in the batch, in order. Failed requests are included.
+    pub results: Vec<Value>,
+}
+
+impl ExecuteBatch {
+    /// Converts the results of the batch into the results of the individual requests.
+    /// Requests that failed are converted into errors like individual requests.
+    pub fn into_results(self) -> Vec<Result<Value, ObsError>> {
+        self.results
+            .into_iter()
+            .map(|result| match ResponseData::deserialize(result)? {
+                ResponseData::Ok(value) => Ok(value),
+                ResponseData::Error { error } => Err(ObsError::from_obs_message(error)),
+            })
+            .collect()
+    }
+
+    /// Converts a result from `into_results` into the response of the corresponding request,
+    /// e.g. `ExecuteBatch::typed::<GetVersion>(result)`.
+    pub fn typed<T: Request>(result: Result<Value, ObsError>) -> Result<T::Response, ObsError> {
+        let value = result?;
+        let message_id = value["message-id"].as_str().unwrap_or_default().to_string();
+        deserialize_response::<T>(message_id, &value)
+    }
+}
+
 // #### non-response typedefs ####
 
-#[derive(Deserialize, Debug, PartialEq, Eq)]
+/// The playing state of a media source.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+#[non_exhaustive]
+pub enum MediaState {
+    None,
+    Playing,
+    Opening,
+    Buffering,
+    Paused,
+    Stopped,
+    Ended,
+    Error,
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
 #[non_exhaustive]
 pub enum ScaleType {
     #[serde(rename = "VIDEO_SCALE_DEFAULT")]
@@ -487,7 +762,7 @@ pub enum ScaleType {
     Unknown,
 }
 
-#[derive(Deserialize, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
 #[non_exhaustive]
 pub enum VideoFormat {
     #[serde(rename = "VIDEO_FORMAT_NONE")]
@@ -528,7 +803,7 @@ pub enum VideoFormat {
     Unknown,
 }
 
-#[derive(Deserialize, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
 #[non_exhaustive]
 pub enum ColorSpace {
     #[serde(rename = "VIDEO_CS_DEFAULT")]
@@ -543,7 +818,7 @@ pub enum ColorSpace {
     Unknown,
 }
 
-#[derive(Deserialize, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
 #[non_exhaustive]
 pub enum ColorRange {
     #[serde(rename = "VIDEO_RANGE_DEFAULT")]
@@ -580,9 +855,8 @@ pub struct Output {
     /// Output name
     pub name: String,
     /// Output type/kind
-    // todo: enum
     #[serde(rename = "type")]
-    pub output_type: String,
+    pub output_type: OutputKind,
     /// Video output width
     pub width: i32,
     /// Video output height
@@ -605,6 +879,46 @@ pub struct Output {
     pub total_bytes: i32,
 }
 
+impl Output {
+    /// Ratio of dropped frames to the total number of frames. 0 if no frames have been sent.
+    pub fn dropped_frame_ratio(&self) -> f64 {
+        if self.total_frames > 0 {
+            f64::from(self.dropped_frames) / f64::from(self.total_frames)
+        } else {
+            0.0
+        }
+    }
+
+    /// Congestion level of the output.
+    pub fn congestion_level(&self) -> CongestionLevel {
+        CongestionLevel::from_congestion(self.congestion)
+    }
+}
+
+/// Output congestion level, e.g. for a stream health indicator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CongestionLevel {
+    /// Congestion below 0.1
+    Low,
+    /// Congestion from 0.1 to below 0.5
+    Medium,
+    /// Congestion of 0.5 or above
+    High,
+}
+
+impl CongestionLevel {
+    /// Converts an output's congestion (0.0 to 1.0) into a congestion level.
+    pub fn from_congestion(congestion: f64) -> Self {
+        if congestion < 0.1 {
+            Self::Low
+        } else if congestion < 0.5 {
+            Self::Medium
+        } else {
+            Self::High
+        }
+    }
+}
+
 #[derive(Deserialize, Debug, PartialEq, Eq)]
 #[serde(rename_all = "kebab-case")]
 pub struct Profile {
@@ -645,6 +959,28 @@ pub struct Source {
     pub source_type: SourceType,
 }
 
+impl Source {
+    /// Whether or not this source is a scene.
+    pub fn is_scene(&self) -> bool {
+        self.source_type == SourceType::Scene
+    }
+
+    /// Whether or not this source is an input.
+    pub fn is_input(&self) -> bool {
+        self.source_type == SourceType::Input
+    }
+
+    /// Whether or not this source is a transition.
+    pub fn is_transition(&self) -> bool {
+        self.source_type == SourceType::Transition
+    }
+
+    /// Whether or not this source is a filter.
+    pub fn is_filter(&self) -> bool {
+        self.source_type == SourceType::Filter
+    }
+}
+
 pub type SourceType = SceneItemType;
 
 #[derive(Deserialize, Debug, PartialEq, Eq)]
@@ -765,6 +1101,17 @@ pub enum StreamType {
     Unknown,
 }
 
+impl StreamType {
+    /// The name of the type used by OBS. `None` if unknown.
+    pub fn as_str(&self) -> Option<&'static str> {
+        match self {
+            Self::Custom => Some("rtmp_custom"),
+            Self::Common => Some("rtmp_common"),
+            Self::Unknown => None,
+        }
+    }
+}
+
 #[derive(Deserialize, Debug, PartialEq, Eq)]
 #[serde(rename_all = "kebab-case")]
 pub struct StreamSettings {
@@ -775,21 +1122,59 @@ pub struct StreamSettings {
     /// Indicates whether authentication should be used when connecting to the streaming server.
     pub use_auth: bool,
     /// The username to use when accessing the streaming server. Only present if use-auth is true.
-    pub username: String,
+    #[serde(default)]
+    pub username: Option<String>,
     /// The password to use when accessing the streaming server. Only present if use-auth is true.
-    pub password: String,
+    #[serde(default)]
+    pub password: Option<String>,
+}
+
+impl StreamSettings {
+    /// Returns a copy with the key and password masked except for their last 4 characters, e.g. for displaying in a UI.
+    /// Values of 4 characters or less are masked entirely.
+    pub fn redacted(&self) -> StreamSettings {
+        StreamSettings {
+            server: self.server.clone(),
+            key: mask(&self.key),
+            use_auth: self.use_auth,
+            username: self.username.clone(),
+            password: self.password.as_deref().map(mask),
+        }
+    }
+}
+
+// replaces all but the last 4 characters with asterisks
+fn mask(secret: &str) -> String {
+    const VISIBLE_CHARS: usize = 4;
+    let len = secret.chars().count();
+    if len <= VISIBLE_CHARS {
+        return "*".repeat(len);
+    }
+    let mut masked = "*".repeat(len - VISIBLE_CHARS);
+    masked.extend(secret.chars().skip(len - VISIBLE_CHARS));
+    masked
 }
 
 #[derive(Deserialize, Debug, PartialEq, Eq)]
 pub struct Transition {
     /// Name of the transition.
     pub name: String,
+    /// Type of the transition, if provided by the server.
+    #[serde(rename = "type", default)]
+    pub transition_type: Option<TransitionKind>,
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
 
+    #[test]
+    fn truncate() {
+        assert_eq!(super::truncate("abc".to_string(), 3), "abc");
+        assert_eq!(super::truncate("abcd".to_string(), 3), "abc...");
+        assert_eq!(super::truncate("äöüß".to_string(), 2), "äö...");
+    }
+
     #[test]
     fn convert_successful_response() {
         let successful = serde_json::json!(
@@ -854,4 +1239,295 @@ mod test {
         let f: Font = serde_json::from_value(font).unwrap();
         assert_eq!(f.flags, FontFlags::Strikeout);
     }
+
+    #[test]
+    fn stream_settings_without_auth() {
+        let settings: StreamSettings = serde_json::from_value(serde_json::json!({
+            "server": "rtmp://live.twitch.tv/app",
+            "key": "live_key",
+            "use-auth": false,
+        }))
+        .unwrap();
+        assert!(!settings.use_auth);
+        assert_eq!(settings.username, None);
+        assert_eq!(settings.password, None);
+    }
+
+    #[test]
+    fn typed_filter() {
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct GainSettings {
+            db: f64,
+        }
+
+        let filters: GetSourceFilters = serde_json::from_value(serde_json::json!({
+            "filters": [
+                {
+                    "enabled": true,
+                    "type": "noise_suppress_filter",
+                    "name": "Noise Suppression",
+                    "settings": { "suppress_level": -30 },
+                },
+                {
+                    "enabled": true,
+                    "type": "gain_filter",
+                    "name": "Gain",
+                    "settings": { "db": 6.5 },
+                },
+            ]
+        }))
+        .unwrap();
+        let gain = filters.typed_filter::<GainSettings>("Gain").unwrap();
+        assert_eq!(gain, Some(GainSettings { db: 6.5 }));
+        let missing = filters.typed_filter::<GainSettings>("Missing").unwrap();
+        assert_eq!(missing, None);
+        assert!(filters
+            .typed_filter::<GainSettings>("Noise Suppression")
+            .is_err());
+    }
+
+    fn output(congestion: f64, total_frames: i32, dropped_frames: i32) -> Output {
+        Output {
+            name: "simple_stream".to_string(),
+            output_type: OutputKind::RtmpOutput,
+            width: 1920,
+            height: 1080,
+            flags: Flags {
+                raw_value: 0,
+                audio: true,
+                video: true,
+                encoded: true,
+                multi_track: false,
+                service: true,
+            },
+            settings: serde_json::json!({}),
+            active: true,
+            reconnecting: false,
+            congestion,
+            total_frames,
+            dropped_frames,
+            total_bytes: 0,
+        }
+    }
+
+    #[test]
+    fn dropped_frame_ratio() {
+        assert_eq!(output(0.0, 0, 0).dropped_frame_ratio(), 0.0);
+        assert_eq!(output(0.0, 200, 0).dropped_frame_ratio(), 0.0);
+        assert_eq!(output(0.0, 200, 50).dropped_frame_ratio(), 0.25);
+    }
+
+    #[test]
+    fn congestion_level() {
+        assert_eq!(output(0.0, 0, 0).congestion_level(), CongestionLevel::Low);
+        assert_eq!(output(0.09, 0, 0).congestion_level(), CongestionLevel::Low);
+        assert_eq!(
+            output(0.1, 0, 0).congestion_level(),
+            CongestionLevel::Medium
+        );
+        assert_eq!(
+            output(0.49, 0, 0).congestion_level(),
+            CongestionLevel::Medium
+        );
+        assert_eq!(output(0.5, 0, 0).congestion_level(), CongestionLevel::High);
+        assert_eq!(output(1.0, 0, 0).congestion_level(), CongestionLevel::High);
+    }
+
+    #[test]
+    fn source_types() {
+        let sources: GetSourcesList = serde_json::from_value(serde_json::json!({
+            "sources": [
+                { "name": "Scene", "typeId": "scene", "type": "scene" },
+                { "name": "Mic", "typeId": "pulse_input_capture", "type": "input" },
+                { "name": "Fade", "typeId": "fade_transition", "type": "transition" },
+                { "name": "Gain", "typeId": "gain_filter", "type": "filter" },
+                { "name": "Other", "typeId": "other", "type": "other" },
+            ]
+        }))
+        .unwrap();
+        let names = |predicate: fn(&Source) -> bool| {
+            sources
+                .sources
+                .iter()
+                .filter(|source| predicate(source))
+                .map(|source| source.name.as_str())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(Source::is_scene), vec!["Scene"]);
+        assert_eq!(names(Source::is_input), vec!["Mic"]);
+        assert_eq!(names(Source::is_transition), vec!["Fade"]);
+        assert_eq!(names(Source::is_filter), vec!["Gain"]);
+    }
+
+    fn scene_item_properties(
+        scale: (f64, f64),
+        source_size: (i32, i32),
+        size: (f64, f64),
+    ) -> GetSceneItemProperties {
+        serde_json::from_value(serde_json::json!({
+            "name": "item",
+            "position": { "x": 0.0, "y": 0.0, "alignment": 5 },
+            "rotation": 0.0,
+            "scale": { "x": scale.0, "y": scale.1 },
+            "crop": { "top": 0, "right": 0, "bottom": 0, "left": 0 },
+            "visible": true,
+            "locked": false,
+            "bounds": { "type": "OBS_BOUNDS_NONE", "alignment": 0, "x": 0.0, "y": 0.0 },
+            "sourceWidth": source_size.0,
+            "sourceHeight": source_size.1,
+            "width": size.0,
+            "height": size.1,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn effective_scale() {
+        let properties = scene_item_properties((0.5, 2.0), (1920, 1080), (960.0, 2160.0));
+        assert_eq!(properties.effective_scale_x(), 0.5);
+        assert_eq!(properties.effective_scale_y(), 2.0);
+        assert!(!properties.is_flipped());
+
+        let properties = scene_item_properties((-1.0, 1.0), (100, 100), (-100.0, 100.0));
+        assert_eq!(properties.effective_scale_x(), -1.0);
+        assert_eq!(properties.effective_scale_y(), 1.0);
+        assert!(properties.is_flipped());
+
+        let properties = scene_item_properties((1.5, -0.5), (0, 0), (0.0, 0.0));
+        assert_eq!(properties.effective_scale_x(), 1.5);
+        assert_eq!(properties.effective_scale_y(), -0.5);
+        assert!(properties.is_flipped());
+    }
+
+    #[test]
+    fn transition_duration() {
+        let duration = GetTransitionDuration { duration: 500 };
+        assert_eq!(duration.as_duration(), Duration::from_millis(500));
+        let duration = GetTransitionDuration { duration: -1 };
+        assert_eq!(duration.as_duration(), Duration::from_millis(0));
+    }
+
+    fn group_transform(position: Position, rotation: f64, scale: f64) -> SceneItemTransform {
+        SceneItemTransform {
+            position,
+            rotation,
+            scale: Scale { x: scale, y: scale },
+            crop: Crop {
+                top: 0,
+                right: 0,
+                bottom: 0,
+                left: 0,
+            },
+            visible: true,
+            locked: false,
+            bounds: Bounds {
+                bounds_type: BoundsType::None,
+                alignment: 0,
+                x: 0.0,
+                y: 0.0,
+            },
+            source_width: 200,
+            source_height: 100,
+            width: 200.0 * scale,
+            height: 100.0 * scale,
+            parent_group_name: None,
+            group_children: None,
+        }
+    }
+
+    fn assert_position(actual: Position, x: f64, y: f64) {
+        assert!((actual.x - x).abs() < 1e-9, "x: {} != {}", actual.x, x);
+        assert!((actual.y - y).abs() < 1e-9, "y: {} != {}", actual.y, y);
+    }
+
+    #[test]
+    fn absolute_position() {
+        let mut properties = scene_item_properties((1.0, 1.0), (100, 100), (100.0, 100.0));
+        properties.position.x = 10.0;
+        properties.position.y = 0.0;
+
+        // not in a group
+        assert_position(properties.absolute_position(None), 10.0, 0.0);
+
+        // group aligned to its top left corner, rotated 90 degrees clockwise and scaled by 2
+        let top_left = Position {
+            x: 100.0,
+            y: 100.0,
+            alignment: alignment::TOP | alignment::LEFT,
+        };
+        let group = group_transform(top_left, 90.0, 2.0);
+        assert_position(properties.absolute_position(Some(&group)), 100.0, 120.0);
+
+        // group aligned to its center, not rotated
+        let center = Position {
+            x: 500.0,
+            y: 500.0,
+            alignment: alignment::CENTER,
+        };
+        let group = group_transform(center.clone(), 0.0, 1.0);
+        assert_position(properties.absolute_position(Some(&group)), 410.0, 450.0);
+
+        // group aligned to its center, rotated 180 degrees
+        let group = group_transform(center, 180.0, 1.0);
+        let position = properties.absolute_position(Some(&group));
+        assert_position(position.clone(), 590.0, 550.0);
+        assert_eq!(position.alignment, 5);
+    }
+
+    fn scene_list(names: &[&str]) -> GetSceneList {
+        GetSceneList {
+            current_scene: names[0].to_string(),
+            scenes: names
+                .iter()
+                .map(|name| Scene {
+                    name: name.to_string(),
+                    sources: vec![],
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn scene_list_diff() {
+        let before = scene_list(&["Intro", "Game", "Outro"]);
+        let after = scene_list(&["Intro", "Game", "Break"]);
+        let diff = before.diff(&after);
+        assert_eq!(diff.added, vec!["Break"]);
+        assert_eq!(diff.removed, vec!["Outro"]);
+        assert!(!diff.reordered);
+
+        let reordered = scene_list(&["Game", "Intro", "Outro"]);
+        let diff = before.diff(&reordered);
+        assert!(diff.added.is_empty());
+        assert!(diff.removed.is_empty());
+        assert!(diff.reordered);
+
+        assert!(before.diff(&before).is_empty());
+    }
+
+    #[test]
+    fn stream_settings_redacted() {
+        let settings = StreamSettings {
+            server: "rtmp://live.example.com/app".to_string(),
+            key: "live_123456789".to_string(),
+            use_auth: true,
+            username: Some("user".to_string()),
+            password: Some("pass".to_string()),
+        };
+        let redacted = settings.redacted();
+        assert_eq!(redacted.key, "**********6789");
+        assert_eq!(redacted.password.as_deref(), Some("****"));
+        assert_eq!(redacted.server, settings.server);
+        assert_eq!(redacted.username, settings.username);
+    }
+
+    #[test]
+    fn media_state() {
+        let playing: GetMediaState =
+            serde_json::from_value(serde_json::json!({ "mediaState": "playing" })).unwrap();
+        assert_eq!(playing.media_state, MediaState::Playing);
+        let unknown: GetMediaState =
+            serde_json::from_value(serde_json::json!({ "mediaState": "rewinding" })).unwrap();
+        assert_eq!(unknown.media_state, MediaState::Unknown);
+    }
 }
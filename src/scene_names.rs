@@ -0,0 +1,45 @@
+//! Rewriting of scene names, see `ObsConfig::scene_name_mapper`.
+
+use serde_json::{Map, Value};
+
+// top-level fields that contain a scene name in requests, responses and events
+const SCENE_NAME_FIELDS: &[&str] = &[
+    "scene-name",
+    "sceneName",
+    "scene",
+    "current-scene",
+    "from-scene",
+    "to-scene",
+    "fromScene",
+    "toScene",
+];
+// responses whose name field is the name of a scene
+const SCENE_RESPONSES: &[&str] = &["GetCurrentScene", "GetPreviewScene"];
+
+// rewrites the scene names in the JSON of a request, response or event with the given function
+// request_type is only needed for responses, where the meaning of a field can depend on the request
+pub(crate) fn map_scene_names<F>(request_type: Option<&str>, object: &mut Map<String, Value>, f: F)
+where
+    F: Fn(&str) -> String,
+{
+    let map_field = |object: &mut Map<String, Value>, field: &str| {
+        if let Some(Value::String(name)) = object.get_mut(field) {
+            *name = f(name);
+        }
+    };
+
+    for field in SCENE_NAME_FIELDS {
+        map_field(object, field);
+    }
+    if matches!(request_type, Some(request_type) if SCENE_RESPONSES.contains(&request_type)) {
+        map_field(object, "name");
+    }
+    // scene lists, e.g. in GetSceneList
+    if let Some(Value::Array(scenes)) = object.get_mut("scenes") {
+        for scene in scenes {
+            if let Value::Object(scene) = scene {
+                map_field(scene, "name");
+            }
+        }
+    }
+}
@@ -0,0 +1,95 @@
+//! Tracks the streaming and recording status of OBS based on events.
+
+use crate::{events::EventType, responses};
+
+use std::sync::Mutex;
+
+// streaming and recording status, updated by the handler thread as events arrive
+#[derive(Debug, Default)]
+pub(crate) struct StatusTracker {
+    // None if tracking is not enabled
+    status: Mutex<Option<TrackedStatus>>,
+}
+
+// each field is None until it's known from an event or the initial status
+#[derive(Debug, Default)]
+struct TrackedStatus {
+    streaming: Option<bool>,
+    recording: Option<bool>,
+    recording_paused: Option<bool>,
+    replay_active: Option<bool>,
+}
+
+impl StatusTracker {
+    // starts tracking, should be called before requesting the initial status
+    // so that events received while waiting for it are not missed
+    pub(crate) fn enable(&self) {
+        *self.status.lock().unwrap() = Some(TrackedStatus::default());
+    }
+
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.status.lock().unwrap().is_some()
+    }
+
+    // fills in the parts of the status that haven't been updated by events
+    pub(crate) fn seed(&self, initial: &responses::GetStreamingStatus) {
+        if let Some(status) = self.status.lock().unwrap().as_mut() {
+            status.streaming.get_or_insert(initial.streaming);
+            status.recording.get_or_insert(initial.recording);
+            status
+                .recording_paused
+                .get_or_insert(initial.recording_paused.unwrap_or(false));
+        }
+    }
+
+    // updates the status according to the event if tracking is enabled
+    pub(crate) fn handle_event(&self, event: &EventType) {
+        let mut status = self.status.lock().unwrap();
+        let status = match status.as_mut() {
+            Some(status) => status,
+            None => return,
+        };
+        match event {
+            EventType::StreamStarting | EventType::StreamStarted => status.streaming = Some(true),
+            EventType::StreamStopping | EventType::StreamStopped => status.streaming = Some(false),
+            EventType::RecordingStarting | EventType::RecordingStarted => {
+                status.recording = Some(true);
+                status.recording_paused = Some(false);
+            }
+            EventType::RecordingStopping | EventType::RecordingStopped => {
+                status.recording = Some(false);
+                status.recording_paused = Some(false);
+            }
+            EventType::RecordingPaused => status.recording_paused = Some(true),
+            EventType::RecordingResumed => status.recording_paused = Some(false),
+            EventType::ReplayStarted => status.replay_active = Some(true),
+            EventType::ReplayStopped => status.replay_active = Some(false),
+            _ => {}
+        }
+    }
+
+    pub(crate) fn is_streaming(&self) -> bool {
+        self.get(|status| status.streaming)
+    }
+
+    pub(crate) fn is_recording(&self) -> bool {
+        self.get(|status| status.recording)
+    }
+
+    pub(crate) fn is_recording_paused(&self) -> bool {
+        self.get(|status| status.recording_paused)
+    }
+
+    pub(crate) fn is_replay_active(&self) -> bool {
+        self.get(|status| status.replay_active)
+    }
+
+    fn get(&self, f: impl Fn(&TrackedStatus) -> Option<bool>) -> bool {
+        self.status
+            .lock()
+            .unwrap()
+            .as_ref()
+            .and_then(f)
+            .unwrap_or(false)
+    }
+}
@@ -0,0 +1,112 @@
+//! Utilities for testing code that handles events.
+//!
+//! Events can be recorded from a real session with `EventRecorder` and replayed later with `replay`.
+
+use crate::{
+    error::ObsError,
+    events::{Event, RawEvent},
+};
+
+use futures::stream::{self, Stream};
+use std::io::{BufRead, Write};
+
+/// Records events as newline-delimited JSON.
+#[derive(Debug)]
+pub struct EventRecorder<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> EventRecorder<W> {
+    /// Creates a recorder that writes the events to the given writer.
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    /// Writes the event as a single line of JSON.
+    pub fn record(&mut self, event: &Event) -> Result<(), ObsError> {
+        serde_json::to_writer(&mut self.writer, event)?;
+        self.writer.write_all(b"\n")?;
+        Ok(())
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// Replays events recorded with `EventRecorder`.
+/// Events that don't match the known events are replayed as `EventType::Unknown`.
+/// Lines that can't be read or parsed are logged and skipped.
+/// Note that the reader is read synchronously as the stream is polled.
+pub fn replay<R: BufRead>(reader: R) -> impl Stream<Item = Event> {
+    let events = reader.lines().filter_map(|line| {
+        let line = line
+            .map_err(|e| log::error!("Failed to read recorded event: {}", e))
+            .ok()?;
+        if line.trim().is_empty() {
+            return None;
+        }
+        // parsed like the events from the connection so that unknown events are replayed as such
+        serde_json::from_str::<RawEvent>(&line)
+            .map(RawEvent::parse)
+            .map_err(|e| log::error!("Failed to parse recorded event \"{}\": {}", line, e))
+            .ok()
+    });
+    stream::iter(events)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::events::EventType;
+    use futures::stream::StreamExt;
+    use serde_json::{json, Value};
+    use std::io::Cursor;
+
+    #[test]
+    fn record_and_replay() {
+        let events = vec![
+            Event {
+                stream_timecode: None,
+                rec_timecode: Some("00:00:01.000".to_string()),
+                update_type: EventType::SwitchTransition {
+                    transition_name: "Fade".to_string(),
+                },
+            },
+            Event {
+                stream_timecode: None,
+                rec_timecode: None,
+                update_type: EventType::RecordingStopped,
+            },
+            Event {
+                stream_timecode: Some("00:00:02.000".to_string()),
+                rec_timecode: None,
+                update_type: EventType::Unknown {
+                    update_type: "SomeFutureEvent".to_string(),
+                    data: json!({ "some-field": 1 }),
+                },
+            },
+        ];
+
+        let mut recorder = EventRecorder::new(vec![]);
+        for event in &events {
+            recorder.record(event).unwrap();
+        }
+        let recorded = recorder.into_inner();
+        assert_eq!(recorded.iter().filter(|b| **b == b'\n').count(), 3);
+        let unknown: Value =
+            serde_json::from_slice(recorded.split(|b| *b == b'\n').nth(2).unwrap()).unwrap();
+        assert_eq!(
+            unknown,
+            json!({
+                "update-type": "SomeFutureEvent",
+                "stream-timecode": "00:00:02.000",
+                "some-field": 1,
+            })
+        );
+
+        let replayed = smol::block_on(replay(Cursor::new(recorded)).collect::<Vec<_>>());
+        assert_eq!(replayed, events);
+    }
+}
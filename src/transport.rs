@@ -0,0 +1,20 @@
+//! The connection that the WebSocket protocol is run over, see `Obs::connect_with_transport`.
+
+use futures::io::{AsyncRead, AsyncWrite};
+use piper::Arc;
+use smol::Async;
+use std::net::TcpStream;
+
+/// A bidirectional byte stream to OBS, such as a TCP stream. Implement this to connect to OBS
+/// over something other than smol's TCP streams, e.g. an in-memory stream in tests.
+pub trait Transport: AsyncRead + AsyncWrite + Unpin + Send + Sync + 'static {
+    /// Returns a new handle to the same connection.
+    /// Reading from or writing to any of the handles must read from or write to the same connection.
+    fn clone_transport(&self) -> Box<dyn Transport>;
+}
+
+impl Transport for Arc<Async<TcpStream>> {
+    fn clone_transport(&self) -> Box<dyn Transport> {
+        Box::new(self.clone())
+    }
+}
@@ -0,0 +1,38 @@
+//! Debounced volume changes, see `VolumeController`.
+
+use std::sync::{Arc, Mutex};
+
+/// Sets the volume of a source, sending at most one SetVolume request per interval with the latest volume.
+/// Useful for UI sliders that generate a flood of volume changes while dragging. Created with `Obs::volume_controller`.
+/// Dropping the controller stops it after the last volume has been sent.
+#[derive(Debug)]
+pub struct VolumeController {
+    state: Arc<Mutex<VolumeState>>,
+}
+
+// shared between the controller and the thread that sends the requests
+#[derive(Debug, Default)]
+pub(crate) struct VolumeState {
+    // the latest volume that hasn't been sent yet
+    pub(crate) pending: Option<f64>,
+    // set when the controller is dropped
+    pub(crate) closed: bool,
+}
+
+impl VolumeController {
+    pub(crate) fn new(state: Arc<Mutex<VolumeState>>) -> Self {
+        Self { state }
+    }
+
+    /// Sets the volume. Must be between 0.0 and 1.0.
+    /// The volume is sent at the end of the current interval unless it's replaced by a newer volume before then.
+    pub fn set(&self, volume: f64) {
+        self.state.lock().unwrap().pending = Some(volume);
+    }
+}
+
+impl Drop for VolumeController {
+    fn drop(&mut self) {
+        self.state.lock().unwrap().closed = true;
+    }
+}